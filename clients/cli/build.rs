@@ -14,6 +14,10 @@ fn main() -> Result<(), Box<dyn Error>> {
         .to_string();
     println!("cargo:rustc-env=BUILD_TIMESTAMP={}", build_timestamp);
 
+    // gRPC service stubs are generated into OUT_DIR, not checked in
+    #[cfg(feature = "grpc")]
+    compile_grpc_service()?;
+
     // Skip proto compilation unless build_proto feature is enabled.
     if !cfg!(feature = "build_proto") {
         println!(
@@ -104,3 +108,48 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+/// Generates the gRPC client and server for the proposed orchestrator service with tonic-build.
+/// Messages imported from orchestrator.proto are declared external, so the stubs refer to the
+/// checked-in `nexus_orchestrator` types instead of regenerating them. Only the messages
+/// orchestrator_service.proto defines itself are generated.
+#[cfg(feature = "grpc")]
+fn compile_grpc_service() -> Result<(), Box<dyn Error>> {
+    println!("cargo:rerun-if-changed=../../proto/orchestrator_service.proto");
+    println!("cargo:rerun-if-changed=../../proto/orchestrator.proto");
+
+    // Both files share the `nexus.orchestrator` package, so the shared types are declared
+    // external one by one rather than by package
+    let shared = fs::read_to_string("../../proto/orchestrator.proto")?;
+    let mut builder = tonic_build::configure();
+    for name in top_level_types(&shared) {
+        builder = builder.extern_path(
+            format!(".nexus.orchestrator.{}", name),
+            format!("crate::nexus_orchestrator::{}", name),
+        );
+    }
+
+    builder
+        .build_client(true)
+        // The server is used by tests to run a local orchestrator
+        .build_server(true)
+        .protoc_arg("--experimental_allow_proto3_optional")
+        .compile_protos(
+            &["../../proto/orchestrator_service.proto"],
+            &["../../proto"],
+        )?;
+    Ok(())
+}
+
+/// Names of the messages and enums declared at the top level of a .proto file.
+#[cfg(feature = "grpc")]
+fn top_level_types(proto: &str) -> Vec<&str> {
+    proto
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("message ")
+                .or_else(|| line.strip_prefix("enum "))
+        })
+        .filter_map(|rest| rest.split_whitespace().next())
+        .collect()
+}
@@ -1,4 +1,5 @@
 use crate::environment::Environment;
+use crate::network::http;
 use crate::prover::input::InputParser;
 use crate::system::{estimate_peak_gflops, measure_gflops, num_cores};
 use crate::task::Task;
@@ -41,14 +42,16 @@ const FIB_INPUT_INITIAL_BYTES: usize = (u32::BITS / 8 * 3) as usize;
 pub fn analytics_id(environment: &Environment) -> String {
     match environment {
         Environment::Production => PRODUCTION_MEASUREMENT_ID.to_string(),
-        Environment::Custom { .. } => String::new(), // Disable analytics for custom environments
+        // Disable analytics for custom environments
+        Environment::Custom { .. } | Environment::Failover { .. } => String::new(),
     }
 }
 
 pub fn analytics_api_key(environment: &Environment) -> String {
     match environment {
         Environment::Production => PRODUCTION_API_SECRET.to_string(),
-        Environment::Custom { .. } => String::new(), // Disable analytics for custom environments
+        // Disable analytics for custom environments
+        Environment::Custom { .. } | Environment::Failover { .. } => String::new(),
     }
 }
 
@@ -124,7 +127,7 @@ pub async fn track(
         }).collect::<Vec<_>>(),
     });
 
-    let client = reqwest::Client::new();
+    let client = http::client_builder().build()?;
     let url = format!(
         "https://www.google-analytics.com/mp/collect?measurement_id={}&api_secret={}",
         analytics_id, analytics_api_key
@@ -195,7 +198,9 @@ pub async fn report_proving_if_needed() {
     }
 
     // Fire-and-forget POST; ignore errors
-    let client = reqwest::Client::new();
+    let Ok(client) = http::client_builder().build() else {
+        return;
+    };
     let body = json!({
         "data": { "address": wallet_address }
     });
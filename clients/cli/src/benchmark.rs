@@ -0,0 +1,271 @@
+//! Hardware benchmark results
+//!
+//! `nexus-network benchmark` proves one input for each [`TaskDifficulty`] bucket and saves a
+//! [`BenchmarkReport`] next to the config file. `start` reads it back so that the task fetcher
+//! skips part of the climb up from `SmallMedium`, and proves on the recommended number of
+//! threads.
+//!
+//! The recommendation is approximate: the input sizes are estimates rather than taken from
+//! assigned tasks, and a task holds several inputs while the benchmark times one. `start`
+//! therefore begins no higher than [`MAX_START_DIFFICULTY`] and leaves the rest to promotion.
+
+use crate::consts::cli_consts::{PROJECTED_MEMORY_REQUIREMENT, difficulty};
+use crate::nexus_orchestrator::TaskDifficulty;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Guest program the benchmark proves
+pub const BENCHMARK_PROGRAM: &str = "fib_input_initial";
+
+/// Highest difficulty a benchmark lets `start` begin at, one step above the default
+pub const MAX_START_DIFFICULTY: TaskDifficulty = TaskDifficulty::Medium;
+
+/// Fibonacci iterations of the input proven for each difficulty. These are estimates that grow
+/// with the difficulty, not sizes measured from tasks the orchestrator assigned.
+pub const REPRESENTATIVE_INPUTS: [(TaskDifficulty, u32); 9] = [
+    (TaskDifficulty::Small, 1_000),
+    (TaskDifficulty::SmallMedium, 5_000),
+    (TaskDifficulty::Medium, 10_000),
+    (TaskDifficulty::Large, 25_000),
+    (TaskDifficulty::ExtraLarge, 50_000),
+    (TaskDifficulty::ExtraLarge2, 100_000),
+    (TaskDifficulty::ExtraLarge3, 200_000),
+    (TaskDifficulty::ExtraLarge4, 400_000),
+    (TaskDifficulty::ExtraLarge5, 800_000),
+];
+
+/// Get the benchmark results file that sits next to the config file, typically
+/// ~/.nexus/benchmark.json.
+pub fn get_benchmark_path(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .map(|parent| parent.join("benchmark.json"))
+        .unwrap_or_else(|| PathBuf::from("benchmark.json"))
+}
+
+/// Public input bytes of a `fib_input_initial` run of `iterations`.
+pub fn fib_input(iterations: u32) -> Vec<u8> {
+    [iterations, 1, 1]
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+/// Outcome of proving one difficulty's input on every thread at once
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BucketResult {
+    #[serde(with = "difficulty_name")]
+    pub difficulty: TaskDifficulty,
+    pub fib_iterations: u32,
+    /// Time until every thread's proof was done (milliseconds)
+    pub wall_time_ms: u64,
+    /// Highest resident memory of a single prover subprocess (bytes)
+    pub peak_rss_bytes: u64,
+    /// Why the proofs failed, if they did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BucketResult {
+    pub fn wall_time(&self) -> Duration {
+        Duration::from_millis(self.wall_time_ms)
+    }
+
+    /// Whether tasks of this difficulty would roughly complete in time to keep being promoted.
+    /// Only one input is timed, so real tasks with several inputs take longer.
+    pub fn within_threshold(&self) -> bool {
+        self.error.is_none() && self.wall_time() < difficulty::promotion_threshold()
+    }
+}
+
+/// Results of a benchmark run and the settings they recommend
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BenchmarkReport {
+    /// Proofs run concurrently for each difficulty
+    pub threads: usize,
+    pub buckets: Vec<BucketResult>,
+    /// Highest difficulty whose proofs completed within the promotion threshold. Approximate;
+    /// see [`BenchmarkReport::start_difficulty`] for where `start` begins.
+    #[serde(with = "difficulty_name")]
+    pub recommended_difficulty: TaskDifficulty,
+    /// Threads that fit in memory at the recommended difficulty, capped by the CPU cores
+    pub recommended_threads: usize,
+}
+
+impl BenchmarkReport {
+    /// Derives the recommendations from the results. `max_threads_by_memory` gives the threads
+    /// that fit in memory at a given memory use per thread, and `max_workers` the threads the
+    /// CPU cores allow. `None` if no difficulty completed within the promotion threshold, since
+    /// there is nothing to recommend.
+    pub fn new(
+        threads: usize,
+        buckets: Vec<BucketResult>,
+        max_threads_by_memory: impl Fn(u64) -> usize,
+        max_workers: usize,
+    ) -> Option<Self> {
+        let recommended = buckets
+            .iter()
+            .filter(|bucket| bucket.within_threshold())
+            .max_by_key(|bucket| bucket.difficulty)?;
+        let recommended_difficulty = recommended.difficulty;
+        let memory_per_thread = Some(recommended.peak_rss_bytes)
+            .filter(|rss| *rss > 0)
+            .unwrap_or(PROJECTED_MEMORY_REQUIREMENT);
+        let recommended_threads = max_threads_by_memory(memory_per_thread).clamp(1, max_workers);
+
+        Some(Self {
+            threads,
+            buckets,
+            recommended_difficulty,
+            recommended_threads,
+        })
+    }
+
+    /// Difficulty for `start` to begin at: the recommendation, capped at
+    /// [`MAX_START_DIFFICULTY`] since it is only approximate.
+    pub fn start_difficulty(&self) -> TaskDifficulty {
+        self.recommended_difficulty.min(MAX_START_DIFFICULTY)
+    }
+
+    /// Loads the results of the last benchmark.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let bytes = fs::read(path)?;
+        serde_json::from_slice(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, json)
+    }
+}
+
+/// Serializes a [`TaskDifficulty`] by its protobuf name, e.g. `SMALL_MEDIUM`
+mod difficulty_name {
+    use crate::nexus_orchestrator::TaskDifficulty;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        difficulty: &TaskDifficulty,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(difficulty.as_str_name())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<TaskDifficulty, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        TaskDifficulty::from_str_name(&name)
+            .ok_or_else(|| D::Error::custom(format!("unknown difficulty '{}'", name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    fn bucket(difficulty: TaskDifficulty, wall_time_secs: u64, peak_rss_gib: u64) -> BucketResult {
+        BucketResult {
+            difficulty,
+            fib_iterations: 0,
+            wall_time_ms: wall_time_secs * 1000,
+            peak_rss_bytes: peak_rss_gib * GIB,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_recommends_largest_difficulty_within_threshold() {
+        let mut failed = bucket(TaskDifficulty::ExtraLarge, 60, 12);
+        failed.error = Some("Out of memory".to_string());
+        let buckets = vec![
+            bucket(TaskDifficulty::Small, 10, 1),
+            bucket(TaskDifficulty::Medium, 120, 2),
+            bucket(
+                TaskDifficulty::Large,
+                difficulty::PROMOTION_THRESHOLD_SECS,
+                3,
+            ),
+            failed,
+        ];
+        // 24 GiB of usable memory and 8 cores
+        let report =
+            BenchmarkReport::new(4, buckets, |per_thread| (24 * GIB / per_thread) as usize, 8)
+                .unwrap();
+
+        assert_eq!(report.recommended_difficulty, TaskDifficulty::Medium);
+        assert_eq!(report.start_difficulty(), TaskDifficulty::Medium);
+        assert_eq!(report.recommended_threads, 8);
+
+        let report = BenchmarkReport::new(
+            4,
+            report.buckets,
+            |per_thread| (6 * GIB / per_thread) as usize,
+            8,
+        )
+        .unwrap();
+        assert_eq!(report.recommended_threads, 3);
+    }
+
+    #[test]
+    fn test_start_difficulty_is_capped() {
+        let report = BenchmarkReport::new(
+            1,
+            vec![
+                bucket(TaskDifficulty::Small, 10, 1),
+                bucket(TaskDifficulty::ExtraLarge3, 60, 4),
+            ],
+            |_| 4,
+            4,
+        )
+        .unwrap();
+        assert_eq!(report.recommended_difficulty, TaskDifficulty::ExtraLarge3);
+        assert_eq!(report.start_difficulty(), MAX_START_DIFFICULTY);
+
+        let report =
+            BenchmarkReport::new(1, vec![bucket(TaskDifficulty::Small, 10, 1)], |_| 4, 4).unwrap();
+        assert_eq!(report.start_difficulty(), TaskDifficulty::Small);
+    }
+
+    #[test]
+    fn test_no_recommendation_when_nothing_completes() {
+        let mut failed = bucket(TaskDifficulty::Small, 5, 0);
+        failed.error = Some("crashed".to_string());
+        let slow = bucket(
+            TaskDifficulty::SmallMedium,
+            difficulty::PROMOTION_THRESHOLD_SECS + 1,
+            1,
+        );
+
+        assert!(BenchmarkReport::new(1, vec![failed, slow], |_| 4, 4).is_none());
+        assert!(BenchmarkReport::new(1, Vec::new(), |_| 4, 4).is_none());
+    }
+
+    #[test]
+    fn test_report_round_trips_with_difficulty_names() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = get_benchmark_path(&tmp.path().join("config.json"));
+        let report = BenchmarkReport::new(
+            2,
+            vec![bucket(TaskDifficulty::SmallMedium, 30, 2)],
+            |_| 4,
+            4,
+        )
+        .unwrap();
+        report.save(&path).unwrap();
+
+        let json = fs::read_to_string(&path).unwrap();
+        assert!(json.contains("\"recommended_difficulty\": \"SMALL_MEDIUM\""));
+        assert_eq!(BenchmarkReport::load(&path).unwrap(), report);
+    }
+}
@@ -0,0 +1,128 @@
+//! `benchmark` subcommand, which calibrates the starting difficulty to this machine.
+
+use crate::benchmark::{
+    BENCHMARK_PROGRAM, BenchmarkReport, BucketResult, REPRESENTATIVE_INPUTS, fib_input,
+};
+use crate::cli_messages::{print_info, print_success, print_warn};
+use crate::consts::cli_consts::prover_pool;
+use crate::nexus_orchestrator::TaskDifficulty;
+use crate::prover::ProvingContext;
+use crate::prover::engine::ProvingEngine;
+use crate::prover::program::{GuestProgram, ProgramRegistry};
+use crate::prover::program_cache::ProgramElf;
+use crate::prover::types::ProverError;
+use crate::session::setup::{max_threads_by_memory, max_workers};
+use futures::future::try_join_all;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Proves each difficulty's representative input on `num_workers` threads at once, from the
+/// smallest difficulty up to `max_difficulty`. Stops at the first difficulty that fails or
+/// takes longer than the promotion threshold, then saves the results to `report_path`. If no
+/// difficulty completed in time, the results of earlier runs are discarded instead.
+pub async fn run_benchmark(
+    proving: &ProvingContext,
+    report_path: &Path,
+    num_workers: usize,
+    max_difficulty: Option<TaskDifficulty>,
+) -> Result<(), Box<dyn Error>> {
+    let program = ProgramRegistry::builtin().get(BENCHMARK_PROGRAM)?;
+    let elf = Arc::new(proving.programs.resolve(program.as_ref()).await?);
+    let pool = &proving.pool;
+    print_info(
+        "Benchmarking",
+        &format!("{} concurrent proofs per difficulty", num_workers),
+    );
+
+    // Start the subprocesses and load the ELF first, so that startup is not timed
+    let warm_up = prove_batch(proving, &program, &elf, &fib_input(1), num_workers).await;
+    if let Err(e) = warm_up {
+        pool.shutdown(prover_pool::stop_grace()).await;
+        return Err(e.into());
+    }
+    pool.take_peak_rss();
+
+    let mut buckets = Vec::new();
+    for (difficulty, iterations) in REPRESENTATIVE_INPUTS {
+        if max_difficulty.is_some_and(|max| difficulty > max) {
+            break;
+        }
+        let started = Instant::now();
+        let result =
+            prove_batch(proving, &program, &elf, &fib_input(iterations), num_workers).await;
+        let bucket = BucketResult {
+            difficulty,
+            fib_iterations: iterations,
+            wall_time_ms: started.elapsed().as_millis() as u64,
+            peak_rss_bytes: pool.take_peak_rss(),
+            error: result.err().map(|e| e.to_string()),
+        };
+        println!("  {}", bucket_line(&bucket));
+
+        let climb = bucket.within_threshold();
+        buckets.push(bucket);
+        if !climb {
+            break;
+        }
+    }
+    pool.shutdown(prover_pool::stop_grace()).await;
+
+    let Some(report) =
+        BenchmarkReport::new(num_workers, buckets, max_threads_by_memory, max_workers())
+    else {
+        // A stale recommendation would have `start` begin above what this machine can prove
+        let _ = fs::remove_file(report_path);
+        print_warn(
+            "Benchmark not saved",
+            "No difficulty completed within the promotion threshold. `start` begins at SMALL_MEDIUM.",
+        );
+        return Ok(());
+    };
+    report.save(report_path)?;
+    print_success(
+        "Benchmark saved",
+        &format!(
+            "Approximate recommendation: --max-difficulty {} --max-threads {}. `start` now begins at {} on {} threads.",
+            report.recommended_difficulty.as_str_name(),
+            report.recommended_threads,
+            report.start_difficulty().as_str_name(),
+            report.recommended_threads
+        ),
+    );
+    Ok(())
+}
+
+/// Proves `input` on `num_workers` threads at once.
+async fn prove_batch(
+    proving: &ProvingContext,
+    program: &Arc<dyn GuestProgram>,
+    elf: &Arc<ProgramElf>,
+    input: &[u8],
+    num_workers: usize,
+) -> Result<(), ProverError> {
+    try_join_all(
+        (0..num_workers).map(|_| ProvingEngine::prove_input(proving, program, elf, input)),
+    )
+    .await?;
+    Ok(())
+}
+
+fn bucket_line(bucket: &BucketResult) -> String {
+    let outcome = match &bucket.error {
+        Some(error) => format!("failed: {}", error),
+        None => format!(
+            "{:.1}s, peak {} MiB per thread",
+            bucket.wall_time().as_secs_f64(),
+            bucket.peak_rss_bytes / (1024 * 1024)
+        ),
+    };
+    format!(
+        "{:<14} n={:<8} {}",
+        bucket.difficulty.as_str_name(),
+        bucket.fib_iterations,
+        outcome
+    )
+}
@@ -2,7 +2,13 @@
 
 use crate::cli_messages::{print_error, print_info, print_success};
 use crate::environment::Environment;
+use crate::network::{CircuitBreakerConfig, RetryPolicies};
 use crate::orchestrator::Orchestrator;
+use crate::orchestrator::error::OrchestratorError;
+use crate::prover::limits::ProverLimits;
+use crate::prover::program_cache::ProgramsConfig;
+use crate::prover::proof_cache::ProofCacheConfig;
+use crate::prover::verifier::VerificationPolicy;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::fs;
@@ -24,6 +30,11 @@ pub struct Config {
     #[serde(default)]
     pub environment: String,
 
+    /// Orchestrator URLs to fail over between, in order of preference. Overridden by the
+    /// `NEXUS_ORCHESTRATOR_URLS` environment variable and `--orchestrator-url`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub orchestrator_urls: Vec<String>,
+
     /// User ID from config file
     #[serde(default)]
     pub user_id: String,
@@ -35,6 +46,34 @@ pub struct Config {
     /// Node ID, resolved to a valid u64 during `Config::resolve`
     #[serde(default)]
     pub node_id: String,
+
+    /// Additional node IDs to run alongside `node_id` from a single process
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub node_ids: Vec<u64>,
+
+    /// Retry and backoff policies for orchestrator requests
+    #[serde(default, skip_serializing_if = "RetryPolicies::is_default")]
+    pub retry_policies: RetryPolicies,
+
+    /// Circuit breaker thresholds for orchestrator requests
+    #[serde(default, skip_serializing_if = "CircuitBreakerConfig::is_default")]
+    pub circuit_breaker: CircuitBreakerConfig,
+
+    /// Guest program ELF pins and download URL
+    #[serde(default, skip_serializing_if = "ProgramsConfig::is_default")]
+    pub programs: ProgramsConfig,
+
+    /// Memory, CPU time and wall-clock limits of prover subprocesses
+    #[serde(default, skip_serializing_if = "ProverLimits::is_default")]
+    pub prover_limits: ProverLimits,
+
+    /// Size limit of the cache of proofs for repeated inputs
+    #[serde(default, skip_serializing_if = "ProofCacheConfig::is_default")]
+    pub proof_cache: ProofCacheConfig,
+
+    /// Which proofs are verified before submission: `full`, `sampled` or `none`
+    #[serde(default, skip_serializing_if = "VerificationPolicy::is_default")]
+    pub verification: VerificationPolicy,
 }
 
 impl Config {
@@ -49,7 +88,15 @@ impl Config {
             user_id,
             wallet_address,
             node_id,
+            node_ids: Vec::new(),
+            retry_policies: RetryPolicies::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            programs: ProgramsConfig::default(),
+            prover_limits: ProverLimits::default(),
+            proof_cache: ProofCacheConfig::default(),
+            verification: VerificationPolicy::default(),
             environment: environment.to_string(),
+            orchestrator_urls: Vec::new(),
         }
     }
 
@@ -61,6 +108,15 @@ impl Config {
         Ok(config)
     }
 
+    /// Loads configuration from a JSON file, or the default configuration if there is no file
+    /// yet. A file that cannot be read or parsed is still an error.
+    pub fn load_or_default(path: &Path) -> Result<Self, std::io::Error> {
+        match Self::load_from_file(path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+            result => result,
+        }
+    }
+
     /// Saves the configuration to a JSON file at the given path.
     pub fn save(&self, path: &Path) -> Result<(), std::io::Error> {
         if let Some(parent) = path.parent() {
@@ -102,14 +158,25 @@ impl Config {
             print_success("Using provided Node ID", &format!("Node ID: {}", node_id));
 
             // Get the wallet address for analytics
-            let wallet_address = orchestrator.get_node(&node_id.to_string()).await?;
+            let wallet_address = Self::node_wallet(orchestrator, node_id).await?;
+
+            // Request policies, program pins, prover limits, the proof cache and the verification policy still apply from the config file, if there is one
+            let file_config = Config::load_or_default(config_path)?;
 
             // Create a minimal config with the provided node_id
             let config = Config {
                 user_id: "anonymous".to_string(), // Use anonymous for --node-id shortcut
                 wallet_address,
                 node_id: node_id.to_string(),
+                node_ids: Vec::new(),
                 environment: "".to_string(),
+                orchestrator_urls: file_config.orchestrator_urls,
+                retry_policies: file_config.retry_policies,
+                circuit_breaker: file_config.circuit_breaker,
+                programs: file_config.programs,
+                prover_limits: file_config.prover_limits,
+                proof_cache: file_config.proof_cache,
+                verification: file_config.verification,
             };
 
             return Ok(config);
@@ -147,7 +214,7 @@ impl Config {
         };
 
         // Get the wallet address for analytics
-        let wallet_address = orchestrator.get_node(&resolved_node_id.to_string()).await?;
+        let wallet_address = Self::node_wallet(orchestrator, resolved_node_id).await?;
 
         // Populate the config struct with the resolved values
         config.node_id = resolved_node_id.to_string();
@@ -156,6 +223,67 @@ impl Config {
         Ok(config)
     }
 
+    /// Resolves one configuration per node to run.
+    ///
+    /// Node IDs given with `--node-id` take precedence. Otherwise the node from the config file
+    /// is run together with any additional `node_ids` listed there.
+    pub async fn resolve_nodes(
+        node_id_args: &[u64],
+        config_path: &Path,
+        orchestrator: &impl Orchestrator,
+    ) -> Result<Vec<Self>, Box<dyn Error>> {
+        let mut configs: Vec<Config> = Vec::new();
+
+        if !node_id_args.is_empty() {
+            for node_id in node_id_args {
+                if configs.iter().any(|c| c.node_id == node_id.to_string()) {
+                    continue;
+                }
+                configs.push(Self::resolve(Some(*node_id), config_path, orchestrator).await?);
+            }
+            return Ok(configs);
+        }
+
+        let primary = Self::resolve(None, config_path, orchestrator).await?;
+        configs.push(primary.clone());
+        for node_id in &primary.node_ids {
+            if configs.iter().any(|c| c.node_id == node_id.to_string()) {
+                continue;
+            }
+            print_success(
+                "Found additional Node ID in config file",
+                &format!("Node ID: {}", node_id),
+            );
+            let wallet_address = Self::node_wallet(orchestrator, *node_id).await?;
+            configs.push(Config {
+                node_id: node_id.to_string(),
+                wallet_address,
+                ..primary.clone()
+            });
+        }
+
+        Ok(configs)
+    }
+
+    /// Looks up the wallet address a node is registered to
+    async fn node_wallet(
+        orchestrator: &impl Orchestrator,
+        node_id: u64,
+    ) -> Result<String, Box<dyn Error>> {
+        orchestrator
+            .get_node(&node_id.to_string())
+            .await
+            .map_err(|e| {
+                if let OrchestratorError::NodeNotFound { .. } = e {
+                    print_error(
+                        &format!("Node {} is not registered", node_id),
+                        Some("Please register your node again: nexus-cli register-node"),
+                    );
+                }
+                e.into()
+            })
+    }
+
     /// Resolves node ID from the configuration file content
     fn resolve_node_id_from_config(&self) -> Result<u64, Box<dyn Error>> {
         if self.user_id.is_empty() {
@@ -199,9 +327,17 @@ mod tests {
     fn get_config() -> Config {
         Config {
             environment: "test".to_string(),
+            orchestrator_urls: Vec::new(),
             user_id: "test_user_id".to_string(),
             wallet_address: "0x1234567890abcdef1234567890abcdef12345678".to_string(),
             node_id: "test_node_id".to_string(),
+            node_ids: Vec::new(),
+            retry_policies: RetryPolicies::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            programs: ProgramsConfig::default(),
+            prover_limits: ProverLimits::default(),
+            proof_cache: ProofCacheConfig::default(),
+            verification: VerificationPolicy::default(),
         }
     }
 
@@ -268,6 +404,19 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    // Only a missing configuration file falls back to the default configuration.
+    fn test_load_or_default_only_defaults_missing_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        assert_eq!(Config::load_or_default(&path).unwrap(), Config::default());
+
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "invalid json").unwrap();
+        let error = Config::load_or_default(&path).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+
     #[test]
     // Clearing the node configuration file should remove it if it exists.
     fn test_clear_node_config_removes_file() {
@@ -338,9 +487,17 @@ mod tests {
 
         let config = Config {
             environment: "".to_string(),
+            orchestrator_urls: Vec::new(),
             user_id: "".to_string(),
             wallet_address: "".to_string(),
             node_id: "12345".to_string(),
+            node_ids: Vec::new(),
+            retry_policies: RetryPolicies::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            programs: ProgramsConfig::default(),
+            prover_limits: ProverLimits::default(),
+            proof_cache: ProofCacheConfig::default(),
+            verification: VerificationPolicy::default(),
         };
         config.save(&path).unwrap();
 
@@ -359,6 +516,128 @@ mod tests {
         }
     }
 
+    #[test]
+    // Should load additional node IDs listed in the config file.
+    fn test_load_config_with_node_ids() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.json");
+
+        let mut file = File::create(&path).unwrap();
+        writeln!(
+            file,
+            r#"{{ "user_id": "test_user", "node_id": "12345", "node_ids": [23456, 34567] }}"#
+        )
+        .unwrap();
+
+        let config = Config::load_from_file(&path).unwrap();
+        assert_eq!(config.node_id, "12345");
+        assert_eq!(config.node_ids, vec![23456, 34567]);
+    }
+
+    #[test]
+    // Should load the orchestrator URLs to fail over between, keeping their order.
+    fn test_load_config_with_orchestrator_urls() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.json");
+
+        let mut file = File::create(&path).unwrap();
+        writeln!(
+            file,
+            r#"{{ "node_id": "12345", "orchestrator_urls": ["https://a.example", "https://b.example"] }}"#
+        )
+        .unwrap();
+
+        let config = Config::load_from_file(&path).unwrap();
+        assert_eq!(
+            config.orchestrator_urls,
+            vec!["https://a.example", "https://b.example"]
+        );
+        assert!(Config::default().orchestrator_urls.is_empty());
+    }
+
+    #[test]
+    // Circuit breaker thresholds left out of the config file should keep their defaults.
+    fn test_load_config_with_partial_circuit_breaker() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.json");
+
+        let mut file = File::create(&path).unwrap();
+        writeln!(
+            file,
+            r#"{{ "node_id": "12345", "circuit_breaker": {{ "failure_threshold": 2 }} }}"#
+        )
+        .unwrap();
+
+        let config = Config::load_from_file(&path).unwrap();
+        assert_eq!(config.circuit_breaker.failure_threshold, 2);
+        assert_eq!(
+            config.circuit_breaker.open_duration_ms,
+            CircuitBreakerConfig::default().open_duration_ms
+        );
+    }
+
+    #[test]
+    // Prover limits left out of the config file should stay unset.
+    fn test_load_config_with_partial_prover_limits() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.json");
+
+        let mut file = File::create(&path).unwrap();
+        writeln!(
+            file,
+            r#"{{ "node_id": "12345", "prover_limits": {{ "timeout_secs": 900 }} }}"#
+        )
+        .unwrap();
+
+        let config = Config::load_from_file(&path).unwrap();
+        assert_eq!(config.prover_limits.timeout_secs, Some(900));
+        assert_eq!(config.prover_limits.memory_mb, None);
+        assert_eq!(config.prover_limits.cpu_secs, None);
+    }
+
+    #[test]
+    // Program pins should load from the config file.
+    fn test_load_config_with_program_pins() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.json");
+
+        let mut file = File::create(&path).unwrap();
+        writeln!(
+            file,
+            r#"{{ "node_id": "12345", "programs": {{ "url": "https://example.com/elfs", "pins": [{{ "program_id": "fib_input_initial", "sha256": "abcd" }}] }} }}"#
+        )
+        .unwrap();
+
+        let config = Config::load_from_file(&path).unwrap();
+        assert_eq!(
+            config.programs.url.as_deref(),
+            Some("https://example.com/elfs")
+        );
+        assert_eq!(config.programs.pins.len(), 1);
+        assert_eq!(config.programs.pins[0].sha256, "abcd");
+        assert_eq!(config.programs.pins[0].keccak256, None);
+    }
+
+    #[tokio::test]
+    // Repeated --node-id values should resolve to one config per distinct node.
+    async fn test_resolve_nodes_from_args() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.json");
+
+        let mut orchestrator = crate::orchestrator::MockOrchestrator::new();
+        orchestrator
+            .expect_get_node()
+            .times(2)
+            .returning(|node_id| Ok(format!("wallet-{}", node_id)));
+
+        let configs = Config::resolve_nodes(&[1, 2, 1], &path, &orchestrator)
+            .await
+            .unwrap();
+        let node_ids: Vec<&str> = configs.iter().map(|c| c.node_id.as_str()).collect();
+        assert_eq!(node_ids, vec!["1", "2"]);
+        assert_eq!(configs[1].wallet_address, "wallet-2");
+    }
+
     #[test]
     // Should ignore unexpected fields in the JSON.
     fn test_load_config_with_additional_fields() {
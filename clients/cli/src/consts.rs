@@ -16,6 +16,26 @@ pub mod cli_consts {
     /// Maximum number of event buffer size for worker threads
     pub const EVENT_QUEUE_SIZE: usize = 100;
 
+    /// Queue depths between the stages of the pipelined worker
+    pub mod pipeline {
+        use std::time::Duration;
+
+        /// Tasks fetched ahead of the prover (prefetch depth)
+        pub const TASK_QUEUE_DEPTH: usize = 1;
+
+        /// Finished proofs waiting for the submitter. The prover stops taking new tasks
+        /// once this many proofs are queued, which bounds memory while the network is down.
+        pub const SUBMISSION_QUEUE_DEPTH: usize = 4;
+
+        /// Time workers get to wind down after shutdown before they are aborted (seconds)
+        pub const SHUTDOWN_TIMEOUT_SECS: u64 = 10;
+
+        /// Helper function to get the shutdown timeout
+        pub const fn shutdown_timeout() -> Duration {
+            Duration::from_secs(SHUTDOWN_TIMEOUT_SECS)
+        }
+    }
+
     // =============================================================================
     // PROVING CONFIGURATIONS
     // =============================================================================
@@ -35,9 +55,16 @@ pub mod cli_consts {
 
     /// Task difficulty system configuration
     pub mod difficulty {
+        use std::time::Duration;
+
         /// Time threshold for auto-promotion (seconds)
         /// Tasks completing faster than this will promote to next difficulty level
         pub const PROMOTION_THRESHOLD_SECS: u64 = 7 * 60; // 7 minutes
+
+        /// Helper function to get the promotion threshold
+        pub const fn promotion_threshold() -> Duration {
+            Duration::from_secs(PROMOTION_THRESHOLD_SECS)
+        }
     }
 
     // =============================================================================
@@ -85,6 +112,10 @@ pub mod cli_consts {
         /// Less restrictive than task fetching
         pub const RATE_LIMIT_INTERVAL_MS: u64 = 100;
 
+        /// Age after which a spooled submission is dropped instead of replayed (seconds)
+        /// Tasks are unlikely to still be accepted by the orchestrator after this long
+        pub const SPOOL_EXPIRY_SECS: u64 = 24 * 60 * 60; // 24 hours
+
         /// Helper function to get initial backoff duration
         pub const fn initial_backoff() -> Duration {
             Duration::from_millis(INITIAL_BACKOFF_MS)
@@ -130,4 +161,155 @@ pub mod cli_consts {
             Duration::from_secs(EXTRA_RETRY_DELAY_SECS)
         }
     }
+
+    /// Request body upload configuration
+    pub mod uploads {
+        use std::time::Duration;
+
+        /// Timeout for requests without a body, and the base timeout for uploads (seconds)
+        pub const BASE_TIMEOUT_SECS: u64 = 10;
+
+        /// Slowest uplink an upload is given time for (bytes per second)
+        pub const MIN_THROUGHPUT_BYTES_PER_SEC: u64 = 32 * 1024; // 32 KiB/s
+
+        /// Upper bound on the timeout of a single upload (seconds)
+        pub const MAX_TIMEOUT_SECS: u64 = 15 * 60; // 15 minutes
+
+        /// Size of the chunks request bodies are streamed in (bytes)
+        pub const CHUNK_BYTES: usize = 64 * 1024;
+
+        /// Bodies smaller than this are sent uncompressed (bytes)
+        pub const COMPRESSION_MIN_BYTES: usize = 1024;
+
+        /// Bodies smaller than this do not report upload progress (bytes)
+        pub const PROGRESS_MIN_BYTES: u64 = 1024 * 1024; // 1 MiB
+
+        /// Upload progress is reported each time this share of the body has been sent
+        pub const PROGRESS_STEP_PERCENT: u64 = 25;
+
+        /// Helper function to get the base request timeout
+        pub const fn base_timeout() -> Duration {
+            Duration::from_secs(BASE_TIMEOUT_SECS)
+        }
+
+        /// Helper function to get the maximum upload timeout
+        pub const fn max_timeout() -> Duration {
+            Duration::from_secs(MAX_TIMEOUT_SECS)
+        }
+    }
+
+    /// Orchestrator endpoint failover configuration
+    pub mod endpoints {
+        use std::time::Duration;
+
+        /// Interval between health and latency probes of each endpoint (seconds)
+        pub const PROBE_INTERVAL_SECS: u64 = 30;
+
+        /// Timeout for a single probe (seconds)
+        pub const PROBE_TIMEOUT_SECS: u64 = 5;
+
+        /// Time to stay on a fallback endpoint before switching back to a healthy
+        /// preferred one (seconds)
+        pub const FAIL_BACK_COOL_DOWN_SECS: u64 = 5 * 60; // 5 minutes
+
+        /// Helper function to get the probe interval
+        pub const fn probe_interval() -> Duration {
+            Duration::from_secs(PROBE_INTERVAL_SECS)
+        }
+
+        /// Helper function to get the probe timeout
+        pub const fn probe_timeout() -> Duration {
+            Duration::from_secs(PROBE_TIMEOUT_SECS)
+        }
+
+        /// Helper function to get the fail-back cool-down
+        pub const fn fail_back_cool_down() -> Duration {
+            Duration::from_secs(FAIL_BACK_COOL_DOWN_SECS)
+        }
+    }
+
+    /// Persistent prover subprocess configuration
+    pub mod prover_pool {
+        use std::time::Duration;
+
+        /// Jobs a prover subprocess runs before it is replaced
+        pub const MAX_JOBS_PER_PROCESS: u32 = 50;
+
+        /// Growth of a prover subprocess's resident memory between jobs, measured from the end
+        /// of its first job, after which it is replaced (bytes)
+        pub const MAX_RSS_GROWTH_BYTES: u64 = 1024 * 1024 * 1024; // 1 GiB
+
+        /// Interval between heartbeats from a prover subprocess (seconds)
+        pub const HEARTBEAT_INTERVAL_SECS: u64 = 5;
+
+        /// Time without any frame from a busy prover subprocess before it is killed (seconds)
+        pub const HEARTBEAT_TIMEOUT_SECS: u64 = 30;
+
+        /// Time for a new prover subprocess to report that it is ready (seconds)
+        pub const STARTUP_TIMEOUT_SECS: u64 = 30;
+
+        /// Size of the chunks a proof is streamed back in (bytes)
+        pub const PROOF_CHUNK_BYTES: usize = 256 * 1024;
+
+        /// Largest frame either side accepts (bytes)
+        pub const MAX_FRAME_BYTES: u32 = 16 * 1024 * 1024; // 16 MiB
+
+        /// Time an idle prover subprocess gets to exit on shutdown before it is killed (seconds)
+        pub const STOP_GRACE_SECS: u64 = 2;
+
+        /// Helper function to get the heartbeat interval
+        pub const fn heartbeat_interval() -> Duration {
+            Duration::from_secs(HEARTBEAT_INTERVAL_SECS)
+        }
+
+        /// Helper function to get the heartbeat timeout
+        pub const fn heartbeat_timeout() -> Duration {
+            Duration::from_secs(HEARTBEAT_TIMEOUT_SECS)
+        }
+
+        /// Helper function to get the startup timeout
+        pub const fn startup_timeout() -> Duration {
+            Duration::from_secs(STARTUP_TIMEOUT_SECS)
+        }
+
+        /// Helper function to get the stop grace period
+        pub const fn stop_grace() -> Duration {
+            Duration::from_secs(STOP_GRACE_SECS)
+        }
+    }
+
+    /// Guest program cache configuration
+    pub mod programs {
+        use std::time::Duration;
+
+        /// Timeout for downloading a guest program ELF (seconds)
+        pub const FETCH_TIMEOUT_SECS: u64 = 60;
+
+        /// Helper function to get the ELF download timeout
+        pub const fn fetch_timeout() -> Duration {
+            Duration::from_secs(FETCH_TIMEOUT_SECS)
+        }
+    }
+
+    /// Proof cache configuration
+    pub mod proof_cache {
+        /// Size limit of the proof cache unless configured otherwise (MiB)
+        pub const DEFAULT_MAX_MB: u64 = 1024;
+    }
+
+    /// Per-input proof checkpoint configuration
+    pub mod checkpoints {
+        /// Age after which checkpoints of an unfinished task are removed (seconds)
+        /// Tasks are unlikely to still be accepted by the orchestrator after this long
+        pub const EXPIRY_SECS: u64 = 24 * 60 * 60; // 24 hours
+    }
+
+    /// Proof verification configuration
+    pub mod verification {
+        /// Under the `sampled` policy, one proof in this many is verified
+        pub const SAMPLE_INTERVAL: u64 = 10;
+
+        /// Threads verifying proofs off the async runtime
+        pub const THREADS: usize = 2;
+    }
 }
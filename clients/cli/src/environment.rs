@@ -9,14 +9,114 @@ pub enum Environment {
     Production,
     /// Custom environment with a specific orchestrator URL.
     Custom { orchestrator_url: String },
+    /// Custom environment with several orchestrator URLs: a preferred one, and the ones to fail
+    /// over to in order of preference.
+    Failover {
+        orchestrator_url: String,
+        fallback_urls: Vec<String>,
+    },
+}
+
+/// Wire protocol used to reach the orchestrator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    /// Protobuf over plain HTTP requests
+    Http,
+    /// gRPC over HTTP/2, for `grpc://` (plaintext) and `grpcs://` (TLS) URLs
+    Grpc,
 }
 
 impl Environment {
-    /// Returns the orchestrator service URL associated with the environment.
+    /// Returns the orchestrator service URL associated with the environment. For a failover
+    /// environment this is the preferred URL.
     pub fn orchestrator_url(&self) -> &str {
         match self {
             Environment::Production => "https://production.orchestrator.nexus.xyz",
-            Environment::Custom { orchestrator_url } => orchestrator_url,
+            Environment::Custom { orchestrator_url }
+            | Environment::Failover {
+                orchestrator_url, ..
+            } => orchestrator_url,
+        }
+    }
+
+    /// Returns every orchestrator service URL of the environment, preferred first.
+    pub fn orchestrator_urls(&self) -> Vec<&str> {
+        match self {
+            Environment::Failover {
+                orchestrator_url,
+                fallback_urls,
+            } => std::iter::once(orchestrator_url)
+                .chain(fallback_urls)
+                .map(String::as_str)
+                .collect(),
+            _ => vec![self.orchestrator_url()],
+        }
+    }
+
+    /// Returns a custom environment for the given URLs, in order of preference, or `None` if
+    /// there are none. Several URLs make a failover environment.
+    pub fn custom(orchestrator_urls: Vec<String>) -> Option<Self> {
+        let mut urls = orchestrator_urls.into_iter();
+        let orchestrator_url = urls.next()?;
+        let fallback_urls: Vec<String> = urls.collect();
+        if fallback_urls.is_empty() {
+            Some(Environment::Custom { orchestrator_url })
+        } else {
+            Some(Environment::Failover {
+                orchestrator_url,
+                fallback_urls,
+            })
+        }
+    }
+
+    /// Returns a custom environment for a comma-separated list of URLs, in order of preference,
+    /// e.g. from the `NEXUS_ORCHESTRATOR_URLS` environment variable.
+    pub fn from_url_list(urls: &str) -> Option<Self> {
+        Self::custom(
+            urls.split(',')
+                .map(str::trim)
+                .filter(|url| !url.is_empty())
+                .map(String::from)
+                .collect(),
+        )
+    }
+
+    /// Returns the environment served by the given orchestrator URL.
+    pub fn from_orchestrator_url(url: &str) -> Self {
+        if url == Environment::Production.orchestrator_url() {
+            Environment::Production
+        } else {
+            Environment::Custom {
+                orchestrator_url: url.to_string(),
+            }
+        }
+    }
+
+    /// Returns the transport implied by the orchestrator URL scheme.
+    pub fn transport(&self) -> Transport {
+        let url = self.orchestrator_url().to_ascii_lowercase();
+        if url.starts_with("grpc://") || url.starts_with("grpcs://") {
+            Transport::Grpc
+        } else {
+            Transport::Http
+        }
+    }
+
+    /// Returns true if the orchestrator runs on this machine, e.g. `nexus-network dev-orchestrator`.
+    /// A failover environment is local only if all of its URLs are.
+    pub fn is_local(&self) -> bool {
+        match self {
+            Environment::Production => false,
+            Environment::Custom { .. } | Environment::Failover { .. } => {
+                self.orchestrator_urls().into_iter().all(|url| {
+                    reqwest::Url::parse(url)
+                        .ok()
+                        .and_then(|url| url.host_str().map(|host| host.to_string()))
+                        .is_some_and(|host| {
+                            matches!(host.as_str(), "localhost" | "127.0.0.1" | "[::1]")
+                        })
+                })
+            }
         }
     }
 }
@@ -37,6 +137,9 @@ impl Display for Environment {
         match self {
             Environment::Production => write!(f, "Production"),
             Environment::Custom { orchestrator_url } => write!(f, "Custom({})", orchestrator_url),
+            Environment::Failover { .. } => {
+                write!(f, "Failover({})", self.orchestrator_urls().join(", "))
+            }
         }
     }
 }
@@ -46,3 +149,83 @@ impl Debug for Environment {
         write!(f, "Environment::{}, URL: {}", self, self.orchestrator_url())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_local() {
+        assert!(!Environment::Production.is_local());
+
+        let local = Environment::Custom {
+            orchestrator_url: "http://127.0.0.1:8080".to_string(),
+        };
+        assert!(local.is_local());
+
+        let localhost = Environment::Custom {
+            orchestrator_url: "http://localhost:8080/".to_string(),
+        };
+        assert!(localhost.is_local());
+
+        let remote = Environment::Custom {
+            orchestrator_url: "https://staging.orchestrator.nexus.xyz".to_string(),
+        };
+        assert!(!remote.is_local());
+
+        let local_grpc = Environment::Custom {
+            orchestrator_url: "grpc://127.0.0.1:50051".to_string(),
+        };
+        assert!(local_grpc.is_local());
+
+        let partly_local = Environment::custom(vec![
+            "http://127.0.0.1:8080".to_string(),
+            "https://staging.orchestrator.nexus.xyz".to_string(),
+        ])
+        .unwrap();
+        assert!(!partly_local.is_local());
+    }
+
+    #[test]
+    fn test_custom_from_urls() {
+        assert_eq!(Environment::custom(Vec::new()), None);
+        assert_eq!(
+            Environment::custom(vec!["http://a".to_string()]),
+            Some(Environment::Custom {
+                orchestrator_url: "http://a".to_string()
+            })
+        );
+
+        let failover =
+            Environment::custom(vec!["http://a".to_string(), "http://b".to_string()]).unwrap();
+        assert_eq!(failover.orchestrator_url(), "http://a");
+        assert_eq!(failover.orchestrator_urls(), vec!["http://a", "http://b"]);
+        assert_eq!(failover.to_string(), "Failover(http://a, http://b)");
+    }
+
+    #[test]
+    fn test_custom_from_url_list() {
+        assert_eq!(Environment::from_url_list(""), None);
+        assert_eq!(Environment::from_url_list(" , "), None);
+        assert_eq!(
+            Environment::from_url_list("http://a, http://b,").unwrap(),
+            Environment::custom(vec!["http://a".to_string(), "http://b".to_string()]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_transport_from_url_scheme() {
+        assert_eq!(Environment::Production.transport(), Transport::Http);
+
+        for (url, transport) in [
+            ("http://127.0.0.1:8080", Transport::Http),
+            ("grpc://127.0.0.1:50051", Transport::Grpc),
+            ("GRPCS://orchestrator.internal", Transport::Grpc),
+        ] {
+            let environment = Environment::Custom {
+                orchestrator_url: url.to_string(),
+            };
+            assert_eq!(environment.transport(), transport);
+        }
+    }
+}
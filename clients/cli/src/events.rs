@@ -3,8 +3,10 @@
 //! Types and implementations for worker events and logging
 
 use crate::logging::{LogLevel, should_log_with_env};
+use crate::network::BreakerState;
 use chrono::Local;
 use std::fmt::Display;
+use std::time::Instant;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Worker {
@@ -43,6 +45,10 @@ pub struct Event {
     pub log_level: LogLevel,
     /// Optional state information for state change events
     pub prover_state: Option<ProverState>,
+    /// Node the event belongs to, set when several nodes run in one session
+    pub node_id: Option<u64>,
+    /// New orchestrator circuit breaker state, for breaker events
+    pub breaker_state: Option<BreakerState>,
 }
 
 impl PartialEq for Event {
@@ -53,6 +59,8 @@ impl PartialEq for Event {
             && self.event_type == other.event_type
             && self.log_level == other.log_level
             && self.prover_state == other.prover_state
+            && self.node_id == other.node_id
+            && self.breaker_state == other.breaker_state
         // Note: We don't compare state_start_time since Instant doesn't implement Eq
     }
 }
@@ -68,6 +76,8 @@ impl Event {
             event_type,
             log_level,
             prover_state: None,
+            node_id: None,
+            breaker_state: None,
         }
     }
 
@@ -79,9 +89,40 @@ impl Event {
             event_type: EventType::StateChange,
             log_level: LogLevel::Info,
             prover_state: Some(state),
+            node_id: None,
+            breaker_state: None,
         }
     }
 
+    /// The orchestrator circuit breaker changed state.
+    pub fn circuit_breaker(state: BreakerState) -> Self {
+        let (msg, event_type, log_level) = match state {
+            BreakerState::Open { next_probe } => (
+                format!(
+                    "Orchestrator unavailable, next probe in {}s",
+                    next_probe
+                        .saturating_duration_since(Instant::now())
+                        .as_secs()
+                ),
+                EventType::Error,
+                LogLevel::Warn,
+            ),
+            BreakerState::HalfOpen => (
+                "Probing whether the orchestrator is available again".to_string(),
+                EventType::Refresh,
+                LogLevel::Info,
+            ),
+            BreakerState::Closed => (
+                "Orchestrator available again".to_string(),
+                EventType::Success,
+                LogLevel::Info,
+            ),
+        };
+        let mut event = Self::new(Worker::TaskFetcher, msg, event_type, log_level);
+        event.breaker_state = Some(state);
+        event
+    }
+
     pub fn task_fetcher_with_level(
         msg: String,
         event_type: EventType,
@@ -107,6 +148,12 @@ impl Event {
         Self::new(Worker::Prover(thread_id), msg, event_type, log_level)
     }
 
+    /// Attribute the event to a node.
+    pub fn with_node_id(mut self, node_id: u64) -> Self {
+        self.node_id = Some(node_id);
+        self
+    }
+
     pub fn should_display(&self) -> bool {
         // Always show success events and info level events
         if self.event_type == EventType::Success || self.log_level >= LogLevel::Info {
@@ -122,6 +169,100 @@ impl Event {
 
 impl Display for Event {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} [{}] {}", self.event_type, self.timestamp, self.msg)
+        match self.node_id {
+            Some(node_id) => write!(
+                f,
+                "{} [{}] [node {}] {}",
+                self.event_type, self.timestamp, node_id, self.msg
+            ),
+            None => write!(f, "{} [{}] {}", self.event_type, self.timestamp, self.msg),
+        }
+    }
+}
+
+/// Per-node progress derived from worker events, for sessions running several nodes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NodeActivity {
+    pub tasks_fetched: u32,
+    pub proofs_submitted: u32,
+    pub failures: u32,
+    pub current_task: Option<String>,
+}
+
+impl NodeActivity {
+    /// Update the counters from an event emitted by this node's worker.
+    pub fn record(&mut self, event: &Event) {
+        match (event.worker, event.event_type) {
+            (Worker::TaskFetcher, EventType::Success)
+                if event.msg.contains("Step 1 of 4: Got task") =>
+            {
+                self.tasks_fetched += 1;
+                self.current_task = event.msg.split_whitespace().last().map(str::to_string);
+            }
+            (Worker::ProofSubmitter, EventType::Success)
+                if event
+                    .msg
+                    .contains("Step 4 of 4: Proof submitted successfully") =>
+            {
+                self.proofs_submitted += 1;
+            }
+            (Worker::Prover(_) | Worker::ProofSubmitter, EventType::Error) => {
+                self.failures += 1;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_activity_counts_events() {
+        let mut activity = NodeActivity::default();
+
+        activity.record(&Event::task_fetcher_with_level(
+            "Step 1 of 4: Got task abc123".to_string(),
+            EventType::Success,
+            LogLevel::Info,
+        ));
+        activity.record(&Event::proof_submitter_with_level(
+            "Step 4 of 4: Proof submitted successfully for task abc123\n".to_string(),
+            EventType::Success,
+            LogLevel::Info,
+        ));
+        activity.record(&Event::prover_with_level(
+            1,
+            "Proof generation failed for task def456".to_string(),
+            EventType::Error,
+            LogLevel::Error,
+        ));
+
+        assert_eq!(
+            activity,
+            NodeActivity {
+                tasks_fetched: 1,
+                proofs_submitted: 1,
+                failures: 1,
+                current_task: Some("abc123".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_display_includes_node_id() {
+        let event = Event::task_fetcher_with_level(
+            "Step 1 of 4: Got task abc123".to_string(),
+            EventType::Success,
+            LogLevel::Info,
+        );
+        assert!(!event.to_string().contains("[node"));
+        assert!(
+            event
+                .with_node_id(7)
+                .to_string()
+                .contains("[node 7] Step 1")
+        );
     }
 }
@@ -0,0 +1,130 @@
+//! `keys` subcommands for managing persistent node signing keys.
+
+use crate::cli_messages::{print_info, print_success, print_warn};
+use crate::config::Config;
+use crate::key_store::{KeyFile, KeyStore, PASSPHRASE_ENV, passphrase_from_env};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// Resolves the node to operate on: the given ID, or the node from the config file.
+fn resolve_node_id(node_id: Option<u64>, config_path: &Path) -> Result<u64, Box<dyn Error>> {
+    if let Some(node_id) = node_id {
+        return Ok(node_id);
+    }
+    let config = Config::load_from_file(config_path)
+        .map_err(|_| "No node configured, pass --node-id to choose a node")?;
+    config
+        .node_id
+        .parse::<u64>()
+        .map_err(|_| "No node configured, pass --node-id to choose a node".into())
+}
+
+fn describe(key_file: &KeyFile) -> String {
+    format!(
+        "{}{}",
+        key_file.public_key,
+        if key_file.is_encrypted() {
+            " (encrypted)"
+        } else {
+            ""
+        }
+    )
+}
+
+/// Shows the public key of one node, or of every stored key.
+pub fn show_keys(store: &KeyStore, node_id: Option<u64>) -> Result<(), Box<dyn Error>> {
+    let key_files = match node_id {
+        Some(node_id) => store.read(node_id)?.into_iter().collect(),
+        None => store.list()?,
+    };
+    if key_files.is_empty() {
+        print_info("No signing keys stored", &store.dir().display().to_string());
+        return Ok(());
+    }
+
+    for key_file in &key_files {
+        print_info(
+            &format!("Node {}", key_file.node_id),
+            &format!("Public key: {}", describe(key_file)),
+        );
+    }
+    Ok(())
+}
+
+/// Replaces a node's signing key with a new one.
+pub fn rotate_key(
+    store: &KeyStore,
+    node_id: Option<u64>,
+    config_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let node_id = resolve_node_id(node_id, config_path)?;
+    let passphrase = passphrase_from_env();
+    let signing_key = store.rotate(node_id, passphrase.as_deref())?;
+    if passphrase.is_none() {
+        print_warn(
+            "Signing key stored unencrypted",
+            &format!("Set {} to encrypt it", PASSPHRASE_ENV),
+        );
+    }
+    print_success(
+        &format!("Rotated signing key for node {}", node_id),
+        &format!(
+            "Public key: {}",
+            hex::encode(signing_key.verifying_key().to_bytes())
+        ),
+    );
+    Ok(())
+}
+
+/// Writes a node's key file to `output`, or to stdout.
+pub fn export_key(
+    store: &KeyStore,
+    node_id: Option<u64>,
+    output: Option<&Path>,
+    config_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let node_id = resolve_node_id(node_id, config_path)?;
+    let key_file = store
+        .read(node_id)?
+        .ok_or_else(|| format!("No signing key stored for node {}", node_id))?;
+    let json = serde_json::to_string_pretty(&key_file)?;
+
+    match output {
+        Some(path) => {
+            crate::fs_util::write_private(path, json.as_bytes())?;
+            print_success(
+                &format!("Exported signing key for node {}", node_id),
+                &path.display().to_string(),
+            );
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+/// Imports a key file, optionally under a different node ID. The key is re-encrypted with the
+/// current passphrase, if any.
+pub fn import_key(
+    store: &KeyStore,
+    input: &Path,
+    node_id: Option<u64>,
+) -> Result<(), Box<dyn Error>> {
+    let key_file: KeyFile = serde_json::from_slice(&fs::read(input)?)?;
+    let passphrase = passphrase_from_env();
+    let signing_key = key_file.signing_key(passphrase.as_deref())?;
+    let node_id = node_id.unwrap_or(key_file.node_id);
+
+    if store.read(node_id)?.is_some() {
+        print_warn(
+            &format!("Replacing existing signing key for node {}", node_id),
+            "",
+        );
+    }
+    store.write(&KeyFile::new(node_id, &signing_key, passphrase.as_deref())?)?;
+    print_success(
+        &format!("Imported signing key for node {}", node_id),
+        &format!("Public key: {}", key_file.public_key),
+    );
+    Ok(())
+}
@@ -0,0 +1,373 @@
+//! Persistent node signing keys
+//!
+//! Each node's Ed25519 signing key is stored as `<node_id>.json` in a `keys` directory next to
+//! the config file, readable only by the owner on Unix. When `NEXUS_KEY_PASSPHRASE` is set, the
+//! secret key is encrypted with ChaCha20-Poly1305 under a key derived from the passphrase with
+//! Argon2id.
+
+use crate::fs_util::write_private;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use ed25519_dalek::SigningKey;
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// Environment variable holding the passphrase used to encrypt signing keys
+pub const PASSPHRASE_ENV: &str = "NEXUS_KEY_PASSPHRASE";
+
+/// Key derivation function recorded in encrypted key files
+const KDF_ARGON2ID: &str = "argon2id";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+#[derive(Error, Debug)]
+pub enum KeyStoreError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Invalid key file: {0}")]
+    InvalidKeyFile(String),
+
+    #[error("Signing key for node {0} is encrypted, set NEXUS_KEY_PASSPHRASE to unlock it")]
+    PassphraseRequired(u64),
+
+    #[error("Could not decrypt signing key for node {0}, wrong passphrase?")]
+    Decryption(u64),
+
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+}
+
+impl From<serde_json::Error> for KeyStoreError {
+    fn from(e: serde_json::Error) -> Self {
+        KeyStoreError::InvalidKeyFile(e.to_string())
+    }
+}
+
+/// Get the key directory that sits next to the config file, typically ~/.nexus/keys.
+pub fn get_keys_dir(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .map(|parent| parent.join("keys"))
+        .unwrap_or_else(|| PathBuf::from("keys"))
+}
+
+/// Read the key passphrase from the environment. An empty value counts as unset.
+pub fn passphrase_from_env() -> Option<String> {
+    std::env::var(PASSPHRASE_ENV)
+        .ok()
+        .filter(|passphrase| !passphrase.is_empty())
+}
+
+/// Parameters needed to decrypt an encrypted secret key
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct KeyEncryption {
+    kdf: String,
+    /// Hex-encoded KDF salt
+    salt: String,
+    /// Hex-encoded ChaCha20-Poly1305 nonce
+    nonce: String,
+}
+
+/// On-disk representation of a node signing key. This is also the export format.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KeyFile {
+    pub node_id: u64,
+    /// Hex-encoded Ed25519 public key
+    pub public_key: String,
+    /// Present when `secret_key` is encrypted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encryption: Option<KeyEncryption>,
+    /// Hex-encoded secret key, or its ciphertext when encrypted
+    secret_key: String,
+    /// Seconds since the Unix epoch when the key was created
+    pub created_at: u64,
+}
+
+impl KeyFile {
+    /// Wrap a signing key, encrypting it when a passphrase is given.
+    pub fn new(
+        node_id: u64,
+        signing_key: &SigningKey,
+        passphrase: Option<&str>,
+    ) -> Result<Self, KeyStoreError> {
+        let secret = signing_key.to_bytes();
+        let (encryption, secret_key) = match passphrase {
+            Some(passphrase) => {
+                let mut salt = [0u8; SALT_LEN];
+                let mut nonce = [0u8; NONCE_LEN];
+                OsRng.fill_bytes(&mut salt);
+                OsRng.fill_bytes(&mut nonce);
+
+                let cipher = cipher_for(passphrase, &salt)?;
+                let ciphertext = cipher
+                    .encrypt(Nonce::from_slice(&nonce), secret.as_slice())
+                    .map_err(|e| KeyStoreError::Encryption(e.to_string()))?;
+                let encryption = KeyEncryption {
+                    kdf: KDF_ARGON2ID.to_string(),
+                    salt: hex::encode(salt),
+                    nonce: hex::encode(nonce),
+                };
+                (Some(encryption), hex::encode(ciphertext))
+            }
+            None => (None, hex::encode(secret)),
+        };
+
+        Ok(Self {
+            node_id,
+            public_key: hex::encode(signing_key.verifying_key().to_bytes()),
+            encryption,
+            secret_key,
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption.is_some()
+    }
+
+    /// Recover the signing key, checking it against the recorded public key.
+    pub fn signing_key(&self, passphrase: Option<&str>) -> Result<SigningKey, KeyStoreError> {
+        let secret = match &self.encryption {
+            Some(encryption) => {
+                if encryption.kdf != KDF_ARGON2ID {
+                    return Err(KeyStoreError::InvalidKeyFile(format!(
+                        "unsupported key derivation function '{}'",
+                        encryption.kdf
+                    )));
+                }
+                let passphrase =
+                    passphrase.ok_or(KeyStoreError::PassphraseRequired(self.node_id))?;
+                let salt = decode_hex(&encryption.salt)?;
+                let nonce = decode_hex(&encryption.nonce)?;
+                if nonce.len() != NONCE_LEN {
+                    return Err(KeyStoreError::InvalidKeyFile("bad nonce length".into()));
+                }
+                cipher_for(passphrase, &salt)?
+                    .decrypt(
+                        Nonce::from_slice(&nonce),
+                        decode_hex(&self.secret_key)?.as_slice(),
+                    )
+                    .map_err(|_| KeyStoreError::Decryption(self.node_id))?
+            }
+            None => decode_hex(&self.secret_key)?,
+        };
+
+        let secret: [u8; 32] = secret
+            .try_into()
+            .map_err(|_| KeyStoreError::InvalidKeyFile("secret key must be 32 bytes".into()))?;
+        let signing_key = SigningKey::from_bytes(&secret);
+        if hex::encode(signing_key.verifying_key().to_bytes()) != self.public_key {
+            return Err(KeyStoreError::InvalidKeyFile(
+                "secret key does not match public key".into(),
+            ));
+        }
+        Ok(signing_key)
+    }
+}
+
+/// Derive the encryption key for a passphrase.
+fn cipher_for(passphrase: &str, salt: &[u8]) -> Result<ChaCha20Poly1305, KeyStoreError> {
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| KeyStoreError::Encryption(e.to_string()))?;
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, KeyStoreError> {
+    hex::decode(value).map_err(|e| KeyStoreError::InvalidKeyFile(e.to_string()))
+}
+
+/// Directory of node signing keys
+#[derive(Debug, Clone)]
+pub struct KeyStore {
+    dir: PathBuf,
+}
+
+impl KeyStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn key_path(&self, node_id: u64) -> PathBuf {
+        self.dir.join(format!("{}.json", node_id))
+    }
+
+    /// Read the key file for a node, if present.
+    pub fn read(&self, node_id: u64) -> Result<Option<KeyFile>, KeyStoreError> {
+        match fs::read(self.key_path(node_id)) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write a key file, replacing any existing key for the same node.
+    pub fn write(&self, key_file: &KeyFile) -> Result<(), KeyStoreError> {
+        fs::create_dir_all(&self.dir)?;
+        let bytes = serde_json::to_vec_pretty(key_file)?;
+        let path = self.key_path(key_file.node_id);
+        let tmp_path = path.with_extension("tmp");
+        write_private(&tmp_path, &bytes)?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+
+    /// Load the signing key for a node, generating and storing a new one on first use.
+    /// Returns the key and whether it was just created.
+    pub fn load_or_create(
+        &self,
+        node_id: u64,
+        passphrase: Option<&str>,
+    ) -> Result<(SigningKey, bool), KeyStoreError> {
+        if let Some(key_file) = self.read(node_id)? {
+            return Ok((key_file.signing_key(passphrase)?, false));
+        }
+        let signing_key = SigningKey::generate(&mut OsRng);
+        self.write(&KeyFile::new(node_id, &signing_key, passphrase)?)?;
+        Ok((signing_key, true))
+    }
+
+    /// Replace a node's signing key with a freshly generated one.
+    pub fn rotate(
+        &self,
+        node_id: u64,
+        passphrase: Option<&str>,
+    ) -> Result<SigningKey, KeyStoreError> {
+        let signing_key = SigningKey::generate(&mut OsRng);
+        self.write(&KeyFile::new(node_id, &signing_key, passphrase)?)?;
+        Ok(signing_key)
+    }
+
+    /// All readable key files, ordered by node ID.
+    pub fn list(&self) -> Result<Vec<KeyFile>, KeyStoreError> {
+        let dir = match fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut key_files = Vec::new();
+        for dir_entry in dir {
+            let path = dir_entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            if let Some(key_file) = fs::read(&path)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<KeyFile>(&bytes).ok())
+            {
+                key_files.push(key_file);
+            }
+        }
+        key_files.sort_by_key(|key_file| key_file.node_id);
+        Ok(key_files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_load_or_create_is_stable() {
+        let dir = tempdir().unwrap();
+        let store = KeyStore::new(dir.path().join("keys"));
+
+        let (first, created) = store.load_or_create(42, None).unwrap();
+        assert!(created);
+        let (second, created) = store.load_or_create(42, None).unwrap();
+        assert!(!created);
+        assert_eq!(first.to_bytes(), second.to_bytes());
+
+        let key_file = store.read(42).unwrap().unwrap();
+        assert!(!key_file.is_encrypted());
+        assert_eq!(
+            key_file.public_key,
+            hex::encode(first.verifying_key().to_bytes())
+        );
+    }
+
+    #[test]
+    fn test_encrypted_key_requires_passphrase() {
+        let dir = tempdir().unwrap();
+        let store = KeyStore::new(dir.path().to_path_buf());
+
+        let (signing_key, _) = store.load_or_create(7, Some("hunter2")).unwrap();
+        let key_file = store.read(7).unwrap().unwrap();
+        assert!(key_file.is_encrypted());
+        assert_ne!(key_file.secret_key, hex::encode(signing_key.to_bytes()));
+
+        assert!(matches!(
+            key_file.signing_key(None),
+            Err(KeyStoreError::PassphraseRequired(7))
+        ));
+        assert!(matches!(
+            key_file.signing_key(Some("wrong")),
+            Err(KeyStoreError::Decryption(7))
+        ));
+        assert_eq!(
+            key_file.signing_key(Some("hunter2")).unwrap().to_bytes(),
+            signing_key.to_bytes()
+        );
+    }
+
+    #[test]
+    fn test_rotate_replaces_key() {
+        let dir = tempdir().unwrap();
+        let store = KeyStore::new(dir.path().to_path_buf());
+
+        let (original, _) = store.load_or_create(1, None).unwrap();
+        let rotated = store.rotate(1, None).unwrap();
+        assert_ne!(original.to_bytes(), rotated.to_bytes());
+
+        let (loaded, created) = store.load_or_create(1, None).unwrap();
+        assert!(!created);
+        assert_eq!(loaded.to_bytes(), rotated.to_bytes());
+    }
+
+    #[test]
+    fn test_mismatched_public_key_is_rejected() {
+        let mut key_file = KeyFile::new(1, &SigningKey::from_bytes(&[7u8; 32]), None).unwrap();
+        key_file.public_key = hex::encode(
+            SigningKey::from_bytes(&[8u8; 32])
+                .verifying_key()
+                .to_bytes(),
+        );
+        assert!(matches!(
+            key_file.signing_key(None),
+            Err(KeyStoreError::InvalidKeyFile(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_key_files_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let store = KeyStore::new(dir.path().to_path_buf());
+        store.load_or_create(1, None).unwrap();
+
+        let mode = fs::metadata(store.key_path(1))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -1,39 +1,70 @@
 // Copyright (c) 2025 Nexus. All rights reserved.
 
 mod analytics;
+mod benchmark;
+mod benchmark_commands;
 mod cli_messages;
 mod config;
 mod consts;
 mod environment;
 mod events;
+mod fs_util;
+mod key_commands;
+mod key_store;
 mod keys;
 mod logging;
 mod network;
 #[path = "proto/nexus.orchestrator.rs"]
 mod nexus_orchestrator;
+mod node_commands;
 mod orchestrator;
+mod program_commands;
+mod proof_commands;
 mod prover;
 mod register;
 mod runtime;
 mod session;
+mod spool_commands;
 pub mod system;
 mod task;
 mod ui;
 mod version;
 mod workers;
 
+use crate::benchmark::{BenchmarkReport, get_benchmark_path};
+use crate::benchmark_commands::run_benchmark;
 use crate::config::{Config, get_config_path};
 use crate::environment::Environment;
-use crate::orchestrator::OrchestratorClient;
+use crate::key_commands::{export_key, import_key, rotate_key, show_keys};
+use crate::key_store::{KeyStore, get_keys_dir};
+use crate::network::Spool;
+use crate::network::http::HttpSettings;
+use crate::network::spool::get_spool_dir;
+use crate::network::upload::ContentEncoding;
+use crate::node_commands::{list_nodes, whoami};
+use crate::orchestrator::Connector;
+use crate::orchestrator::dev_server::{self, DevOrchestratorConfig};
+use crate::orchestrator::traffic::{ReplayOrchestrator, TrafficRecorder};
+use crate::program_commands::{list_programs, verify_programs};
+use crate::proof_commands::{inspect_proof, parse_input, prove_offline, verify_offline};
+use crate::prover::ProvingContext;
+use crate::prover::checkpoint::{TaskCheckpoints, get_checkpoint_dir};
 use crate::prover::engine::ProvingEngine;
+use crate::prover::limits::ProverLimits;
+use crate::prover::pool::{PoolConfig, ProverPool};
+use crate::prover::program_cache::{ProgramCache, get_programs_dir};
+use crate::prover::proof_cache::{ProofCache, get_proof_cache_dir};
+use crate::prover::verifier::{VerificationPolicy, VerificationPool};
 use crate::register::{register_node, register_user};
+use crate::session::setup::resolve_num_workers;
 use crate::session::{run_headless_mode, run_tui_mode, setup_session};
+use crate::spool_commands::{flush_spool, list_spool, purge_spool};
 use crate::version::manager::validate_version_requirements;
 use clap::{ArgAction, Parser, Subcommand};
-use postcard::to_allocvec;
 use std::error::Error;
-use std::io::Write;
+use std::io::IsTerminal;
 use std::process::exit;
+use std::sync::Arc;
 
 /// All available difficulty levels as (name, enum_value) pairs
 const DIFFICULTY_LEVELS: &[(&str, crate::nexus_orchestrator::TaskDifficulty)] = &[
@@ -83,6 +114,27 @@ fn print_available_difficulties() {
     }
 }
 
+/// Parse and validate a `--max-difficulty` argument (case-insensitive), exiting on an invalid
+/// level
+fn parse_max_difficulty(
+    max_difficulty: Option<&str>,
+) -> Option<crate::nexus_orchestrator::TaskDifficulty> {
+    let difficulty_str = max_difficulty?;
+    match validate_difficulty(difficulty_str) {
+        Some(difficulty) => Some(difficulty),
+        None => {
+            eprintln!(
+                "Error: Invalid difficulty level '{}'",
+                difficulty_str.trim()
+            );
+            print_available_difficulties();
+            eprintln!();
+            eprintln!("Note: Difficulty levels are case-insensitive.");
+            std::process::exit(1);
+        }
+    }
+}
+
 #[derive(Parser)]
 #[command(author, version = concat!(env!("CARGO_PKG_VERSION"), " (build ", env!("BUILD_TIMESTAMP"), ")"), about, long_about = None)]
 /// Command-line arguments
@@ -90,15 +142,54 @@ struct Args {
     /// Command to execute
     #[command(subcommand)]
     command: Command,
+
+    /// Proxy for all outbound traffic (http://, https://, socks5:// or socks5h://).
+    /// Without it, HTTPS_PROXY, HTTP_PROXY, ALL_PROXY and NO_PROXY are used.
+    #[arg(long, global = true, value_name = "URL")]
+    proxy: Option<String>,
+
+    /// PEM bundle of extra CA certificates to trust. Can be repeated.
+    #[arg(long = "ca-bundle", global = true, value_name = "PATH")]
+    ca_bundle: Vec<std::path::PathBuf>,
+
+    /// Compress request bodies sent to the orchestrator: none, gzip or zstd. The orchestrator
+    /// must accept the chosen Content-Encoding.
+    #[arg(long, global = true, value_name = "ENCODING", default_value = "none")]
+    compress: ContentEncoding,
+
+    /// Save every orchestrator request and response to this directory, as decoded JSON
+    /// and raw protobuf bytes
+    #[arg(long = "record-traffic", global = true, value_name = "DIR")]
+    record_traffic: Option<std::path::PathBuf>,
+
+    /// Serve orchestrator responses from a directory written by --record-traffic instead of
+    /// the network
+    #[arg(
+        long = "replay-traffic",
+        global = true,
+        value_name = "DIR",
+        conflicts_with = "record_traffic"
+    )]
+    replay_traffic: Option<std::path::PathBuf>,
+
+    /// Base URL to download guest program ELFs missing from the local cache from. Overrides
+    /// `programs.url` in the config file.
+    #[arg(long = "program-url", global = true, value_name = "URL")]
+    program_url: Option<String>,
+
+    /// Which proofs to verify before submitting them: full, sampled (one in ten) or none.
+    /// Overrides `verification` in the config file.
+    #[arg(long, global = true, value_name = "POLICY")]
+    verification: Option<VerificationPolicy>,
 }
 
 #[derive(Subcommand)]
 enum Command {
     /// Start the prover
     Start {
-        /// Node ID
-        #[arg(long, value_name = "NODE_ID")]
-        node_id: Option<u64>,
+        /// Node ID. Repeat to run several nodes from one process.
+        #[arg(long = "node-id", value_name = "NODE_ID")]
+        node_id: Vec<u64>,
 
         /// Run without the terminal UI
         #[arg(long = "headless", action = ArgAction::SetTrue)]
@@ -108,9 +199,11 @@ enum Command {
         #[arg(long = "max-threads", value_name = "MAX_THREADS")]
         max_threads: Option<u32>,
 
-        /// Custom orchestrator URL (overrides environment setting)
+        /// Custom orchestrator URL (overrides environment setting). `grpc://` and `grpcs://` URLs
+        /// use the gRPC transport, available in builds with the `grpc` feature. Repeat to fail
+        /// over between several HTTP orchestrators, in order of preference.
         #[arg(long = "orchestrator-url", value_name = "URL")]
-        orchestrator_url: Option<String>,
+        orchestrator_url: Vec<String>,
 
         /// Enable checking for risk of memory errors, may slow down CLI startup
         #[arg(long = "check-memory", default_value_t = false)]
@@ -133,21 +226,185 @@ enum Command {
         /// User's public Ethereum wallet address. 42-character hex string starting with '0x'
         #[arg(long, value_name = "WALLET_ADDRESS")]
         wallet_address: String,
+
+        /// Custom orchestrator URL (overrides environment setting). Repeat to fail over between
+        /// several orchestrators, in order of preference.
+        #[arg(long = "orchestrator-url", value_name = "URL")]
+        orchestrator_url: Vec<String>,
     },
     /// Register a new node to an existing user, or link an existing node to a user.
     RegisterNode {
         /// ID of the node to register. If not provided, a new node will be created.
         #[arg(long, value_name = "NODE_ID")]
         node_id: Option<u64>,
+
+        /// Custom orchestrator URL (overrides environment setting). Repeat to fail over between
+        /// several orchestrators, in order of preference.
+        #[arg(long = "orchestrator-url", value_name = "URL")]
+        orchestrator_url: Vec<String>,
     },
     /// Clear the node configuration and logout.
     Logout,
-    /// Hidden command for subprocess proof generation
-    #[command(hide = true, name = "prove-fib-subprocess")]
-    ProveFibSubprocess {
-        /// Serialized inputs blob
+    /// Inspect or drain proofs waiting to be accepted by the orchestrator.
+    Spool {
+        #[command(subcommand)]
+        command: SpoolCommand,
+    },
+    /// Show, rotate, export or import node signing keys.
+    Keys {
+        #[command(subcommand)]
+        command: KeysCommand,
+    },
+    /// List the nodes registered to the configured wallet.
+    Nodes {
+        #[command(subcommand)]
+        command: NodesCommand,
+    },
+    /// Show the configured user, wallet and nodes.
+    Whoami {
+        /// Custom orchestrator URL (overrides environment setting). Repeat to fail over between
+        /// several orchestrators, in order of preference.
+        #[arg(long = "orchestrator-url", value_name = "URL")]
+        orchestrator_url: Vec<String>,
+    },
+    /// List or verify the cached guest program ELFs.
+    Programs {
+        #[command(subcommand)]
+        command: ProgramsCommand,
+    },
+    /// Prove one input locally, without an orchestrator, and write the proof to a file.
+    Prove {
+        /// Guest program to prove
+        #[arg(long = "program-id", default_value = "fib_input_initial")]
+        program_id: String,
+        /// Public input: comma-separated u32 values (e.g. 1000,1,1) or raw bytes in hex
+        #[arg(long, value_name = "INPUT")]
+        input: String,
+        /// File to write the postcard-encoded proof to
+        #[arg(long, short, value_name = "PATH")]
+        output: std::path::PathBuf,
+    },
+    /// Verify a proof file against the input it was generated for.
+    Verify {
+        /// Guest program the proof is of
+        #[arg(long = "program-id", default_value = "fib_input_initial")]
+        program_id: String,
+        /// Public input: comma-separated u32 values (e.g. 1000,1,1) or raw bytes in hex
+        #[arg(long, value_name = "INPUT")]
+        input: String,
+        /// Postcard-encoded proof file
+        #[arg(long, value_name = "PATH")]
+        proof: std::path::PathBuf,
+    },
+    /// Show the size of a proof file and the hash a node would submit for it.
+    InspectProof {
+        /// Postcard-encoded proof file
+        #[arg(value_name = "PATH")]
+        proof: std::path::PathBuf,
+    },
+    /// Measure proving time and memory at each difficulty, and save the recommended difficulty
+    /// for `start` to begin at.
+    Benchmark {
+        /// Number of proofs to run at once, as with `start`. Capped at the number of CPU cores.
+        #[arg(long = "max-threads", value_name = "MAX_THREADS")]
+        max_threads: Option<u32>,
+
+        /// Highest difficulty to benchmark
+        #[arg(long = "max-difficulty", value_name = "DIFFICULTY")]
+        max_difficulty: Option<String>,
+    },
+    /// Serve a local orchestrator with a fixed task queue, for offline end-to-end runs.
+    DevOrchestrator {
+        /// Port to listen on (127.0.0.1)
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+
+        /// Number of tasks in the queue
+        #[arg(long, default_value_t = 10)]
+        tasks: usize,
+
+        /// Number of inputs per task
+        #[arg(long = "inputs-per-task", default_value_t = 1)]
+        inputs_per_task: usize,
+    },
+    /// Hidden command for a persistent prover subprocess, serving jobs on stdin and stdout
+    #[command(hide = true, name = "prover-worker")]
+    ProverWorker {
+        /// Address space cap (MiB)
+        #[arg(long = "memory-limit-mb")]
+        memory_limit_mb: Option<u64>,
+        /// CPU time cap per job (seconds)
+        #[arg(long = "cpu-limit-secs")]
+        cpu_limit_secs: Option<u64>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SpoolCommand {
+    /// List spooled proofs
+    List,
+    /// Submit all spooled proofs now
+    Flush,
+    /// Delete all spooled proofs without submitting them
+    Purge,
+}
+
+#[derive(Subcommand)]
+enum ProgramsCommand {
+    /// List pinned guest program ELFs and whether each is available
+    List,
+    /// Check every cached ELF against its pinned hashes
+    Verify,
+}
+
+#[derive(Subcommand)]
+enum NodesCommand {
+    /// List every node registered to the configured wallet. Locally active nodes are marked *
+    List {
+        /// Print nodes as JSON instead of a table
         #[arg(long)]
-        inputs: String,
+        json: bool,
+
+        /// Custom orchestrator URL (overrides environment setting). Repeat to fail over between
+        /// several orchestrators, in order of preference.
+        #[arg(long = "orchestrator-url", value_name = "URL")]
+        orchestrator_url: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum KeysCommand {
+    /// Show stored public keys
+    Show {
+        /// Only show the key for this node
+        #[arg(long, value_name = "NODE_ID")]
+        node_id: Option<u64>,
+    },
+    /// Replace a node's signing key with a new one
+    Rotate {
+        /// Node to rotate, defaults to the node in the config file
+        #[arg(long, value_name = "NODE_ID")]
+        node_id: Option<u64>,
+    },
+    /// Export a node's key file
+    Export {
+        /// Node to export, defaults to the node in the config file
+        #[arg(long, value_name = "NODE_ID")]
+        node_id: Option<u64>,
+
+        /// File to write the key to. Prints to stdout if not provided.
+        #[arg(long, value_name = "PATH")]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Import a key file exported on another machine
+    Import {
+        /// Key file to import
+        #[arg(value_name = "PATH")]
+        input: std::path::PathBuf,
+
+        /// Store the key under this node ID instead of the one in the file
+        #[arg(long, value_name = "NODE_ID")]
+        node_id: Option<u64>,
     },
 }
 
@@ -167,7 +424,93 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let config_path = get_config_path()?;
 
     let args = Args::parse();
-    match args.command {
+
+    let file_config = Config::load_or_default(&config_path).map_err(|e| {
+        format!(
+            "Failed to read config file {}: {}",
+            config_path.display(),
+            e
+        )
+    })?;
+
+    // An ordered list of orchestrator URLs to fail over between may come from the environment
+    // or the config file, in that order of precedence. `--orchestrator-url` overrides both.
+    let environment = std::env::var("NEXUS_ORCHESTRATOR_URLS")
+        .ok()
+        .and_then(|urls| Environment::from_url_list(&urls))
+        .or_else(|| Environment::custom(file_config.orchestrator_urls.clone()))
+        .unwrap_or(environment);
+
+    // Proxy and CA settings apply to every HTTP client created from here on
+    // Display rather than Debug-format setup errors, which main would otherwise print
+    HttpSettings::new(args.proxy.as_deref(), &args.ca_bundle)
+        .map_err(|e| e.to_string())?
+        .install();
+
+    // Compression and traffic recording or replay apply to every orchestrator client
+    let connector = Connector {
+        content_encoding: args.compress,
+        recorder: match &args.record_traffic {
+            Some(dir) => Some(Arc::new(
+                TrafficRecorder::new(dir).map_err(|e| e.to_string())?,
+            )),
+            None => None,
+        },
+        replay: match &args.replay_traffic {
+            Some(dir) => Some(Arc::new(
+                ReplayOrchestrator::load(dir, environment.clone()).map_err(|e| e.to_string())?,
+            )),
+            None => None,
+        },
+    };
+
+    // Guest program ELFs are resolved from the verified cache next to the config file
+    let programs = ProgramCache::new(Some(get_programs_dir(&config_path)))
+        .with_pins(file_config.programs.pins)
+        .with_url(args.program_url.or(file_config.programs.url));
+
+    // Prover subprocesses only load ELFs and prove, so they start before the pools and caches
+    // of their parent are set up
+    let command = match args.command {
+        Command::ProverWorker {
+            memory_limit_mb,
+            cpu_limit_secs,
+        } => {
+            let limits = ProverLimits {
+                memory_mb: memory_limit_mb,
+                cpu_secs: cpu_limit_secs,
+                // Enforced by the parent
+                timeout_secs: None,
+            };
+            if let Err(e) = ProvingEngine::serve_subprocess(limits, programs) {
+                eprintln!("{}", e);
+                exit(consts::cli_consts::SUBPROCESS_INTERNAL_ERROR_CODE);
+            }
+            return Ok(());
+        }
+        command => command,
+    };
+
+    // ELFs are proven in subprocesses under the configured limits, and verified on dedicated
+    // threads under the verification policy. Proofs of repeated inputs are kept in a cache next
+    // to the config file too, and proofs of the inputs of unfinished tasks are checkpointed there.
+    let proving = ProvingContext {
+        pool: Arc::new(ProverPool::new(
+            PoolConfig::default().with_limits(file_config.prover_limits),
+        )),
+        verifier: Arc::new(VerificationPool::new(
+            args.verification.unwrap_or(file_config.verification),
+            consts::cli_consts::verification::THREADS,
+        )),
+        programs: Arc::new(programs),
+        proof_cache: Arc::new(
+            ProofCache::new(Some(get_proof_cache_dir(&config_path)))
+                .with_max_bytes(file_config.proof_cache.max_bytes()),
+        ),
+        checkpoints: Arc::new(TaskCheckpoints::new(Some(get_checkpoint_dir(&config_path)))),
+    };
+
+    match command {
         Command::Start {
             node_id,
             headless,
@@ -178,14 +521,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
             max_tasks,
             max_difficulty,
         } => {
-            // If a custom orchestrator URL is provided, create a custom environment
-            let final_environment = if let Some(url) = orchestrator_url {
-                Environment::Custom {
-                    orchestrator_url: url,
-                }
-            } else {
-                environment
-            };
+            // If custom orchestrator URLs are provided, create a custom environment
+            let final_environment = Environment::custom(orchestrator_url).unwrap_or(environment);
             start(
                 node_id,
                 final_environment,
@@ -196,6 +533,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 with_background,
                 max_tasks,
                 max_difficulty,
+                connector,
+                proving,
             )
             .await
         }
@@ -203,47 +542,123 @@ async fn main() -> Result<(), Box<dyn Error>> {
             print_cmd_info!("Logging out", "Clearing node configuration file...");
             Config::clear_node_config(&config_path).map_err(Into::into)
         }
-        Command::RegisterUser { wallet_address } => {
+        Command::Spool { command } => {
+            let spool = Spool::new(get_spool_dir(&config_path));
+            match command {
+                SpoolCommand::List => list_spool(&spool),
+                SpoolCommand::Flush => {
+                    let key_store = KeyStore::new(get_keys_dir(&config_path));
+                    flush_spool(&spool, &key_store, &connector).await
+                }
+                SpoolCommand::Purge => purge_spool(&spool),
+            }
+        }
+        Command::Keys { command } => {
+            let store = KeyStore::new(get_keys_dir(&config_path));
+            match command {
+                KeysCommand::Show { node_id } => show_keys(&store, node_id),
+                KeysCommand::Rotate { node_id } => rotate_key(&store, node_id, &config_path),
+                KeysCommand::Export { node_id, output } => {
+                    export_key(&store, node_id, output.as_deref(), &config_path)
+                }
+                KeysCommand::Import { input, node_id } => import_key(&store, &input, node_id),
+            }
+        }
+        Command::Nodes { command } => match command {
+            NodesCommand::List {
+                json,
+                orchestrator_url,
+            } => {
+                let environment = Environment::custom(orchestrator_url).unwrap_or(environment);
+                let orchestrator = connector.connect(environment)?;
+                list_nodes(orchestrator.as_ref(), &config_path, json).await
+            }
+        },
+        Command::Whoami { orchestrator_url } => {
+            let environment = Environment::custom(orchestrator_url).unwrap_or(environment);
+            let orchestrator = connector.connect(environment)?;
+            whoami(orchestrator.as_ref(), &config_path).await
+        }
+        Command::RegisterUser {
+            wallet_address,
+            orchestrator_url,
+        } => {
             print_cmd_info!("Registering user", "Wallet address: {}", wallet_address);
-            let orchestrator = Box::new(OrchestratorClient::new(environment));
+            let environment = Environment::custom(orchestrator_url).unwrap_or(environment);
+            let orchestrator = Box::new(connector.connect(environment)?);
             register_user(&wallet_address, &config_path, orchestrator).await
         }
-        Command::RegisterNode { node_id } => {
-            let orchestrator = Box::new(OrchestratorClient::new(environment));
-            register_node(node_id, &config_path, orchestrator).await
+        Command::RegisterNode {
+            node_id,
+            orchestrator_url,
+        } => {
+            let environment = Environment::custom(orchestrator_url).unwrap_or(environment);
+            let orchestrator = Box::new(connector.connect(environment)?);
+            let interactive = std::io::stdin().is_terminal();
+            register_node(node_id, &config_path, orchestrator, interactive).await
         }
-        Command::ProveFibSubprocess { inputs } => {
-            let inputs: (u32, u32, u32) = serde_json::from_str(&inputs)?;
-            match ProvingEngine::prove_fib_subprocess(&inputs) {
-                Ok(proof) => {
-                    let bytes = to_allocvec(&proof)?;
-                    let mut out = std::io::stdout().lock();
-                    out.write_all(&bytes)?;
-                    Ok(())
-                }
-                Err(e) => {
-                    eprintln!("{}", e);
-                    exit(consts::cli_consts::SUBPROCESS_INTERNAL_ERROR_CODE);
-                }
-            }
+        Command::DevOrchestrator {
+            port,
+            tasks,
+            inputs_per_task,
+        } => {
+            let config = DevOrchestratorConfig {
+                num_tasks: tasks,
+                inputs_per_task,
+                ..Default::default()
+            };
+            dev_server::run(port, config).await
         }
+        Command::Programs { command } => match command {
+            ProgramsCommand::List => list_programs(&proving.programs),
+            ProgramsCommand::Verify => verify_programs(&proving.programs),
+        },
+        Command::Prove {
+            program_id,
+            input,
+            output,
+        } => prove_offline(&proving, &program_id, &parse_input(&input)?, &output).await,
+        Command::Verify {
+            program_id,
+            input,
+            proof,
+        } => verify_offline(&proving, &program_id, &parse_input(&input)?, &proof).await,
+        Command::InspectProof { proof } => inspect_proof(&proof),
+        Command::Benchmark {
+            max_threads,
+            max_difficulty,
+        } => {
+            let max_difficulty = parse_max_difficulty(max_difficulty.as_deref());
+            let num_workers = resolve_num_workers(max_threads, false);
+            run_benchmark(
+                &proving,
+                &get_benchmark_path(&config_path),
+                num_workers,
+                max_difficulty,
+            )
+            .await
+        }
+        Command::ProverWorker { .. } => unreachable!("prover workers are served above"),
     }
 }
 
 /// Starts the Nexus CLI application.
 ///
 /// # Arguments
-/// * `node_id` - This client's unique identifier, if available.
+/// * `node_ids` - Node IDs to run; falls back to the config file when empty.
 /// * `env` - The environment to connect to.
 /// * `config_path` - Path to the configuration file.
 /// * `headless` - If true, runs without the terminal UI.
-/// * `max_threads` - Optional maximum number of threads to use for proving.
+/// * `max_threads` - Optional maximum number of threads to use for proving; defaults to the
+///   thread count the last benchmark recommended.
 /// * `check_mem` - Whether to check risky memory usage.
 /// * `with_background` - Whether to use the alternate TUI background color.
 /// * `max_tasks` - Optional maximum number of tasks to prove.
+/// * `connector` - Creates orchestrator clients with the session's request settings.
+/// * `proving` - Prover subprocesses, caches and verifier shared by the workers.
 #[allow(clippy::too_many_arguments)]
 async fn start(
-    node_id: Option<u64>,
+    node_ids: Vec<u64>,
     env: Environment,
     config_path: std::path::PathBuf,
     headless: bool,
@@ -252,41 +667,61 @@ async fn start(
     with_background: bool,
     max_tasks: Option<u32>,
     max_difficulty: Option<String>,
+    connector: Connector,
+    proving: ProvingContext,
 ) -> Result<(), Box<dyn Error>> {
-    // 1. Version checking (will internally perform country detection without race)
-    validate_version_requirements().await?;
+    // 1. Version checking (will internally perform country detection without race).
+    // Skipped for a local orchestrator or a replay so that offline runs don't depend on the network.
+    if !env.is_local() && connector.replay.is_none() {
+        validate_version_requirements().await?;
+    }
 
     // 2. Configuration resolution
-    let orchestrator_client = OrchestratorClient::new(env.clone());
-    let config = Config::resolve(node_id, &config_path, &orchestrator_client).await?;
+    let orchestrator_client = connector.connect(env.clone())?;
+    let configs = Config::resolve_nodes(&node_ids, &config_path, &orchestrator_client).await?;
 
     // 3. Session setup (authenticated worker only)
     // Parse and validate difficulty override (case-insensitive)
-    let max_difficulty_parsed = if let Some(difficulty_str) = &max_difficulty {
-        match validate_difficulty(difficulty_str) {
-            Some(difficulty) => Some(difficulty),
-            None => {
-                eprintln!(
-                    "Error: Invalid difficulty level '{}'",
-                    difficulty_str.trim()
-                );
-                print_available_difficulties();
-                eprintln!();
-                eprintln!("Note: Difficulty levels are case-insensitive.");
-                std::process::exit(1);
-            }
+    let max_difficulty_parsed = parse_max_difficulty(max_difficulty.as_deref());
+
+    // Start at the (capped) difficulty and thread count the last benchmark recommended, if
+    // there was one. Explicit flags take precedence.
+    let benchmark = BenchmarkReport::load(&get_benchmark_path(&config_path)).ok();
+    let initial_difficulty = benchmark.as_ref().map(BenchmarkReport::start_difficulty);
+    if let (Some(difficulty), None) = (initial_difficulty, max_difficulty_parsed) {
+        print_cmd_info!(
+            "Benchmark",
+            "Starting at {} difficulty",
+            difficulty.as_str_name()
+        );
+    }
+    let max_threads = match (max_threads, &benchmark) {
+        (None, Some(report)) => {
+            print_cmd_info!(
+                "Benchmark",
+                "Proving on {} threads",
+                report.recommended_threads
+            );
+            Some(report.recommended_threads as u32)
         }
-    } else {
-        None
+        _ => max_threads,
     };
 
+    // Checkpoints of tasks that were never fetched again are only of use until they expire
+    let _ = proving.checkpoints.remove_expired();
+
     let session = setup_session(
-        config,
+        configs,
         env,
         check_mem,
         max_threads,
         max_tasks,
         max_difficulty_parsed,
+        initial_difficulty,
+        Some(get_spool_dir(&config_path)),
+        KeyStore::new(get_keys_dir(&config_path)),
+        &connector,
+        proving,
     )
     .await?;
 
@@ -0,0 +1,337 @@
+//! Circuit breaker for orchestrator requests
+//!
+//! After enough consecutive failures that suggest the orchestrator is down, the breaker opens
+//! and requests stop going out until the open period has passed. It then half-opens and lets
+//! one request at a time probe the orchestrator: enough successful probes close it again, any
+//! failure reopens it. A probe the orchestrator rejects shows that it is up, but does not count
+//! towards closing.
+//! One breaker is shared by the fetchers and submitters of every node in a session.
+
+use crate::network::retry_policy::ErrorClass;
+use crate::orchestrator::error::OrchestratorError;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// Circuit breaker thresholds, configurable under `circuit_breaker` in the config file
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failed requests that open the breaker
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// How long the breaker stays open before requests may probe the orchestrator again
+    /// (milliseconds)
+    #[serde(default = "default_open_duration_ms")]
+    pub open_duration_ms: u64,
+    /// Successful probes needed to close a half-open breaker
+    #[serde(default = "default_success_threshold")]
+    pub success_threshold: u32,
+}
+
+fn default_failure_threshold() -> u32 {
+    5
+}
+
+fn default_open_duration_ms() -> u64 {
+    30_000
+}
+
+fn default_success_threshold() -> u32 {
+    1
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: default_failure_threshold(),
+            open_duration_ms: default_open_duration_ms(),
+            success_threshold: default_success_threshold(),
+        }
+    }
+}
+
+impl CircuitBreakerConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// State of the circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Requests go out as normal
+    Closed,
+    /// The orchestrator looks unavailable; no requests go out until `next_probe`
+    Open { next_probe: Instant },
+    /// One request at a time goes out to probe whether the orchestrator is back
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Counters {
+    consecutive_failures: u32,
+    probe_successes: u32,
+    /// When the probe in flight went out, while half-open
+    probe_started: Option<Instant>,
+}
+
+/// Circuit breaker shared by every orchestrator request of a session
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    counters: Mutex<Counters>,
+    state: watch::Sender<BreakerState>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            counters: Mutex::new(Counters {
+                consecutive_failures: 0,
+                probe_successes: 0,
+                probe_started: None,
+            }),
+            state: watch::Sender::new(BreakerState::Closed),
+        }
+    }
+
+    /// Current state, moving from open to half-open once the open period has passed.
+    pub fn state(&self) -> BreakerState {
+        self.state_at(Instant::now())
+    }
+
+    fn state_at(&self, now: Instant) -> BreakerState {
+        let state = *self.state.borrow();
+        match state {
+            BreakerState::Open { next_probe } if now >= next_probe => {
+                self.set_state(BreakerState::HalfOpen);
+                BreakerState::HalfOpen
+            }
+            state => state,
+        }
+    }
+
+    /// Watch state changes, e.g. to report them as events.
+    pub fn subscribe(&self) -> watch::Receiver<BreakerState> {
+        self.state.subscribe()
+    }
+
+    /// `Ok` if a request may go out now, otherwise the time until one may.
+    pub fn check(&self) -> Result<(), Duration> {
+        self.check_at(&self.counters(), Instant::now())
+    }
+
+    /// Like [`Self::check`], but also claims the probe for the request about to go out while
+    /// the breaker is half-open.
+    pub fn admit(&self) -> Result<(), Duration> {
+        self.admit_at(Instant::now())
+    }
+
+    fn admit_at(&self, now: Instant) -> Result<(), Duration> {
+        let mut counters = self.counters();
+        self.check_at(&counters, now)?;
+        if self.state_at(now) == BreakerState::HalfOpen {
+            counters.probe_started = Some(now);
+        }
+        Ok(())
+    }
+
+    fn check_at(&self, counters: &Counters, now: Instant) -> Result<(), Duration> {
+        match self.state_at(now) {
+            BreakerState::Open { next_probe } => Err(next_probe.saturating_duration_since(now)),
+            BreakerState::Closed => Ok(()),
+            // A probe that has not reported back within the open period is presumed lost
+            BreakerState::HalfOpen => match counters.probe_started {
+                Some(started) => {
+                    let deadline = started + Duration::from_millis(self.config.open_duration_ms);
+                    if now < deadline {
+                        Err(deadline - now)
+                    } else {
+                        Ok(())
+                    }
+                }
+                None => Ok(()),
+            },
+        }
+    }
+
+    /// Records a request the orchestrator answered successfully.
+    pub fn record_success(&self) {
+        let mut counters = self.counters();
+        counters.consecutive_failures = 0;
+        counters.probe_started = None;
+        if self.state() == BreakerState::HalfOpen {
+            counters.probe_successes += 1;
+            if counters.probe_successes >= self.config.success_threshold {
+                counters.probe_successes = 0;
+                self.set_state(BreakerState::Closed);
+            }
+        }
+    }
+
+    /// Records a failed request. Only failures suggesting the orchestrator is unavailable
+    /// count towards opening the breaker; rejected requests show that it is up, but only
+    /// successful ones count towards closing it.
+    pub fn record_failure(&self, error: &OrchestratorError) {
+        if !Self::is_unavailable(error) {
+            let mut counters = self.counters();
+            counters.consecutive_failures = 0;
+            counters.probe_started = None;
+            return;
+        }
+        self.record_unavailable_at(Instant::now());
+    }
+
+    fn record_unavailable_at(&self, now: Instant) {
+        let mut counters = self.counters();
+        counters.consecutive_failures += 1;
+        counters.probe_successes = 0;
+        counters.probe_started = None;
+        let trip = match self.state_at(now) {
+            BreakerState::Closed => counters.consecutive_failures >= self.config.failure_threshold,
+            BreakerState::HalfOpen => true,
+            BreakerState::Open { .. } => false,
+        };
+        if trip {
+            let open_for = Duration::from_millis(self.config.open_duration_ms);
+            self.set_state(BreakerState::Open {
+                next_probe: now + open_for,
+            });
+        }
+    }
+
+    fn is_unavailable(error: &OrchestratorError) -> bool {
+        matches!(
+            ErrorClass::of(error),
+            ErrorClass::Network | ErrorClass::Timeout | ErrorClass::ServerError
+        )
+    }
+
+    fn counters(&self) -> std::sync::MutexGuard<'_, Counters> {
+        self.counters
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn set_state(&self, state: BreakerState) {
+        self.state.send_if_modified(|current| {
+            let changed = *current != state;
+            *current = state;
+            changed
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn breaker(failure_threshold: u32, success_threshold: u32) -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold,
+            open_duration_ms: 1_000,
+            success_threshold,
+        })
+    }
+
+    fn http_error(status: u16) -> OrchestratorError {
+        OrchestratorError::Http {
+            status,
+            message: String::new(),
+            headers: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let breaker = breaker(3, 1);
+        breaker.record_failure(&http_error(503));
+        breaker.record_failure(&http_error(503));
+        assert_eq!(breaker.state(), BreakerState::Closed);
+
+        // A success in between resets the count
+        breaker.record_success();
+        breaker.record_failure(&http_error(503));
+        breaker.record_failure(&http_error(503));
+        assert!(breaker.check().is_ok());
+
+        breaker.record_failure(&http_error(502));
+        assert!(matches!(breaker.state(), BreakerState::Open { .. }));
+        let wait = breaker.check().unwrap_err();
+        assert!(wait <= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_rejections_do_not_open() {
+        let breaker = breaker(1, 1);
+        breaker.record_failure(&http_error(400));
+        breaker.record_failure(&http_error(429));
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+
+    #[test]
+    fn test_half_open_probes_close_or_reopen() {
+        let breaker = breaker(1, 2);
+        let start = Instant::now();
+        breaker.record_unavailable_at(start);
+        assert!(matches!(breaker.state_at(start), BreakerState::Open { .. }));
+
+        let after_open = start + Duration::from_secs(2);
+        assert_eq!(breaker.state_at(after_open), BreakerState::HalfOpen);
+
+        // A failed probe reopens the breaker
+        breaker.record_unavailable_at(after_open);
+        assert_eq!(
+            breaker.state_at(after_open),
+            BreakerState::Open {
+                next_probe: after_open + Duration::from_secs(1)
+            }
+        );
+
+        // Two successful probes close it
+        let later = after_open + Duration::from_secs(2);
+        assert_eq!(breaker.state_at(later), BreakerState::HalfOpen);
+        breaker.record_success();
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+        breaker.record_success();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+
+    #[test]
+    fn test_half_open_admits_one_probe_at_a_time() {
+        let breaker = breaker(1, 1);
+        let start = Instant::now();
+        breaker.record_unavailable_at(start);
+
+        let after_open = start + Duration::from_secs(2);
+        assert!(breaker.admit_at(after_open).is_ok());
+        let wait = breaker.admit_at(after_open).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(1));
+
+        // A rejected probe frees the slot without closing the breaker
+        breaker.record_failure(&http_error(404));
+        assert_eq!(breaker.state_at(after_open), BreakerState::HalfOpen);
+        assert!(breaker.admit_at(after_open).is_ok());
+
+        // A probe that never reports back is given up on after the open period
+        let much_later = after_open + Duration::from_secs(1);
+        assert!(breaker.admit_at(much_later).is_ok());
+        breaker.record_success();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+
+    #[test]
+    fn test_subscribers_see_transitions() {
+        let breaker = breaker(1, 1);
+        let mut states = breaker.subscribe();
+        breaker.record_failure(&http_error(500));
+        assert!(states.has_changed().unwrap());
+        assert!(matches!(
+            *states.borrow_and_update(),
+            BreakerState::Open { .. }
+        ));
+    }
+}
@@ -1,13 +1,16 @@
 //! Network client with built-in retry and error handling
 
+use super::circuit_breaker::CircuitBreaker;
 use super::error_handler::ErrorHandler;
 use super::request_timer::RequestTimer;
+use super::retry_policy::RetryPolicy;
 use crate::consts::cli_consts;
 use crate::logging::LogLevel;
 use crate::orchestrator::Orchestrator;
 use crate::orchestrator::error::OrchestratorError;
 use ed25519_dalek::{SigningKey, VerifyingKey};
 
+use std::sync::Arc;
 use std::{cmp::min, time::Duration};
 
 /// Proof submission data grouped by business concern
@@ -53,19 +56,81 @@ impl ProofSubmission {
 pub struct NetworkClient {
     error_handler: ErrorHandler,
     request_timer: RequestTimer,
-    max_retries: u32,
+    retry_policy: RetryPolicy,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
 }
 
 impl NetworkClient {
-    pub fn new(request_timer: RequestTimer, max_retries: u32) -> Self {
+    pub fn new(request_timer: RequestTimer, retry_policy: RetryPolicy) -> Self {
         Self {
             error_handler: ErrorHandler::new(),
             request_timer,
-            max_retries,
+            retry_policy,
+            circuit_breaker: None,
         }
     }
 
-    /// Fetch a task with automatic retry and server-controlled timing
+    /// Guard requests with a circuit breaker, usually shared with other clients.
+    pub fn with_circuit_breaker(mut self, circuit_breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// Time until the circuit breaker lets requests out again, if it is holding them back.
+    pub fn unavailable_for(&self) -> Option<Duration> {
+        self.circuit_breaker
+            .as_ref()
+            .and_then(|breaker| breaker.check().err())
+    }
+
+    /// Claims the circuit breaker's permission to send a request now, or fails with the time
+    /// until it allows one.
+    fn admit(&self) -> Result<(), OrchestratorError> {
+        match &self.circuit_breaker {
+            Some(breaker) => breaker
+                .admit()
+                .map_err(|retry_in| OrchestratorError::Unavailable { retry_in }),
+            None => Ok(()),
+        }
+    }
+
+    fn record_success(&mut self) {
+        self.request_timer.record_success();
+        if let Some(breaker) = &self.circuit_breaker {
+            breaker.record_success();
+        }
+    }
+
+    /// Record a failed attempt with the request timer and decide whether to retry.
+    /// Returns the delay to wait before the next attempt, or None to give up. A server delay
+    /// longer than the policy's `max_server_delay_ms` ends the loop: the fetch stage then waits
+    /// on the request timer, and a submission stays in the spool.
+    fn on_failure(
+        &mut self,
+        error: &OrchestratorError,
+        attempts: u32,
+        previous_delay: Duration,
+    ) -> Option<Duration> {
+        if let Some(breaker) = &self.circuit_breaker {
+            breaker.record_failure(error);
+        }
+
+        // The request timer holds back the next request for the full server delay, even
+        // when it is too long for the retry policy to wait for in the loop
+        let server_retry_delay = error.server_retry_delay().map(|delay| {
+            min(
+                delay + cli_consts::rate_limiting::extra_retry_delay(),
+                Duration::from_secs(60 * 10),
+            )
+        });
+        self.request_timer.record_failure(server_retry_delay);
+
+        self.retry_policy
+            .retry_delay(error, attempts, previous_delay)
+    }
+
+    /// Fetch a task with automatic retry and server-controlled timing.
+    /// Fails fast with `OrchestratorError::Unavailable` while the circuit breaker is open.
     pub async fn fetch_task(
         &mut self,
         orchestrator: &dyn Orchestrator,
@@ -74,8 +139,11 @@ impl NetworkClient {
         max_difficulty: crate::nexus_orchestrator::TaskDifficulty,
     ) -> Result<crate::orchestrator::client::ProofTaskResult, OrchestratorError> {
         let mut attempts = 0;
+        let mut retry_delay = Duration::ZERO;
 
         loop {
+            self.admit()?;
+
             // Make the request
             // Default to Large; callers can adapt or override upstream
             match orchestrator
@@ -83,27 +151,19 @@ impl NetworkClient {
                 .await
             {
                 Ok(proof_task_result) => {
-                    self.request_timer.record_success();
+                    self.record_success();
                     return Ok(proof_task_result);
                 }
                 Err(e) => {
                     attempts += 1;
 
-                    // Get server-provided retry delay and record failure
-                    let server_retry_delay = e
-                        .get_retry_after_seconds()
-                        .map(|secs| Duration::from_secs(secs as u64))
-                        .map(|delay| {
-                            min(
-                                delay + cli_consts::rate_limiting::extra_retry_delay(),
-                                Duration::from_secs(60 * 10),
-                            )
-                        });
-                    self.request_timer.record_failure(server_retry_delay);
-
-                    // Check if we should retry
-                    if attempts >= self.max_retries || !self.error_handler.should_retry(&e) {
-                        return Err(e);
+                    // Check if we should retry, and wait before doing so
+                    match self.on_failure(&e, attempts, retry_delay) {
+                        Some(delay) => {
+                            retry_delay = delay;
+                            tokio::time::sleep(delay).await;
+                        }
+                        None => return Err(e),
                     }
                 }
             }
@@ -111,7 +171,9 @@ impl NetworkClient {
     }
 
     /// Submit a proof with automatic retry and server-controlled timing
-    /// Returns Ok(attempts) on success or Err((error, attempts)) on failure
+    /// Returns Ok(attempts) on success or Err((error, attempts)) on failure.
+    /// Fails with `OrchestratorError::Unavailable` while the circuit breaker holds requests
+    /// back, leaving the proof in the spool for a later replay.
     pub async fn submit_proof(
         &mut self,
         orchestrator: &dyn Orchestrator,
@@ -120,8 +182,13 @@ impl NetworkClient {
         num_provers: usize,
     ) -> Result<u32, (OrchestratorError, u32)> {
         let mut attempts = 0;
+        let mut retry_delay = Duration::ZERO;
 
         loop {
+            if let Err(e) = self.admit() {
+                return Err((e, attempts));
+            }
+
             // Make the request
             match orchestrator
                 .submit_proof(
@@ -138,27 +205,19 @@ impl NetworkClient {
             {
                 Ok(()) => {
                     attempts += 1;
-                    self.request_timer.record_success();
+                    self.record_success();
                     return Ok(attempts);
                 }
                 Err(e) => {
                     attempts += 1;
 
-                    // Get server-provided retry delay and record failure
-                    let server_retry_delay = e
-                        .get_retry_after_seconds()
-                        .map(|secs| Duration::from_secs(secs as u64))
-                        .map(|delay| {
-                            min(
-                                delay + cli_consts::rate_limiting::extra_retry_delay(),
-                                Duration::from_secs(60 * 10),
-                            )
-                        });
-                    self.request_timer.record_failure(server_retry_delay);
-
-                    // Check if we should retry
-                    if attempts >= self.max_retries || !self.error_handler.should_retry(&e) {
-                        return Err((e, attempts));
+                    // Check if we should retry, and wait before doing so
+                    match self.on_failure(&e, attempts, retry_delay) {
+                        Some(delay) => {
+                            retry_delay = delay;
+                            tokio::time::sleep(delay).await;
+                        }
+                        None => return Err((e, attempts)),
                     }
                 }
             }
@@ -170,6 +229,11 @@ impl NetworkClient {
         self.error_handler.classify_error(error)
     }
 
+    /// Whether a failed request was definitively rejected by the orchestrator
+    pub fn is_permanent_rejection(&self, error: &OrchestratorError) -> bool {
+        self.error_handler.is_permanent_rejection(error)
+    }
+
     /// Get a mutable reference to the request timer
     pub fn request_timer_mut(&mut self) -> &mut RequestTimer {
         &mut self.request_timer
@@ -16,8 +16,19 @@ impl ErrorHandler {
     pub fn classify_error(&self, error: &OrchestratorError) -> LogLevel {
         match error {
             // Rate limiting - low priority
+            OrchestratorError::RateLimited { .. } => LogLevel::Debug,
             OrchestratorError::Http { status, .. } if *status == 429 => LogLevel::Debug,
 
+            // Expired tasks - the proof is simply dropped
+            OrchestratorError::TaskExpired { .. } => LogLevel::Warn,
+
+            // Node, key or version no longer accepted - critical
+            OrchestratorError::NodeNotFound { .. }
+            | OrchestratorError::UserNotFound { .. }
+            | OrchestratorError::KeyRejected { .. }
+            | OrchestratorError::VersionRejected { .. }
+            | OrchestratorError::Banned { .. } => LogLevel::Error,
+
             // Server errors - temporary issues
             OrchestratorError::Http { status, .. } if (500..=599).contains(status) => {
                 LogLevel::Warn
@@ -35,25 +46,61 @@ impl ErrorHandler {
         }
     }
 
-    /// Determine if an error should trigger retry logic
-    pub fn should_retry(&self, error: &OrchestratorError) -> bool {
+    /// Determine if the orchestrator definitively rejected a request, so sending the same
+    /// request again later cannot succeed.
+    /// Rejections of the node, key or CLI version are not permanent: the request can succeed
+    /// once the user re-registers or upgrades, so spooled proofs are kept for that.
+    pub fn is_permanent_rejection(&self, error: &OrchestratorError) -> bool {
         match error {
-            // Retry on network/connection errors
-            OrchestratorError::Reqwest(_) => true,
-            OrchestratorError::Decode(_) => true,
-
-            // HTTP errors - check status code
             OrchestratorError::Http { status, .. } => {
-                match *status {
-                    // Don't retry client errors (except rate limiting)
-                    429 => false,      // Rate limiting - don't retry
-                    400..=499 => true, // Other client errors - should retry
-                    // Retry server errors
-                    500..=599 => true,
-                    // Don't retry other status codes
-                    _ => false,
-                }
+                // Timeouts and rate limiting are transient
+                (400..=499).contains(status) && !matches!(*status, 408 | 429)
             }
+            OrchestratorError::TaskExpired { .. } => true,
+            OrchestratorError::NodeNotFound { .. }
+            | OrchestratorError::UserNotFound { .. }
+            | OrchestratorError::KeyRejected { .. }
+            | OrchestratorError::VersionRejected { .. }
+            | OrchestratorError::Banned { .. }
+            | OrchestratorError::Decode(_)
+            | OrchestratorError::Reqwest(_)
+            | OrchestratorError::Unavailable { .. }
+            | OrchestratorError::RateLimited { .. } => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_only_expired_tasks_and_client_errors_are_permanent() {
+        let handler = ErrorHandler::new();
+        let http = |status| OrchestratorError::Http {
+            status,
+            message: String::new(),
+            headers: HashMap::new(),
+        };
+        let message = || "rejected".to_string();
+
+        assert!(
+            handler.is_permanent_rejection(&OrchestratorError::TaskExpired { message: message() })
+        );
+        assert!(handler.is_permanent_rejection(&http(400)));
+        assert!(!handler.is_permanent_rejection(&http(429)));
+        assert!(!handler.is_permanent_rejection(&http(503)));
+
+        // Recoverable after an upgrade or re-registration
+        for error in [
+            OrchestratorError::VersionRejected { message: message() },
+            OrchestratorError::KeyRejected { message: message() },
+            OrchestratorError::Banned { message: message() },
+            OrchestratorError::NodeNotFound { message: message() },
+            OrchestratorError::UserNotFound { message: message() },
+        ] {
+            assert!(!handler.is_permanent_rejection(&error), "{}", error);
         }
     }
 }
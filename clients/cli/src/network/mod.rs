@@ -1,6 +1,14 @@
+pub mod circuit_breaker;
 pub mod client;
 pub mod error_handler;
+pub mod http;
 pub mod request_timer;
+pub mod retry_policy;
+pub mod spool;
+pub mod upload;
 
+pub use circuit_breaker::{BreakerState, CircuitBreaker, CircuitBreakerConfig};
 pub use client::{NetworkClient, ProofSubmission};
 pub use request_timer::{RequestTimer, RequestTimerConfig};
+pub use retry_policy::{RetryPolicies, RetryPolicy};
+pub use spool::{Spool, SpoolEntry};
@@ -0,0 +1,465 @@
+//! Retry and backoff policies for orchestrator requests
+//!
+//! A [`RetryPolicy`] decides whether a failed request is retried and how long to wait first.
+//! Server-provided delays (`Retry-After`, `X-RateLimit-*`) always win over the local backoff,
+//! and local backoff can be tuned per error class. Fetching and submitting use separate
+//! policies, both of which can be overridden from the config file.
+
+use crate::consts::cli_consts::{proof_submission, task_fetching};
+use crate::orchestrator::error::OrchestratorError;
+use chrono::DateTime;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Longest server-requested delay to wait for inside a retry loop (milliseconds). Longer
+/// delays end the loop and are left to the request timer.
+const DEFAULT_MAX_SERVER_DELAY_MS: u64 = 60 * 1000; // 1 minute
+
+/// `X-RateLimit-Reset` values above this are Unix timestamps rather than delays (seconds)
+const RATE_LIMIT_RESET_EPOCH_THRESHOLD: u64 = 1_000_000_000;
+
+/// How long to wait between attempts
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum Backoff {
+    /// The same delay before every retry
+    Fixed { delay_ms: u64 },
+    /// `initial_ms * multiplier^(retry - 1)`, capped at `max_ms`
+    Exponential {
+        initial_ms: u64,
+        max_ms: u64,
+        multiplier: u32,
+    },
+    /// Random delay between `base_ms` and three times the previous delay, capped at `max_ms`.
+    /// Spreads out clients that fail at the same moment.
+    DecorrelatedJitter { base_ms: u64, max_ms: u64 },
+}
+
+impl Backoff {
+    /// Delay before retry number `retry` (starting at 1), given the previous delay.
+    pub fn next_delay(&self, retry: u32, previous: Duration, rng: &mut impl Rng) -> Duration {
+        match *self {
+            Backoff::Fixed { delay_ms } => Duration::from_millis(delay_ms),
+            Backoff::Exponential {
+                initial_ms,
+                max_ms,
+                multiplier,
+            } => {
+                let factor = (multiplier.max(1) as u64).saturating_pow(retry.saturating_sub(1));
+                Duration::from_millis(initial_ms.saturating_mul(factor).min(max_ms))
+            }
+            Backoff::DecorrelatedJitter { base_ms, max_ms } => {
+                let previous_ms = (previous.as_millis() as u64).max(base_ms);
+                let upper = previous_ms.saturating_mul(3).min(max_ms).max(base_ms);
+                Duration::from_millis(rng.gen_range(base_ms..=upper))
+            }
+        }
+    }
+}
+
+/// Broad categories of request failures that policies can treat differently
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    /// Connection failures and other transport errors
+    Network,
+    /// Request timed out, locally or with HTTP 408
+    Timeout,
+    /// Response could not be decoded
+    Decode,
+    /// HTTP 429
+    RateLimited,
+    /// HTTP 5xx
+    ServerError,
+    /// Other HTTP 4xx: the request itself was rejected
+    ClientError,
+}
+
+impl ErrorClass {
+    pub fn of(error: &OrchestratorError) -> Self {
+        match error {
+            OrchestratorError::Reqwest(e) if e.is_timeout() => ErrorClass::Timeout,
+            OrchestratorError::Reqwest(_) | OrchestratorError::Unavailable { .. } => {
+                ErrorClass::Network
+            }
+            OrchestratorError::Decode(_) => ErrorClass::Decode,
+            OrchestratorError::RateLimited { .. } => ErrorClass::RateLimited,
+            OrchestratorError::NodeNotFound { .. }
+            | OrchestratorError::UserNotFound { .. }
+            | OrchestratorError::VersionRejected { .. }
+            | OrchestratorError::KeyRejected { .. }
+            | OrchestratorError::TaskExpired { .. }
+            | OrchestratorError::Banned { .. } => ErrorClass::ClientError,
+            OrchestratorError::Http { status, .. } => match *status {
+                408 => ErrorClass::Timeout,
+                429 => ErrorClass::RateLimited,
+                500..=599 => ErrorClass::ServerError,
+                _ => ErrorClass::ClientError,
+            },
+        }
+    }
+
+    /// Whether the class is retried when a policy has no rule for it. Sending a rejected
+    /// request again cannot succeed.
+    fn retry_by_default(self) -> bool {
+        !matches!(self, ErrorClass::ClientError)
+    }
+}
+
+/// Overrides retry behaviour for one error class
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ErrorRule {
+    pub class: ErrorClass,
+    pub retry: bool,
+    /// Backoff for this class instead of the policy's default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backoff: Option<Backoff>,
+}
+
+/// Retry behaviour for one endpoint
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts including the first request
+    pub max_attempts: u32,
+    pub backoff: Backoff,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<ErrorRule>,
+    /// Longest server-requested delay to wait for before giving up (milliseconds)
+    #[serde(default = "default_max_server_delay_ms")]
+    pub max_server_delay_ms: u64,
+}
+
+fn default_max_server_delay_ms() -> u64 {
+    DEFAULT_MAX_SERVER_DELAY_MS
+}
+
+impl RetryPolicy {
+    /// Default policy for task fetching
+    pub fn fetch_default() -> Self {
+        Self {
+            max_attempts: task_fetching::MAX_RETRIES,
+            backoff: Backoff::DecorrelatedJitter {
+                base_ms: 2_000,
+                max_ms: 60_000,
+            },
+            rules: Vec::new(),
+            max_server_delay_ms: DEFAULT_MAX_SERVER_DELAY_MS,
+        }
+    }
+
+    /// Default policy for proof submission: more attempts since submissions are critical
+    pub fn submit_default() -> Self {
+        Self {
+            max_attempts: proof_submission::MAX_RETRIES,
+            backoff: Backoff::DecorrelatedJitter {
+                base_ms: proof_submission::INITIAL_BACKOFF_MS,
+                max_ms: 30_000,
+            },
+            rules: Vec::new(),
+            max_server_delay_ms: DEFAULT_MAX_SERVER_DELAY_MS,
+        }
+    }
+
+    fn rule(&self, class: ErrorClass) -> Option<&ErrorRule> {
+        self.rules.iter().find(|rule| rule.class == class)
+    }
+
+    /// Whether an error of this class is retried at all.
+    pub fn retries(&self, class: ErrorClass) -> bool {
+        self.rule(class)
+            .map(|rule| rule.retry)
+            .unwrap_or_else(|| class.retry_by_default())
+    }
+
+    /// Delay before retrying after `attempts` failed attempts, or `None` if the request should
+    /// not be retried. `previous` is the delay used before the last attempt.
+    pub fn retry_delay(
+        &self,
+        error: &OrchestratorError,
+        attempts: u32,
+        previous: Duration,
+    ) -> Option<Duration> {
+        let class = ErrorClass::of(error);
+        if attempts >= self.max_attempts || !self.retries(class) {
+            return None;
+        }
+
+        let mut rng = rand::thread_rng();
+        if let Some(server_delay) = error.server_retry_delay() {
+            if server_delay > Duration::from_millis(self.max_server_delay_ms) {
+                return None;
+            }
+            // Up to 10% extra so clients told to wait the same time don't return together
+            let jitter_ms = rng.gen_range(0..=server_delay.as_millis() as u64 / 10);
+            return Some(server_delay + Duration::from_millis(jitter_ms));
+        }
+
+        let backoff = self
+            .rule(class)
+            .and_then(|rule| rule.backoff)
+            .unwrap_or(self.backoff);
+        Some(backoff.next_delay(attempts, previous, &mut rng))
+    }
+}
+
+/// Retry policies per endpoint, configurable under `retry_policies` in the config file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicies {
+    #[serde(default = "RetryPolicy::fetch_default")]
+    pub fetch: RetryPolicy,
+    #[serde(default = "RetryPolicy::submit_default")]
+    pub submit: RetryPolicy,
+}
+
+impl Default for RetryPolicies {
+    fn default() -> Self {
+        Self {
+            fetch: RetryPolicy::fetch_default(),
+            submit: RetryPolicy::submit_default(),
+        }
+    }
+}
+
+impl RetryPolicies {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Parse a `Retry-After` value, given either as seconds or as an HTTP-date.
+pub fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    // HTTP-dates (IMF-fixdate, e.g. "Wed, 21 Oct 2015 07:28:00 GMT") are valid RFC 2822
+    let retry_at = DateTime::parse_from_rfc2822(value).ok()?;
+    let retry_at = UNIX_EPOCH + Duration::from_secs(retry_at.timestamp().try_into().ok()?);
+    Some(retry_at.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+/// Delay until the rate limit window resets, if the `X-RateLimit-*` headers say that no
+/// requests remain. `X-RateLimit-Reset` may be a delay in seconds or a Unix timestamp.
+pub fn parse_rate_limit_reset(
+    headers: &HashMap<String, String>,
+    now: SystemTime,
+) -> Option<Duration> {
+    let remaining = headers
+        .get("x-ratelimit-remaining")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    if remaining > 0 {
+        return None;
+    }
+    let reset = headers
+        .get("x-ratelimit-reset")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    if reset < RATE_LIMIT_RESET_EPOCH_THRESHOLD {
+        return Some(Duration::from_secs(reset));
+    }
+    let reset_at = UNIX_EPOCH + Duration::from_secs(reset);
+    Some(reset_at.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn http_error(status: u16, headers: &[(&str, &str)]) -> OrchestratorError {
+        OrchestratorError::Http {
+            status,
+            message: String::new(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_exponential_backoff_is_capped() {
+        let backoff = Backoff::Exponential {
+            initial_ms: 100,
+            max_ms: 1_000,
+            multiplier: 2,
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let delays: Vec<u64> = (1..=6)
+            .map(|retry| {
+                backoff
+                    .next_delay(retry, Duration::ZERO, &mut rng)
+                    .as_millis() as u64
+            })
+            .collect();
+        assert_eq!(delays, vec![100, 200, 400, 800, 1_000, 1_000]);
+    }
+
+    #[test]
+    fn test_decorrelated_jitter_stays_in_bounds() {
+        let backoff = Backoff::DecorrelatedJitter {
+            base_ms: 100,
+            max_ms: 5_000,
+        };
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut previous = Duration::ZERO;
+        let mut delays = Vec::new();
+        for retry in 1..=50 {
+            let delay = backoff.next_delay(retry, previous, &mut rng);
+            let upper = (previous.as_millis() as u64).max(100) * 3;
+            assert!(delay >= Duration::from_millis(100));
+            assert!(delay <= Duration::from_millis(upper.min(5_000)));
+            delays.push(delay);
+            previous = delay;
+        }
+        // Successive delays are not all the same
+        assert!(delays.windows(2).any(|pair| pair[0] != pair[1]));
+    }
+
+    #[test]
+    fn test_client_errors_are_not_retried_by_default() {
+        let policy = RetryPolicy::submit_default();
+        assert!(
+            policy
+                .retry_delay(&http_error(400, &[]), 1, Duration::ZERO)
+                .is_none()
+        );
+        assert!(
+            policy
+                .retry_delay(&http_error(503, &[]), 1, Duration::ZERO)
+                .is_some()
+        );
+        assert!(
+            policy
+                .retry_delay(&http_error(408, &[]), 1, Duration::ZERO)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_max_attempts() {
+        let policy = RetryPolicy::submit_default();
+        let error = http_error(503, &[]);
+        assert!(
+            policy
+                .retry_delay(&error, policy.max_attempts - 1, Duration::ZERO)
+                .is_some()
+        );
+        assert!(
+            policy
+                .retry_delay(&error, policy.max_attempts, Duration::ZERO)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_error_rule_overrides_class() {
+        let mut policy = RetryPolicy::fetch_default();
+        policy.rules.push(ErrorRule {
+            class: ErrorClass::ServerError,
+            retry: true,
+            backoff: Some(Backoff::Fixed { delay_ms: 7 }),
+        });
+        policy.rules.push(ErrorRule {
+            class: ErrorClass::RateLimited,
+            retry: false,
+            backoff: None,
+        });
+
+        assert_eq!(
+            policy.retry_delay(&http_error(502, &[]), 1, Duration::ZERO),
+            Some(Duration::from_millis(7))
+        );
+        assert!(
+            policy
+                .retry_delay(&http_error(429, &[]), 1, Duration::ZERO)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_server_delay_wins_over_backoff() {
+        let mut policy = RetryPolicy::fetch_default();
+        policy.backoff = Backoff::Fixed { delay_ms: 1 };
+
+        let delay = policy
+            .retry_delay(
+                &http_error(429, &[("retry-after", "20")]),
+                1,
+                Duration::ZERO,
+            )
+            .unwrap();
+        assert!(delay >= Duration::from_secs(20) && delay <= Duration::from_secs(22));
+
+        // Longer than the policy is willing to wait
+        let error = http_error(429, &[("retry-after", "3600")]);
+        assert!(policy.retry_delay(&error, 1, Duration::ZERO).is_none());
+        let error = http_error(429, &[("retry-after", "120")]);
+        assert!(policy.retry_delay(&error, 1, Duration::ZERO).is_none());
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_445_412_480); // Wed, 21 Oct 2015 07:28:00 GMT
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:30:00 GMT", now),
+            Some(Duration::from_secs(120))
+        );
+        // Dates in the past mean retry now
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_parse_rate_limit_reset() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let headers = |remaining: &str, reset: &str| {
+            HashMap::from([
+                ("x-ratelimit-remaining".to_string(), remaining.to_string()),
+                ("x-ratelimit-reset".to_string(), reset.to_string()),
+            ])
+        };
+
+        assert_eq!(
+            parse_rate_limit_reset(&headers("0", "30"), now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_rate_limit_reset(&headers("0", "1700000045"), now),
+            Some(Duration::from_secs(45))
+        );
+        assert_eq!(parse_rate_limit_reset(&headers("5", "30"), now), None);
+    }
+
+    #[test]
+    fn test_policies_from_config() {
+        let json = r#"{
+            "submit": {
+                "max_attempts": 8,
+                "backoff": { "strategy": "exponential", "initial_ms": 500, "max_ms": 8000, "multiplier": 2 },
+                "rules": [{ "class": "rate_limited", "retry": false }]
+            }
+        }"#;
+        let policies: RetryPolicies = serde_json::from_str(json).unwrap();
+        assert_eq!(policies.fetch, RetryPolicy::fetch_default());
+        assert_eq!(policies.submit.max_attempts, 8);
+        assert_eq!(
+            policies.submit.max_server_delay_ms,
+            DEFAULT_MAX_SERVER_DELAY_MS
+        );
+        assert!(!policies.submit.retries(ErrorClass::RateLimited));
+    }
+}
@@ -0,0 +1,286 @@
+//! Durable on-disk spool of proof submissions
+//!
+//! A submission is written to the spool before it is sent and removed once the orchestrator
+//! accepts it, so proofs survive both exhausted retries and a killed process. Each entry is a
+//! postcard-encoded [`SpoolEntry`] in its own file, named after the task ID.
+//!
+//! Entries record the node the task was issued to but not its signing key, which is loaded
+//! from the key store when the entry is submitted. Files are only readable by the owner on Unix.
+
+use super::client::ProofSubmission;
+use crate::consts::cli_consts::proof_submission;
+use crate::fs_util::{task_file_stem, write_private};
+use crate::nexus_orchestrator::TaskType;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// File extension for spool entries
+const ENTRY_EXTENSION: &str = "spool";
+
+/// Get the spool directory that sits next to the config file, typically ~/.nexus/spool.
+pub fn get_spool_dir(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .map(|parent| parent.join("spool"))
+        .unwrap_or_else(|| PathBuf::from("spool"))
+}
+
+/// A proof submission waiting to be accepted by the orchestrator
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SpoolEntry {
+    pub task_id: String,
+    pub proof_hash: String,
+    pub proof_bytes: Vec<u8>,
+    /// `TaskType` as its protobuf value
+    pub task_type: i32,
+    pub individual_proof_hashes: Vec<String>,
+    pub proofs_bytes: Vec<Vec<u8>>,
+    /// Node the task was issued to
+    pub node_id: u64,
+    /// Orchestrator the task was fetched from
+    pub orchestrator_url: String,
+    /// Seconds since the Unix epoch when the proof was spooled
+    pub created_at: u64,
+}
+
+impl SpoolEntry {
+    pub fn new(submission: &ProofSubmission, node_id: u64, orchestrator_url: &str) -> Self {
+        Self {
+            task_id: submission.task_id.clone(),
+            proof_hash: submission.proof_hash.clone(),
+            proof_bytes: submission.proof_bytes.clone(),
+            task_type: submission.task_type as i32,
+            individual_proof_hashes: submission.individual_proof_hashes.clone(),
+            proofs_bytes: submission.proofs_bytes.clone(),
+            node_id,
+            orchestrator_url: orchestrator_url.to_string(),
+            created_at: unix_now(),
+        }
+    }
+
+    /// Rebuild the submission for sending.
+    pub fn submission(&self) -> ProofSubmission {
+        let task_type = TaskType::try_from(self.task_type).unwrap_or_default();
+        ProofSubmission::new(
+            self.task_id.clone(),
+            self.proof_hash.clone(),
+            self.proof_bytes.clone(),
+            task_type,
+        )
+        .with_individual_hashes(self.individual_proof_hashes.clone())
+        .with_proofs(self.proofs_bytes.clone())
+    }
+
+    /// Seconds since the entry was spooled.
+    pub fn age_secs(&self) -> u64 {
+        unix_now().saturating_sub(self.created_at)
+    }
+
+    /// Whether the task has most likely expired on the orchestrator.
+    pub fn is_expired(&self) -> bool {
+        self.age_secs() >= proof_submission::SPOOL_EXPIRY_SECS
+    }
+
+    /// Total size of the proof payload in bytes.
+    pub fn payload_size(&self) -> usize {
+        self.proof_bytes.len() + self.proofs_bytes.iter().map(Vec::len).sum::<usize>()
+    }
+}
+
+/// Directory of spooled submissions
+#[derive(Debug, Clone)]
+pub struct Spool {
+    dir: PathBuf,
+}
+
+impl Spool {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// File name for a task.
+    fn entry_path(&self, task_id: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.{}", task_file_stem(task_id), ENTRY_EXTENSION))
+    }
+
+    /// Write an entry, replacing any existing entry for the same task.
+    /// The file is written under a temporary name and renamed so a crash never leaves a
+    /// truncated entry behind.
+    pub fn store(&self, entry: &SpoolEntry) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let bytes = postcard::to_allocvec(entry)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let path = self.entry_path(&entry.task_id);
+        let tmp_path = path.with_extension("tmp");
+        write_private(&tmp_path, &bytes)?;
+        fs::rename(&tmp_path, &path)
+    }
+
+    /// Remove the entry for a task, if present.
+    pub fn remove(&self, task_id: &str) -> io::Result<()> {
+        match fs::remove_file(self.entry_path(task_id)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Load all readable entries, oldest first. Unreadable files are returned separately so
+    /// callers can report them.
+    pub fn load(&self) -> io::Result<(Vec<SpoolEntry>, Vec<PathBuf>)> {
+        let mut entries = Vec::new();
+        let mut unreadable = Vec::new();
+
+        let dir = match fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok((entries, unreadable)),
+            Err(e) => return Err(e),
+        };
+
+        for dir_entry in dir {
+            let path = dir_entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(ENTRY_EXTENSION) {
+                continue;
+            }
+            match fs::read(&path)
+                .ok()
+                .and_then(|bytes| postcard::from_bytes::<SpoolEntry>(&bytes).ok())
+            {
+                Some(entry) => entries.push(entry),
+                None => unreadable.push(path),
+            }
+        }
+
+        entries.sort_by_key(|entry| entry.created_at);
+        Ok((entries, unreadable))
+    }
+
+    /// Remove every entry, including unreadable ones. Returns the number of files removed.
+    pub fn purge(&self) -> io::Result<usize> {
+        let dir = match fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+
+        let mut removed = 0;
+        for dir_entry in dir {
+            let path = dir_entry?.path();
+            let extension = path.extension().and_then(|ext| ext.to_str());
+            if extension == Some(ENTRY_EXTENSION) || extension == Some("tmp") {
+                fs::remove_file(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn submission(task_id: &str) -> ProofSubmission {
+        ProofSubmission::new(
+            task_id.to_string(),
+            "hash".to_string(),
+            vec![1, 2, 3],
+            TaskType::ProofRequired,
+        )
+        .with_proofs(vec![vec![1, 2, 3]])
+    }
+
+    #[test]
+    fn test_store_load_remove() {
+        let dir = tempdir().unwrap();
+        let spool = Spool::new(dir.path().join("spool"));
+
+        let entry = SpoolEntry::new(&submission("task-1"), 42, "http://127.0.0.1:8080");
+        spool.store(&entry).unwrap();
+
+        let (entries, unreadable) = spool.load().unwrap();
+        assert_eq!(entries, vec![entry.clone()]);
+        assert!(unreadable.is_empty());
+
+        let restored = entries[0].submission();
+        assert_eq!(restored.task_id, "task-1");
+        assert_eq!(restored.task_type, TaskType::ProofRequired);
+        assert_eq!(restored.proofs_bytes, vec![vec![1, 2, 3]]);
+        assert_eq!(entries[0].node_id, 42);
+
+        spool.remove("task-1").unwrap();
+        assert!(spool.load().unwrap().0.is_empty());
+
+        // Removing a missing entry is not an error
+        spool.remove("task-1").unwrap();
+    }
+
+    #[test]
+    fn test_unsafe_task_ids_stay_in_spool_dir() {
+        let dir = tempdir().unwrap();
+        let spool = Spool::new(dir.path().to_path_buf());
+
+        let path = spool.entry_path("../../etc/passwd");
+        assert_eq!(path.parent(), Some(dir.path()));
+    }
+
+    #[test]
+    fn test_purge_removes_unreadable_entries() {
+        let dir = tempdir().unwrap();
+        let spool = Spool::new(dir.path().to_path_buf());
+
+        let entry = SpoolEntry::new(&submission("task-1"), 42, "url");
+        spool.store(&entry).unwrap();
+        fs::write(dir.path().join("garbage.spool"), b"not postcard").unwrap();
+
+        let (entries, unreadable) = spool.load().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(unreadable.len(), 1);
+
+        assert_eq!(spool.purge().unwrap(), 2);
+        let (entries, unreadable) = spool.load().unwrap();
+        assert!(entries.is_empty() && unreadable.is_empty());
+    }
+
+    #[test]
+    fn test_expiry() {
+        let mut entry = SpoolEntry::new(&submission("task-1"), 42, "url");
+        assert!(!entry.is_expired());
+
+        entry.created_at -= proof_submission::SPOOL_EXPIRY_SECS;
+        assert!(entry.is_expired());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_entries_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let spool = Spool::new(dir.path().to_path_buf());
+        let entry = SpoolEntry::new(&submission("task-1"), 42, "url");
+        spool.store(&entry).unwrap();
+
+        let mode = fs::metadata(spool.entry_path("task-1"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
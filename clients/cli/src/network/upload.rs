@@ -0,0 +1,260 @@
+//! Request body uploads
+//!
+//! Protobuf request bodies can be compressed with a `Content-Encoding`, are streamed to the
+//! orchestrator in chunks so that progress can be reported while a large proof goes out, and
+//! get a timeout that grows with their size instead of a flat one. A body is encoded once per
+//! request, off the async runtime, and shared by every attempt and endpoint it is sent to.
+
+use crate::consts::cli_consts::uploads;
+use bytes::Bytes;
+use futures::stream::{self, Stream, StreamExt};
+use std::future::Future;
+use std::io::{Read, Write};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Compression applied to protobuf request bodies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContentEncoding {
+    /// Bodies are sent as is
+    #[default]
+    Identity,
+    Gzip,
+    Zstd,
+}
+
+impl ContentEncoding {
+    /// Value of the `Content-Encoding` header, `None` for uncompressed bodies.
+    pub fn header_value(self) -> Option<&'static str> {
+        match self {
+            ContentEncoding::Identity => None,
+            ContentEncoding::Gzip => Some("gzip"),
+            ContentEncoding::Zstd => Some("zstd"),
+        }
+    }
+
+    /// Encoding named by a `Content-Encoding` header value.
+    pub fn from_header_value(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "identity" => Some(ContentEncoding::Identity),
+            "gzip" => Some(ContentEncoding::Gzip),
+            "zstd" => Some(ContentEncoding::Zstd),
+            _ => None,
+        }
+    }
+
+    pub fn encode(self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            ContentEncoding::Identity => Ok(body.to_vec()),
+            ContentEncoding::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+            ContentEncoding::Zstd => zstd::encode_all(body, 0),
+        }
+    }
+
+    pub fn decode(self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            ContentEncoding::Identity => Ok(body.to_vec()),
+            ContentEncoding::Gzip => {
+                let mut decoded = Vec::new();
+                flate2::read::GzDecoder::new(body).read_to_end(&mut decoded)?;
+                Ok(decoded)
+            }
+            ContentEncoding::Zstd => zstd::decode_all(body),
+        }
+    }
+}
+
+impl FromStr for ContentEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(ContentEncoding::Identity),
+            "gzip" => Ok(ContentEncoding::Gzip),
+            "zstd" => Ok(ContentEncoding::Zstd),
+            _ => Err(format!(
+                "unknown compression '{}', expected none, gzip or zstd",
+                s
+            )),
+        }
+    }
+}
+
+/// How much of a request body has been handed to the connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadProgress {
+    pub sent: u64,
+    pub total: u64,
+}
+
+impl UploadProgress {
+    pub fn percent(&self) -> u64 {
+        (self.sent * 100).checked_div(self.total).unwrap_or(100)
+    }
+}
+
+type ProgressCallback = Arc<dyn Fn(UploadProgress) + Send + Sync>;
+
+tokio::task_local! {
+    static ON_PROGRESS: ProgressCallback;
+}
+
+/// Runs `future`, reporting the progress of large request bodies it uploads to `on_progress`.
+pub async fn with_progress<F: Future>(
+    on_progress: impl Fn(UploadProgress) + Send + Sync + 'static,
+    future: F,
+) -> F::Output {
+    ON_PROGRESS.scope(Arc::new(on_progress), future).await
+}
+
+/// Timeout for a request carrying `body_len` bytes: the base timeout plus the time the body
+/// takes at the slowest supported throughput, capped.
+pub fn upload_timeout(body_len: usize) -> Duration {
+    let transfer = Duration::from_secs(body_len as u64 / uploads::MIN_THROUGHPUT_BYTES_PER_SEC);
+    (uploads::base_timeout() + transfer).min(uploads::max_timeout())
+}
+
+/// A request body ready to send, cheap to clone for another attempt
+#[derive(Debug, Clone)]
+pub struct EncodedBody {
+    pub bytes: Bytes,
+    /// Encoding actually applied to `bytes`
+    pub encoding: ContentEncoding,
+}
+
+/// Compresses the body with `encoding` on a blocking thread, unless it is too small to be worth
+/// it. A body that fails to compress goes out as is.
+pub async fn encode_body(encoding: ContentEncoding, body: Vec<u8>) -> EncodedBody {
+    let body = Bytes::from(body);
+    if encoding == ContentEncoding::Identity || body.len() < uploads::COMPRESSION_MIN_BYTES {
+        return EncodedBody {
+            bytes: body,
+            encoding: ContentEncoding::Identity,
+        };
+    }
+    let input = body.clone();
+    match tokio::task::spawn_blocking(move || encoding.encode(&input)).await {
+        Ok(Ok(encoded)) => EncodedBody {
+            bytes: Bytes::from(encoded),
+            encoding,
+        },
+        _ => EncodedBody {
+            bytes: body,
+            encoding: ContentEncoding::Identity,
+        },
+    }
+}
+
+/// Request body streaming `bytes` in chunks. Progress of large bodies goes to the callback of
+/// the enclosing [`with_progress`], if any.
+pub fn streamed_body(bytes: Bytes) -> reqwest::Body {
+    let on_progress = ON_PROGRESS
+        .try_with(Arc::clone)
+        .ok()
+        .filter(|_| bytes.len() as u64 >= uploads::PROGRESS_MIN_BYTES);
+    reqwest::Body::wrap_stream(chunks(bytes, on_progress))
+}
+
+/// The body in chunks sharing its buffer, reporting progress every `PROGRESS_STEP_PERCENT` as
+/// chunks are taken.
+fn chunks(
+    bytes: Bytes,
+    on_progress: Option<ProgressCallback>,
+) -> impl Stream<Item = std::io::Result<Bytes>> {
+    let total = bytes.len() as u64;
+    let mut sent = 0;
+    let mut reported_step = 0;
+    stream::iter((0..bytes.len()).step_by(uploads::CHUNK_BYTES)).map(move |start| {
+        let chunk = bytes.slice(start..(start + uploads::CHUNK_BYTES).min(bytes.len()));
+        sent += chunk.len() as u64;
+        if let Some(on_progress) = &on_progress {
+            let progress = UploadProgress { sent, total };
+            let step = progress.percent() / uploads::PROGRESS_STEP_PERCENT;
+            if step > reported_step {
+                reported_step = step;
+                on_progress(progress);
+            }
+        }
+        Ok(chunk)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_encodings_round_trip() {
+        let body: Vec<u8> = (0..10_000u32).flat_map(|i| (i % 7).to_le_bytes()).collect();
+        for encoding in [ContentEncoding::Gzip, ContentEncoding::Zstd] {
+            let encoded = encoding.encode(&body).unwrap();
+            assert!(encoded.len() < body.len());
+            assert_eq!(encoding.decode(&encoded).unwrap(), body);
+            assert_eq!(
+                ContentEncoding::from_header_value(encoding.header_value().unwrap()),
+                Some(encoding)
+            );
+        }
+        assert_eq!("ZSTD".parse::<ContentEncoding>(), Ok(ContentEncoding::Zstd));
+        assert_eq!(
+            "none".parse::<ContentEncoding>(),
+            Ok(ContentEncoding::Identity)
+        );
+        assert!("brotli".parse::<ContentEncoding>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_small_bodies_are_not_compressed() {
+        let body = encode_body(ContentEncoding::Gzip, vec![1, 2, 3]).await;
+        assert_eq!(body.bytes, vec![1, 2, 3]);
+        assert_eq!(body.encoding, ContentEncoding::Identity);
+
+        let body = encode_body(ContentEncoding::Gzip, vec![0; 4096]).await;
+        assert_eq!(body.encoding, ContentEncoding::Gzip);
+        assert_eq!(
+            ContentEncoding::Gzip.decode(&body.bytes).unwrap(),
+            vec![0; 4096]
+        );
+    }
+
+    #[test]
+    fn test_upload_timeout_scales_with_size() {
+        assert_eq!(upload_timeout(0), uploads::base_timeout());
+        let ten_mib = upload_timeout(10 * 1024 * 1024);
+        assert_eq!(ten_mib, uploads::base_timeout() + Duration::from_secs(320));
+        assert_eq!(upload_timeout(usize::MAX / 2), uploads::max_timeout());
+    }
+
+    #[tokio::test]
+    async fn test_chunks_report_progress_in_steps() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&reports);
+        let on_progress: ProgressCallback =
+            Arc::new(move |progress: UploadProgress| recorded.lock().unwrap().push(progress));
+
+        let body = Bytes::from(vec![7u8; uploads::CHUNK_BYTES * 8]);
+        let sent: Vec<Bytes> = chunks(body.clone(), Some(on_progress))
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(sent.len(), 8);
+        assert_eq!(sent.concat(), body);
+        // Chunks are views of the body, not copies
+        assert_eq!(sent[1].as_ptr(), body[uploads::CHUNK_BYTES..].as_ptr());
+
+        let percents: Vec<u64> = reports
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|p| p.percent())
+            .collect();
+        assert_eq!(percents, vec![25, 50, 75, 100]);
+    }
+}
@@ -0,0 +1,168 @@
+//! `nodes` and `whoami` commands for inspecting the nodes registered to the configured wallet.
+
+use crate::cli_messages::{print_info, print_warn};
+use crate::config::Config;
+use crate::nexus_orchestrator::NodeType;
+use crate::orchestrator::Orchestrator;
+use crate::orchestrator::client::RegisteredNode;
+use serde_json::json;
+use std::error::Error;
+use std::path::Path;
+
+/// Loads the config file, requiring a registered wallet.
+fn load_registered_config(config_path: &Path) -> Result<Config, Box<dyn Error>> {
+    let config = Config::load_from_file(config_path)
+        .map_err(|_| "No user registered, run `nexus-network register-user` first")?;
+    if config.wallet_address.is_empty() {
+        return Err("No wallet address configured, run `nexus-network register-user` first".into());
+    }
+    Ok(config)
+}
+
+/// Node IDs this machine runs: the config file's node and any additional `node_ids`.
+fn local_node_ids(config: &Config) -> Vec<String> {
+    let mut node_ids = Vec::new();
+    if !config.node_id.is_empty() {
+        node_ids.push(config.node_id.clone());
+    }
+    for node_id in &config.node_ids {
+        let node_id = node_id.to_string();
+        if !node_ids.contains(&node_id) {
+            node_ids.push(node_id);
+        }
+    }
+    node_ids
+}
+
+fn node_type_label(node_type: NodeType) -> &'static str {
+    match node_type {
+        NodeType::CliProver => "cli",
+        NodeType::WebProver => "web",
+    }
+}
+
+/// Lists every node registered to the configured wallet, marking the ones run locally.
+pub async fn list_nodes(
+    orchestrator: &dyn Orchestrator,
+    config_path: &Path,
+    as_json: bool,
+) -> Result<(), Box<dyn Error>> {
+    let config = load_registered_config(config_path)?;
+    let page = orchestrator.get_user_page(&config.wallet_address).await?;
+    let nodes: Vec<RegisteredNode> = page.nodes;
+    let local = local_node_ids(&config);
+
+    if as_json {
+        let value = json!(
+            nodes
+                .iter()
+                .map(|node| json!({
+                    "node_id": node.node_id,
+                    "node_type": node.node_type.as_str_name(),
+                    "active": local.contains(&node.node_id),
+                }))
+                .collect::<Vec<_>>()
+        );
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+
+    if nodes.is_empty() {
+        print_info(
+            "No nodes registered",
+            &format!(
+                "Wallet {}. Register one with `nexus-network register-node`",
+                config.wallet_address
+            ),
+        );
+    } else {
+        print_info(
+            "Registered nodes",
+            &format!("{} for wallet {}", nodes.len(), config.wallet_address),
+        );
+        for node in &nodes {
+            println!(
+                "  {} {:<12} {}",
+                if local.contains(&node.node_id) {
+                    "*"
+                } else {
+                    " "
+                },
+                node.node_id,
+                node_type_label(node.node_type)
+            );
+        }
+    }
+
+    // Local nodes may be registered on a later page, so only check a complete list
+    if page.more_nodes {
+        print_warn(
+            "Only the first page of nodes is listed",
+            "the orchestrator holds more nodes for this wallet",
+        );
+        return Ok(());
+    }
+    for node_id in local
+        .iter()
+        .filter(|node_id| !nodes.iter().any(|node| node.node_id == **node_id))
+    {
+        print_warn(
+            &format!("Node {} is configured locally", node_id),
+            "but is not registered to this wallet",
+        );
+    }
+    Ok(())
+}
+
+/// Shows the local configuration and a summary of the wallet's registered nodes.
+pub async fn whoami(
+    orchestrator: &dyn Orchestrator,
+    config_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let config = load_registered_config(config_path)?;
+    let local = local_node_ids(&config);
+
+    print_info("Config file", &config_path.display().to_string());
+    println!("  Wallet:       {}", config.wallet_address);
+    println!("  User ID:      {}", config.user_id);
+    println!("  Orchestrator: {}", orchestrator.environment());
+    println!(
+        "  Active nodes: {}",
+        if local.is_empty() {
+            "none".to_string()
+        } else {
+            local.join(", ")
+        }
+    );
+
+    let page = orchestrator.get_user_page(&config.wallet_address).await?;
+    let nodes: Vec<RegisteredNode> = page.nodes;
+    let cli_nodes = nodes
+        .iter()
+        .filter(|node| node.node_type == NodeType::CliProver)
+        .count();
+    println!(
+        "  Registered:   {}{} nodes ({} cli, {} web)",
+        nodes.len(),
+        if page.more_nodes { "+" } else { "" },
+        cli_nodes,
+        nodes.len() - cli_nodes
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_node_ids_deduplicates() {
+        let config = Config {
+            node_id: "7".to_string(),
+            node_ids: vec![7, 8],
+            ..Default::default()
+        };
+        assert_eq!(local_node_ids(&config), vec!["7", "8"]);
+        assert!(local_node_ids(&Config::default()).is_empty());
+    }
+}
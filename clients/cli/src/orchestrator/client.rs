@@ -2,20 +2,25 @@
 //!
 //! A client for the Nexus Orchestrator, allowing for proof task retrieval and submission.
 
+use crate::consts::cli_consts::uploads;
 use crate::environment::Environment;
+use crate::network::http;
+use crate::network::upload::{self, ContentEncoding, EncodedBody};
 use crate::nexus_orchestrator::{
     GetProofTaskRequest, GetProofTaskResponse, NodeType, RegisterNodeRequest, RegisterNodeResponse,
     RegisterUserRequest, SubmitProofRequest, UserResponse,
 };
 use crate::orchestrator::Orchestrator;
+use crate::orchestrator::endpoints::EndpointPool;
 use crate::orchestrator::error::OrchestratorError;
+use crate::orchestrator::traffic::TrafficRecorder;
 use crate::system::{estimate_peak_gflops, get_memory_info};
 use crate::task::Task;
 use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
 use prost::Message;
-use reqwest::{Client, ClientBuilder, Response};
-use std::sync::OnceLock;
-use std::time::Duration;
+use reqwest::{Client, Method, Response};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
 /// Proof payload returned by `select_proof_payload`.
 ///
@@ -34,6 +39,52 @@ pub struct ProofTaskResult {
     pub actual_difficulty: crate::nexus_orchestrator::TaskDifficulty,
 }
 
+impl From<&GetProofTaskResponse> for ProofTaskResult {
+    fn from(response: &GetProofTaskResponse) -> Self {
+        let task = Task::from(response);
+        let actual_difficulty = task.difficulty;
+        Self {
+            task,
+            actual_difficulty,
+        }
+    }
+}
+
+/// A node registered to a user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredNode {
+    pub node_id: String,
+    pub node_type: NodeType,
+}
+
+/// A user and the first page of their registered nodes
+#[derive(Debug, Clone, Default)]
+pub struct UserPage {
+    pub user_id: String,
+    pub wallet_address: String,
+    pub nodes: Vec<RegisteredNode>,
+    /// Whether the user has more nodes than this page holds
+    pub more_nodes: bool,
+}
+
+impl From<&UserResponse> for UserPage {
+    fn from(response: &UserResponse) -> Self {
+        Self {
+            user_id: response.user_id.clone(),
+            wallet_address: response.wallet_address.clone(),
+            nodes: response
+                .nodes
+                .iter()
+                .map(|node| RegisteredNode {
+                    node_id: node.node_id.clone(),
+                    node_type: NodeType::try_from(node.node_type).unwrap_or_default(),
+                })
+                .collect(),
+            more_nodes: !response.nodes_next_cursor.is_empty(),
+        }
+    }
+}
+
 impl std::fmt::Display for ProofTaskResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -45,13 +96,13 @@ impl std::fmt::Display for ProofTaskResult {
 }
 
 // Build timestamp in milliseconds since epoch
-static BUILD_TIMESTAMP: &str = match option_env!("BUILD_TIMESTAMP") {
+pub(crate) static BUILD_TIMESTAMP: &str = match option_env!("BUILD_TIMESTAMP") {
     Some(timestamp) => timestamp,
     None => "Build timestamp not available",
 };
 
 // User-Agent string with CLI version
-const USER_AGENT: &str = concat!("nexus-cli/", env!("CARGO_PKG_VERSION"));
+pub(crate) const USER_AGENT: &str = concat!("nexus-cli/", env!("CARGO_PKG_VERSION"));
 
 // Privacy-preserving country detection for network optimization.
 // Only stores 2-letter country codes (e.g., "US", "CA", "GB") to help route
@@ -63,34 +114,65 @@ pub(crate) static COUNTRY_CODE: OnceLock<String> = OnceLock::new();
 pub struct OrchestratorClient {
     client: Client,
     environment: Environment,
+    /// Endpoints of the environment, shared with the probes that track their health
+    endpoints: Arc<EndpointPool>,
+    /// Compression applied to request bodies
+    content_encoding: ContentEncoding,
+    /// Where exchanges are saved when traffic is being recorded
+    recorder: Option<Arc<TrafficRecorder>>,
 }
 
 impl OrchestratorClient {
     pub fn new(environment: Environment) -> Self {
         Self {
-            client: ClientBuilder::new()
+            client: http::client_builder()
                 .connect_timeout(Duration::from_secs(10))
-                .timeout(Duration::from_secs(10))
+                .timeout(uploads::base_timeout())
                 .build()
                 .expect("Failed to create HTTP client"),
+            endpoints: Arc::new(EndpointPool::for_environment(&environment)),
             environment,
+            content_encoding: ContentEncoding::default(),
+            recorder: None,
         }
     }
 
+    /// Compress request bodies with `content_encoding`.
+    pub fn with_content_encoding(mut self, content_encoding: ContentEncoding) -> Self {
+        self.content_encoding = content_encoding;
+        self
+    }
+
+    /// Record every exchange with `recorder`, if set.
+    pub fn with_recorder(mut self, recorder: Option<Arc<TrafficRecorder>>) -> Self {
+        self.recorder = recorder;
+        self
+    }
+
     /// Public accessor for privacy-preserving country code (cached during run)
     #[allow(dead_code)]
     pub async fn country(&self) -> String {
         self.get_country().await
     }
 
-    fn build_url(&self, endpoint: &str) -> String {
+    fn build_url(base_url: &str, endpoint: &str) -> String {
         format!(
             "{}/{}",
-            self.environment.orchestrator_url().trim_end_matches('/'),
+            base_url.trim_end_matches('/'),
             endpoint.trim_start_matches('/')
         )
     }
 
+    /// Whether the error suggests the endpoint itself is down, rather than the request being
+    /// rejected.
+    fn is_endpoint_failure(error: &OrchestratorError) -> bool {
+        match error {
+            OrchestratorError::Reqwest(e) => e.is_connect() || e.is_timeout(),
+            OrchestratorError::Http { status, .. } => matches!(status, 502..=504),
+            _ => false,
+        }
+    }
+
     fn encode_request<T: Message>(request: &T) -> Vec<u8> {
         request.encode_to_vec()
     }
@@ -144,21 +226,106 @@ impl OrchestratorClient {
         Ok(response)
     }
 
-    async fn get_request<T: Message + Default>(
+    /// Sends one request, recording the exchange if traffic recording is on.
+    async fn exchange(
         &self,
+        method: Method,
         endpoint: &str,
-    ) -> Result<T, OrchestratorError> {
-        let url = self.build_url(endpoint);
-        let response = self
+        body: Option<Vec<u8>>,
+    ) -> Result<Vec<u8>, OrchestratorError> {
+        let Some(recorder) = &self.recorder else {
+            return self
+                .send(method, endpoint, body)
+                .await
+                .map(|(_, bytes)| bytes);
+        };
+
+        let started = Instant::now();
+        let recorded_body = body.clone();
+        let result = self.send(method.clone(), endpoint, body).await;
+        // Recording is best effort and never fails the request
+        let _ = recorder.record(
+            method.as_str(),
+            endpoint,
+            recorded_body.as_deref(),
+            &result,
+            started.elapsed(),
+        );
+        result.map(|(_, bytes)| bytes)
+    }
+
+    /// Sends the request to the active endpoint. When an endpoint fails, the pool moves on to
+    /// the next one; requests that never connected are retried there straight away.
+    async fn send(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<Vec<u8>>,
+    ) -> Result<(u16, Vec<u8>), OrchestratorError> {
+        // Encoded once and shared by every endpoint the request goes to
+        let body = match body {
+            Some(body) => Some(upload::encode_body(self.content_encoding, body).await),
+            None => None,
+        };
+        let mut attempts_left = self.endpoints.endpoint_count();
+        loop {
+            let base_url = self.endpoints.active_url();
+            let started = Instant::now();
+            let result = self
+                .send_to(&base_url, method.clone(), endpoint, body.clone())
+                .await;
+            match &result {
+                Err(e) if Self::is_endpoint_failure(e) => {
+                    self.endpoints.record_failure(&base_url);
+                    attempts_left -= 1;
+                    let connect_failed =
+                        matches!(e, OrchestratorError::Reqwest(e) if e.is_connect());
+                    if connect_failed && attempts_left > 0 {
+                        continue;
+                    }
+                }
+                _ => self.endpoints.record_success(&base_url, started.elapsed()),
+            }
+            return result;
+        }
+    }
+
+    async fn send_to(
+        &self,
+        base_url: &str,
+        method: Method,
+        endpoint: &str,
+        body: Option<EncodedBody>,
+    ) -> Result<(u16, Vec<u8>), OrchestratorError> {
+        let url = Self::build_url(base_url, endpoint);
+        let mut request = self
             .client
-            .get(&url)
+            .request(method, &url)
             .header("User-Agent", USER_AGENT)
-            .header("X-Build-Timestamp", BUILD_TIMESTAMP)
-            .send()
-            .await?;
+            .header("X-Build-Timestamp", BUILD_TIMESTAMP);
+        if let Some(body) = body {
+            // Large proofs can take a while on slow uplinks, so the timeout grows with the body
+            request = request
+                .header("Content-Type", "application/octet-stream")
+                .header("Content-Length", body.bytes.len())
+                .timeout(upload::upload_timeout(body.bytes.len()))
+                .body(upload::streamed_body(body.bytes));
+            if let Some(content_encoding) = body.encoding.header_value() {
+                request = request.header("Content-Encoding", content_encoding);
+            }
+        }
 
-        let response = Self::handle_response_status(response).await?;
+        let response = Self::handle_response_status(request.send().await?).await?;
+        let status = response.status().as_u16();
         let response_bytes = response.bytes().await?;
+        Ok((status, response_bytes.to_vec()))
+    }
+
+    async fn get_request<T: Message + Default>(
+        &self,
+        endpoint: &str,
+    ) -> Result<T, OrchestratorError> {
+        let response_bytes = self.exchange(Method::GET, endpoint, None).await?;
         Self::decode_response(&response_bytes)
     }
 
@@ -167,19 +334,7 @@ impl OrchestratorClient {
         endpoint: &str,
         body: Vec<u8>,
     ) -> Result<T, OrchestratorError> {
-        let url = self.build_url(endpoint);
-        let response = self
-            .client
-            .post(&url)
-            .header("Content-Type", "application/octet-stream")
-            .header("User-Agent", USER_AGENT)
-            .header("X-Build-Timestamp", BUILD_TIMESTAMP)
-            .body(body)
-            .send()
-            .await?;
-
-        let response = Self::handle_response_status(response).await?;
-        let response_bytes = response.bytes().await?;
+        let response_bytes = self.exchange(Method::POST, endpoint, Some(body)).await?;
         Self::decode_response(&response_bytes)
     }
 
@@ -188,38 +343,10 @@ impl OrchestratorClient {
         endpoint: &str,
         body: Vec<u8>,
     ) -> Result<(), OrchestratorError> {
-        let url = self.build_url(endpoint);
-        let response = self
-            .client
-            .post(&url)
-            .header("Content-Type", "application/octet-stream")
-            .header("User-Agent", USER_AGENT)
-            .header("X-Build-Timestamp", BUILD_TIMESTAMP)
-            .body(body)
-            .send()
-            .await?;
-
-        Self::handle_response_status(response).await?;
+        self.exchange(Method::POST, endpoint, Some(body)).await?;
         Ok(())
     }
 
-    fn create_signature(
-        &self,
-        signing_key: &SigningKey,
-        task_id: &str,
-        proof_hash: &str,
-    ) -> (Vec<u8>, Vec<u8>) {
-        let signature_version = 0;
-        let msg = format!("{} | {} | {}", signature_version, task_id, proof_hash);
-        let signature = signing_key.sign(msg.as_bytes());
-        let verifying_key: VerifyingKey = signing_key.verifying_key();
-
-        (
-            signature.to_bytes().to_vec(),
-            verifying_key.to_bytes().to_vec(),
-        )
-    }
-
     /// Detects the user's country for network optimization purposes.
     ///
     /// Privacy Note: This only detects the country (2-letter code like "US", "CA", "GB")
@@ -233,6 +360,11 @@ impl OrchestratorClient {
             return country.clone();
         }
 
+        // A local orchestrator has no routing to optimize, and may be running offline.
+        if self.environment.is_local() {
+            return "US".to_string();
+        }
+
         let country = self.detect_country().await;
         let _ = COUNTRY_CODE.set(country.clone());
         country
@@ -294,6 +426,30 @@ impl OrchestratorClient {
     }
 }
 
+/// Signs a proof submission, returning the signature and the public key to verify it with.
+fn create_signature(
+    signing_key: &SigningKey,
+    task_id: &str,
+    proof_hash: &str,
+) -> (Vec<u8>, Vec<u8>) {
+    let msg = signature_message(task_id, proof_hash);
+    let signature = signing_key.sign(msg.as_bytes());
+    let verifying_key: VerifyingKey = signing_key.verifying_key();
+
+    (
+        signature.to_bytes().to_vec(),
+        verifying_key.to_bytes().to_vec(),
+    )
+}
+
+/// Message signed with the node's Ed25519 key when submitting a proof.
+///
+/// Shared with the local orchestrator stand-in so that it checks exactly what we sign.
+pub(crate) fn signature_message(task_id: &str, proof_hash: &str) -> String {
+    let signature_version = 0;
+    format!("{} | {} | {}", signature_version, task_id, proof_hash)
+}
+
 /// Detect country code once globally without requiring a client instance.
 /// This ensures callers don't need to sequence a warm-up before using the result.
 pub(crate) async fn detect_country_once() -> String {
@@ -301,7 +457,10 @@ pub(crate) async fn detect_country_once() -> String {
         return country.clone();
     }
 
-    let client = match ClientBuilder::new().timeout(Duration::from_secs(5)).build() {
+    let client = match http::client_builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+    {
         Ok(c) => c,
         Err(_) => return "US".to_string(),
     };
@@ -348,14 +507,23 @@ impl Orchestrator for OrchestratorClient {
         &self.environment
     }
 
+    fn endpoints(&self) -> Option<Arc<EndpointPool>> {
+        Some(Arc::clone(&self.endpoints))
+    }
+
     /// Get the user ID associated with a wallet address.
     async fn get_user(&self, wallet_address: &str) -> Result<String, OrchestratorError> {
-        let wallet_path = urlencoding::encode(wallet_address).into_owned();
-        let endpoint = format!("v3/users/{}", wallet_path);
+        let endpoint = user_endpoint(wallet_address);
         let user_response: UserResponse = self.get_request(&endpoint).await?;
         Ok(user_response.user_id)
     }
 
+    async fn get_user_page(&self, wallet_address: &str) -> Result<UserPage, OrchestratorError> {
+        let endpoint = user_endpoint(wallet_address);
+        let user_response: UserResponse = self.get_request(&endpoint).await?;
+        Ok(UserPage::from(&user_response))
+    }
+
     /// Registers a new user with the orchestrator.
     async fn register_user(
         &self,
@@ -404,14 +572,7 @@ impl Orchestrator for OrchestratorClient {
         };
         let request_bytes = Self::encode_request(&request);
         let response: GetProofTaskResponse = self.post_request("v3/tasks", request_bytes).await?;
-
-        let task = Task::from(&response);
-        let actual_difficulty = task.difficulty;
-
-        Ok(ProofTaskResult {
-            task,
-            actual_difficulty,
-        })
+        Ok(ProofTaskResult::from(&response))
     }
 
     async fn submit_proof(
@@ -425,131 +586,174 @@ impl Orchestrator for OrchestratorClient {
         task_type: crate::nexus_orchestrator::TaskType,
         individual_proof_hashes: &[String],
     ) -> Result<(), OrchestratorError> {
-        let (program_memory, total_memory) = get_memory_info();
-        let flops = estimate_peak_gflops(num_provers);
-        let (signature, public_key) = self.create_signature(&signing_key, task_id, proof_hash);
-
         // Detect country for network optimization (privacy-preserving: only country code, no precise location)
         let location = self.get_country().await;
-        // Handle different task types
-        let (proof_to_send, proofs_to_send, all_proof_hashes_to_send) =
-            OrchestratorClient::select_proof_payload(
-                task_type,
-                proof,
-                proofs,
-                individual_proof_hashes,
-            );
-
-        let request = SubmitProofRequest {
-            task_id: task_id.to_string(),
-            node_type: NodeType::CliProver as i32,
-            proof_hash: proof_hash.to_string(),
-            proof: proof_to_send,
-            proofs: proofs_to_send,
-            node_telemetry: Some(crate::nexus_orchestrator::NodeTelemetry {
-                flops_per_sec: Some(flops as i32),
-                memory_used: Some(program_memory),
-                memory_capacity: Some(total_memory),
-                // Country code for network routing optimization (privacy-preserving)
-                location: Some(location),
-            }),
-            ed25519_public_key: public_key,
-            signature,
-            all_proof_hashes: all_proof_hashes_to_send,
-        };
+        let request = submit_proof_request(
+            task_id,
+            proof_hash,
+            proof,
+            proofs,
+            &signing_key,
+            num_provers,
+            task_type,
+            individual_proof_hashes,
+            location,
+        );
         let request_bytes = Self::encode_request(&request);
         self.post_request_no_response("v3/tasks/submit", request_bytes)
             .await
     }
 }
 
+/// Endpoint for a user and the first page of their nodes. Shared with traffic replay.
+pub(crate) fn user_endpoint(wallet_address: &str) -> String {
+    format!("v3/users/{}", urlencoding::encode(wallet_address))
+}
+
+/// Builds a signed proof submission with node telemetry. Shared by every transport.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn submit_proof_request(
+    task_id: &str,
+    proof_hash: &str,
+    proof: Vec<u8>,
+    proofs: Vec<Vec<u8>>,
+    signing_key: &SigningKey,
+    num_provers: usize,
+    task_type: crate::nexus_orchestrator::TaskType,
+    individual_proof_hashes: &[String],
+    location: String,
+) -> SubmitProofRequest {
+    let (program_memory, total_memory) = get_memory_info();
+    let flops = estimate_peak_gflops(num_provers);
+    let (signature, public_key) = create_signature(signing_key, task_id, proof_hash);
+
+    // Handle different task types
+    let (proof_to_send, proofs_to_send, all_proof_hashes_to_send) =
+        OrchestratorClient::select_proof_payload(task_type, proof, proofs, individual_proof_hashes);
+
+    SubmitProofRequest {
+        task_id: task_id.to_string(),
+        node_type: NodeType::CliProver as i32,
+        proof_hash: proof_hash.to_string(),
+        proof: proof_to_send,
+        proofs: proofs_to_send,
+        node_telemetry: Some(crate::nexus_orchestrator::NodeTelemetry {
+            flops_per_sec: Some(flops as i32),
+            memory_used: Some(program_memory),
+            memory_capacity: Some(total_memory),
+            // Country code for network routing optimization (privacy-preserving)
+            location: Some(location),
+        }),
+        ed25519_public_key: public_key,
+        signature,
+        all_proof_hashes: all_proof_hashes_to_send,
+    }
+}
+
 #[cfg(test)]
-/// These are ignored by default since they require a live orchestrator to run.
-mod live_orchestrator_tests {
+/// These run against the local orchestrator stand-in, so they need no network access.
+mod local_orchestrator_tests {
     use crate::environment::Environment;
     use crate::orchestrator::Orchestrator;
+    use crate::orchestrator::dev_server::{DevOrchestrator, DevOrchestratorConfig};
+    use std::net::SocketAddr;
+
+    const WALLET_ADDRESS: &str = "0x1234567890abcdef1234567890cbaabc12345678";
+
+    async fn start() -> (DevOrchestrator, super::OrchestratorClient) {
+        let server = DevOrchestrator::bind(
+            SocketAddr::from(([127, 0, 0, 1], 0)),
+            DevOrchestratorConfig::default(),
+        )
+        .await
+        .expect("failed to bind local orchestrator");
+        let client = super::OrchestratorClient::new(Environment::Custom {
+            orchestrator_url: server.url(),
+        });
+        (server, client)
+    }
+
+    /// Register a user and one node for it, returning the user ID and node ID.
+    async fn register(client: &super::OrchestratorClient) -> (String, String) {
+        // UUIDv4 for the user ID
+        let user_id = uuid::Uuid::new_v4().to_string();
+        client
+            .register_user(&user_id, WALLET_ADDRESS)
+            .await
+            .expect("Failed to register user");
+        let node_id = client
+            .register_node(&user_id)
+            .await
+            .expect("Failed to register node");
+        (user_id, node_id)
+    }
 
     #[tokio::test]
-    #[ignore] // This test requires a live orchestrator instance.
     /// Should register a new user with the orchestrator.
     async fn test_register_user() {
-        let client = super::OrchestratorClient::new(Environment::Production);
-        // UUIDv4 for the user ID
+        let (_server, client) = start().await;
         let user_id = uuid::Uuid::new_v4().to_string();
-        let wallet_address = "0x1234567890abcdef1234567890cbaabc12345678"; // Example wallet address
-        match client.register_user(&user_id, wallet_address).await {
-            Ok(_) => println!("User registered successfully: {}", user_id),
+        match client.register_user(&user_id, WALLET_ADDRESS).await {
+            Ok(_) => assert_eq!(client.get_user(WALLET_ADDRESS).await.unwrap(), user_id),
             Err(e) => panic!("Failed to register user: {}", e),
         }
     }
 
     #[tokio::test]
-    #[ignore] // This test requires a live orchestrator instance.
     /// Should register a new node to an existing user.
     async fn test_register_node() {
-        let client = super::OrchestratorClient::new(Environment::Production);
-        let user_id = "78db0be7-f603-4511-9576-c660f3c58395";
-        match client.register_node(user_id).await {
-            Ok(node_id) => println!("Node registered successfully: {}", node_id),
+        let (_server, client) = start().await;
+        let (user_id, first_node_id) = register(&client).await;
+        match client.register_node(&user_id).await {
+            Ok(node_id) => assert_ne!(node_id, first_node_id),
             Err(e) => panic!("Failed to register node: {}", e),
         }
     }
 
     #[tokio::test]
-    #[ignore] // This test requires a live orchestrator instance.
     /// Should return a new proof task for the node.
     async fn test_get_proof_task() {
-        let client = super::OrchestratorClient::new(Environment::Production);
-        let node_id = "5880437"; // Example node ID
+        let (_server, client) = start().await;
+        let (_, node_id) = register(&client).await;
         let signing_key = ed25519_dalek::SigningKey::generate(&mut rand::thread_rng());
         let verifying_key = signing_key.verifying_key();
         let result = client
             .get_proof_task(
-                node_id,
+                &node_id,
                 verifying_key,
                 crate::nexus_orchestrator::TaskDifficulty::SmallMedium,
             )
             .await;
         match result {
-            Ok(task) => {
-                println!("Got proof task: {}", task);
+            Ok(result) => {
+                assert_eq!(result.task.program_id, "fib_input_initial");
+                assert!(!result.task.public_inputs_list.is_empty());
             }
             Err(e) => panic!("Failed to get proof task: {}", e),
         }
     }
 
     #[tokio::test]
-    #[ignore] // This test requires a live orchestrator instance.
     /// Should return the user ID for a wallet address.
     async fn test_get_user() {
-        let client = super::OrchestratorClient::new(Environment::Production);
-        let wallet_address = "0x1234567890abcdef1234567890cbaabc12345678"; // Example wallet address
-        match client.get_user(wallet_address).await {
-            Ok(user_id) => println!("User ID: {}", user_id),
+        let (_server, client) = start().await;
+        let (user_id, _) = register(&client).await;
+        match client.get_user(WALLET_ADDRESS).await {
+            Ok(found) => assert_eq!(found, user_id),
             Err(e) => panic!("Failed to get user: {}", e),
         }
     }
 
     #[tokio::test]
-    #[ignore] // This test requires a live orchestrator instance.
     /// Should return the wallet address for a node ID.
     async fn test_get_node() {
-        let client = super::OrchestratorClient::new(Environment::Production);
-        let node_id = "5880437"; // Example node ID
-        match client.get_node(node_id).await {
-            Ok(wallet_address) => println!("Wallet address: {}", wallet_address),
+        let (_server, client) = start().await;
+        let (_, node_id) = register(&client).await;
+        match client.get_node(&node_id).await {
+            Ok(wallet_address) => assert_eq!(wallet_address, WALLET_ADDRESS),
             Err(e) => panic!("Failed to get node: {}", e),
         }
     }
-
-    #[tokio::test]
-    #[ignore] // This test requires a live orchestrator instance.
-    /// Should detect the country for network optimization.
-    async fn test_country_detection() {
-        let client = super::OrchestratorClient::new(Environment::Production);
-        let country = client.get_country().await;
-        println!("Detected country: {}", country);
-    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,705 @@
+//! Local orchestrator stand-in
+//!
+//! Serves the `v3/users`, `v3/nodes`, `v3/tasks` and `v3/tasks/submit` endpoints over plain
+//! HTTP/1.1, speaking the protobuf messages from `proto/orchestrator.proto`. Tasks are handed
+//! out from a fixed queue of `fib_input_initial` inputs, and every submission is recorded once
+//! its Ed25519 signature has been checked against the key the task was issued to.
+//!
+//! This lets `start --headless --orchestrator-url http://127.0.0.1:PORT --max-tasks N` run
+//! end-to-end without a production orchestrator, both in CI and on dev boxes.
+
+use crate::network::upload::ContentEncoding;
+use crate::nexus_orchestrator::{
+    GetNodeResponse, GetProofTaskRequest, GetProofTaskResponse, Node, RegisterNodeRequest,
+    RegisterNodeResponse, RegisterUserRequest, SubmitProofRequest, Task, TaskDifficulty, TaskType,
+    UserResponse,
+};
+use crate::orchestrator::client::signature_message;
+use crate::orchestrator::error::OrchestratorError;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use prost::Message;
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// First node ID handed out by `v3/nodes`.
+const FIRST_NODE_ID: u64 = 1_000_000;
+
+/// Wallet address reported for nodes that were never registered with the stand-in.
+const UNREGISTERED_NODE_WALLET: &str = "0x0000000000000000000000000000000000000000";
+
+/// Queue configuration for the local orchestrator.
+#[derive(Debug, Clone)]
+pub struct DevOrchestratorConfig {
+    /// Number of tasks in the queue.
+    pub num_tasks: usize,
+    /// Number of `(n, init_a, init_b)` inputs in each task's `public_inputs_list`.
+    pub inputs_per_task: usize,
+    /// Base Fibonacci iteration count; each input adds its index so inputs are distinct.
+    pub fib_n: u32,
+    /// Task type assigned to every task.
+    pub task_type: TaskType,
+    /// Difficulty assigned to every task.
+    pub difficulty: TaskDifficulty,
+}
+
+impl Default for DevOrchestratorConfig {
+    fn default() -> Self {
+        Self {
+            num_tasks: 10,
+            inputs_per_task: 1,
+            fib_n: 9,
+            task_type: TaskType::ProofRequired,
+            difficulty: TaskDifficulty::SmallMedium,
+        }
+    }
+}
+
+impl DevOrchestratorConfig {
+    /// Build the `index`-th task of the queue.
+    fn build_task(&self, index: usize) -> Task {
+        let inputs_per_task = self.inputs_per_task.max(1);
+        let public_inputs_list = (0..inputs_per_task)
+            .map(|input_index| {
+                let n = self.fib_n + (index * inputs_per_task + input_index) as u32;
+                [n, 1u32, 1u32]
+                    .iter()
+                    .flat_map(|value| value.to_le_bytes())
+                    .collect::<Vec<u8>>()
+            })
+            .collect();
+
+        Task {
+            task_id: format!("dev-task-{}", index + 1),
+            program_id: "fib_input_initial".to_string(),
+            public_inputs_list,
+            task_type: self.task_type as i32,
+            difficulty: self.difficulty as i32,
+            ..Default::default()
+        }
+    }
+}
+
+/// A proof submission accepted by the local orchestrator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedSubmission {
+    pub task_id: String,
+    pub proof_hash: String,
+    pub task_type: TaskType,
+    /// Number of full proofs attached to the submission.
+    pub num_proofs: usize,
+    pub all_proof_hashes: Vec<String>,
+    pub ed25519_public_key: Vec<u8>,
+}
+
+/// A task that was handed out and is waiting for its proof.
+#[derive(Debug, Clone)]
+struct IssuedTask {
+    task_type: TaskType,
+    ed25519_public_key: Vec<u8>,
+}
+
+/// Users, nodes, tasks and submissions known to the local orchestrator.
+#[derive(Debug, Default)]
+struct DevState {
+    /// Lowercased wallet address -> user ID
+    users_by_wallet: HashMap<String, String>,
+    /// Node ID -> (node type, owning user ID)
+    nodes: HashMap<String, (i32, String)>,
+    next_node_id: u64,
+    queue: VecDeque<Task>,
+    issued: HashMap<String, IssuedTask>,
+    submissions: Vec<RecordedSubmission>,
+}
+
+/// HTTP response produced by a route handler.
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn ok<T: Message>(message: &T) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/octet-stream",
+            body: message.encode_to_vec(),
+        }
+    }
+
+    fn empty() -> Self {
+        Self {
+            status: 200,
+            content_type: "application/octet-stream",
+            body: Vec::new(),
+        }
+    }
+
+    /// Error in the orchestrator's `{ name, message, httpCode }` JSON format.
+    fn error(status: u16, name: &str, message: &str) -> Self {
+        let body = serde_json::json!({
+            "name": name,
+            "message": message,
+            "httpCode": status,
+        });
+        Self {
+            status,
+            content_type: "application/json",
+            body: body.to_string().into_bytes(),
+        }
+    }
+
+    /// A typed orchestrator error, encoded with the name and status the client decodes back
+    /// into the same variant.
+    fn typed_error(error: OrchestratorError) -> Self {
+        let (status, body, _) = error
+            .http_parts()
+            .expect("typed errors have an HTTP encoding");
+        Self {
+            status,
+            content_type: "application/json",
+            body: body.into_bytes(),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            404 => "Not Found",
+            410 => "Gone",
+            415 => "Unsupported Media Type",
+            _ => "Error",
+        }
+    }
+}
+
+impl DevState {
+    fn new(config: &DevOrchestratorConfig) -> Self {
+        Self {
+            next_node_id: FIRST_NODE_ID,
+            queue: (0..config.num_tasks)
+                .map(|index| config.build_task(index))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn get_user(&self, wallet_address: &str) -> Response {
+        let Some(user_id) = self.users_by_wallet.get(&wallet_address.to_lowercase()) else {
+            return Response::typed_error(OrchestratorError::UserNotFound {
+                message: "User not found".to_string(),
+            });
+        };
+
+        let mut nodes: Vec<Node> = self
+            .nodes
+            .iter()
+            .filter(|(_, (_, owner))| owner == user_id)
+            .map(|(node_id, (node_type, _))| Node {
+                node_id: node_id.clone(),
+                node_type: *node_type,
+            })
+            .collect();
+        nodes.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+
+        Response::ok(&UserResponse {
+            nodes,
+            nodes_next_cursor: String::new(),
+            user_id: user_id.clone(),
+            wallet_address: wallet_address.to_string(),
+        })
+    }
+
+    fn register_user(&mut self, request: RegisterUserRequest) -> Response {
+        self.users_by_wallet
+            .insert(request.wallet_address.to_lowercase(), request.uuid);
+        Response::empty()
+    }
+
+    fn register_node(&mut self, request: RegisterNodeRequest) -> Response {
+        if !self
+            .users_by_wallet
+            .values()
+            .any(|id| *id == request.user_id)
+        {
+            return Response::typed_error(OrchestratorError::UserNotFound {
+                message: "User not found".to_string(),
+            });
+        }
+
+        let node_id = self.next_node_id.to_string();
+        self.next_node_id += 1;
+        self.nodes
+            .insert(node_id.clone(), (request.node_type, request.user_id));
+
+        Response::ok(&RegisterNodeResponse { node_id })
+    }
+
+    /// Unknown nodes are accepted so that `start --node-id` works without registering first.
+    fn get_node(&self, node_id: &str) -> Response {
+        let wallet_address = self
+            .nodes
+            .get(node_id)
+            .and_then(|(_, owner)| {
+                self.users_by_wallet
+                    .iter()
+                    .find(|(_, user_id)| *user_id == owner)
+                    .map(|(wallet, _)| wallet.clone())
+            })
+            .unwrap_or_else(|| UNREGISTERED_NODE_WALLET.to_string());
+
+        Response::ok(&GetNodeResponse { wallet_address })
+    }
+
+    fn get_proof_task(&mut self, request: GetProofTaskRequest) -> Response {
+        let Some(task) = self.queue.pop_front() else {
+            return Response::error(404, "NotFoundError", "No tasks available");
+        };
+
+        self.issued.insert(
+            task.task_id.clone(),
+            IssuedTask {
+                task_type: TaskType::try_from(task.task_type).unwrap_or_default(),
+                ed25519_public_key: request.ed25519_public_key,
+            },
+        );
+
+        Response::ok(&GetProofTaskResponse {
+            task: Some(task),
+            ..Default::default()
+        })
+    }
+
+    fn submit_proof(&mut self, request: SubmitProofRequest) -> Response {
+        let Some(issued) = self.issued.get(&request.task_id) else {
+            return Response::typed_error(OrchestratorError::TaskExpired {
+                message: "Task not found".to_string(),
+            });
+        };
+
+        if issued.ed25519_public_key != request.ed25519_public_key {
+            return Response::typed_error(OrchestratorError::KeyRejected {
+                message: "Public key does not match the key the task was issued to".to_string(),
+            });
+        }
+
+        if let Err(e) = verify_signature(&request) {
+            return Response::typed_error(OrchestratorError::KeyRejected { message: e });
+        }
+
+        let task_type = issued.task_type;
+        self.issued.remove(&request.task_id);
+        self.submissions.push(RecordedSubmission {
+            task_id: request.task_id,
+            proof_hash: request.proof_hash,
+            task_type,
+            num_proofs: request.proofs.len(),
+            all_proof_hashes: request.all_proof_hashes,
+            ed25519_public_key: request.ed25519_public_key,
+        });
+
+        Response::empty()
+    }
+}
+
+/// Check the submission's signature over `signature_message(task_id, proof_hash)`.
+fn verify_signature(request: &SubmitProofRequest) -> Result<(), String> {
+    let key_bytes: [u8; 32] = request
+        .ed25519_public_key
+        .as_slice()
+        .try_into()
+        .map_err(|_| "Malformed Ed25519 public key".to_string())?;
+    let verifying_key =
+        VerifyingKey::from_bytes(&key_bytes).map_err(|e| format!("Invalid public key: {}", e))?;
+    let signature = Signature::from_slice(&request.signature)
+        .map_err(|e| format!("Malformed signature: {}", e))?;
+
+    let msg = signature_message(&request.task_id, &request.proof_hash);
+    verifying_key
+        .verify(msg.as_bytes(), &signature)
+        .map_err(|e| format!("Signature verification failed: {}", e))
+}
+
+fn decode<T: Message + Default>(body: &[u8]) -> Result<T, Response> {
+    T::decode(body).map_err(|e| Response::error(400, "BadRequestError", &e.to_string()))
+}
+
+/// Dispatch a request to its handler.
+fn route(state: &mut DevState, method: &str, path: &str, body: &[u8]) -> Response {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match (method, segments.as_slice()) {
+        ("GET", ["v3", "users", wallet]) => {
+            let wallet = urlencoding::decode(wallet)
+                .map(|decoded| decoded.into_owned())
+                .unwrap_or_else(|_| wallet.to_string());
+            state.get_user(&wallet)
+        }
+        ("POST", ["v3", "users"]) => match decode(body) {
+            Ok(request) => state.register_user(request),
+            Err(response) => response,
+        },
+        ("POST", ["v3", "nodes"]) => match decode(body) {
+            Ok(request) => state.register_node(request),
+            Err(response) => response,
+        },
+        ("GET", ["v3", "nodes", node_id]) => state.get_node(node_id),
+        ("POST", ["v3", "tasks"]) => match decode(body) {
+            Ok(request) => state.get_proof_task(request),
+            Err(response) => response,
+        },
+        ("POST", ["v3", "tasks", "submit"]) => match decode(body) {
+            Ok(request) => state.submit_proof(request),
+            Err(response) => response,
+        },
+        _ => Response::error(
+            404,
+            "NotFoundError",
+            &format!("No route for {} {}", method, path),
+        ),
+    }
+}
+
+fn lock(state: &Mutex<DevState>) -> MutexGuard<'_, DevState> {
+    match state.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Serve a single request on the connection, then close it.
+async fn handle_connection(stream: TcpStream, state: Arc<Mutex<DevState>>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).await? == 0 {
+        return Ok(());
+    }
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0usize;
+    let mut content_encoding = String::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            } else if name.trim().eq_ignore_ascii_case("content-encoding") {
+                content_encoding = value.trim().to_string();
+            }
+        }
+    }
+
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).await?;
+
+    let path = target.split('?').next().unwrap_or_default();
+    let response = match ContentEncoding::from_header_value(&content_encoding)
+        .map(|encoding| encoding.decode(&body))
+    {
+        Some(Ok(body)) => route(&mut lock(&state), &method, path, &body),
+        Some(Err(e)) => Response::error(
+            400,
+            "BadRequestError",
+            &format!("Invalid {} body: {}", content_encoding, e),
+        ),
+        None => Response::error(
+            415,
+            "UnsupportedMediaTypeError",
+            &format!("Unsupported Content-Encoding {}", content_encoding),
+        ),
+    };
+
+    let mut stream = reader.into_inner();
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.reason(),
+        response.content_type,
+        response.body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&response.body).await?;
+    stream.shutdown().await
+}
+
+/// A running local orchestrator. The server stops when this is dropped.
+pub struct DevOrchestrator {
+    local_addr: SocketAddr,
+    state: Arc<Mutex<DevState>>,
+    accept_handle: JoinHandle<()>,
+}
+
+impl DevOrchestrator {
+    /// Bind to `addr` (use port 0 for a free port) and start serving.
+    pub async fn bind(addr: SocketAddr, config: DevOrchestratorConfig) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(DevState::new(&config)));
+
+        let accept_state = Arc::clone(&state);
+        let accept_handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(handle_connection(stream, Arc::clone(&accept_state)));
+            }
+        });
+
+        Ok(Self {
+            local_addr,
+            state,
+            accept_handle,
+        })
+    }
+
+    /// Base URL to pass as `--orchestrator-url`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.local_addr)
+    }
+
+    /// Submissions accepted so far, in order.
+    pub fn submissions(&self) -> Vec<RecordedSubmission> {
+        lock(&self.state).submissions.clone()
+    }
+
+    /// Number of tasks that have not been handed out yet.
+    pub fn remaining_tasks(&self) -> usize {
+        lock(&self.state).queue.len()
+    }
+}
+
+impl Drop for DevOrchestrator {
+    fn drop(&mut self) {
+        self.accept_handle.abort();
+    }
+}
+
+/// Entry point for `nexus-network dev-orchestrator`: serve until Ctrl+C, then print a summary.
+pub async fn run(port: u16, config: DevOrchestratorConfig) -> Result<(), Box<dyn Error>> {
+    let num_tasks = config.num_tasks;
+    let server = DevOrchestrator::bind(SocketAddr::from(([127, 0, 0, 1], port)), config).await?;
+
+    crate::print_cmd_info!(
+        "Local orchestrator listening",
+        "{} ({} tasks queued)",
+        server.url(),
+        num_tasks
+    );
+    crate::print_cmd_info!(
+        "Usage",
+        "nexus-network start --headless --orchestrator-url {} --node-id {} --max-tasks {}",
+        server.url(),
+        FIRST_NODE_ID,
+        num_tasks
+    );
+
+    tokio::signal::ctrl_c().await?;
+
+    let submissions = server.submissions();
+    crate::print_cmd_success!(
+        "Local orchestrator stopped",
+        "{} submissions recorded, {} tasks never handed out",
+        submissions.len(),
+        server.remaining_tasks()
+    );
+    for submission in submissions {
+        println!(
+            "  {} ({}): hash {}, {} proofs, {} proof hashes",
+            submission.task_id,
+            submission.task_type.as_str_name(),
+            submission.proof_hash,
+            submission.num_proofs,
+            submission.all_proof_hashes.len()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::Environment;
+    use crate::orchestrator::{Orchestrator, OrchestratorClient};
+    use ed25519_dalek::SigningKey;
+
+    const WALLET: &str = "0x1234567890abcdef1234567890abcdef12345678";
+
+    async fn start(config: DevOrchestratorConfig) -> (DevOrchestrator, OrchestratorClient) {
+        let server = DevOrchestrator::bind(SocketAddr::from(([127, 0, 0, 1], 0)), config)
+            .await
+            .expect("failed to bind local orchestrator");
+        let client = OrchestratorClient::new(Environment::Custom {
+            orchestrator_url: server.url(),
+        });
+        (server, client)
+    }
+
+    #[tokio::test]
+    /// Registering a user and a node should make both visible through the lookup endpoints.
+    async fn test_register_and_lookup() {
+        let (_server, client) = start(DevOrchestratorConfig::default()).await;
+
+        client
+            .register_user("user-1", WALLET)
+            .await
+            .expect("register_user failed");
+        assert_eq!(client.get_user(WALLET).await.unwrap(), "user-1");
+
+        let node_id = client.register_node("user-1").await.unwrap();
+        assert_eq!(node_id, FIRST_NODE_ID.to_string());
+        assert_eq!(client.get_node(&node_id).await.unwrap(), WALLET);
+
+        let second_node_id = client.register_node("user-1").await.unwrap();
+        let page = client.get_user_page(WALLET).await.unwrap();
+        assert_eq!(page.user_id, "user-1");
+        assert_eq!(
+            page.nodes
+                .iter()
+                .map(|node| node.node_id.clone())
+                .collect::<Vec<_>>(),
+            vec![node_id, second_node_id]
+        );
+        assert!(!page.more_nodes);
+    }
+
+    #[tokio::test]
+    /// A task proven and signed by the node it was issued to should be recorded.
+    async fn test_records_signed_submission() {
+        let config = DevOrchestratorConfig {
+            num_tasks: 1,
+            inputs_per_task: 3,
+            ..Default::default()
+        };
+        let (server, client) = start(config).await;
+        let signing_key = SigningKey::generate(&mut rand_core::OsRng);
+
+        let result = client
+            .get_proof_task("42", signing_key.verifying_key(), TaskDifficulty::Small)
+            .await
+            .expect("get_proof_task failed");
+        assert_eq!(result.task.program_id, "fib_input_initial");
+        assert_eq!(result.task.public_inputs_list.len(), 3);
+        assert_eq!(result.task.public_inputs_list[0].len(), 12);
+        assert_eq!(server.remaining_tasks(), 0);
+
+        client
+            .submit_proof(
+                &result.task.task_id,
+                "deadbeef",
+                vec![1],
+                vec![vec![1], vec![2], vec![3]],
+                signing_key.clone(),
+                1,
+                TaskType::ProofRequired,
+                &[],
+            )
+            .await
+            .expect("submit_proof failed");
+
+        let submissions = server.submissions();
+        assert_eq!(submissions.len(), 1);
+        assert_eq!(submissions[0].task_id, result.task.task_id);
+        assert_eq!(submissions[0].proof_hash, "deadbeef");
+        assert_eq!(submissions[0].num_proofs, 3);
+        assert_eq!(
+            submissions[0].ed25519_public_key,
+            signing_key.verifying_key().to_bytes().to_vec()
+        );
+    }
+
+    #[tokio::test]
+    /// A submission signed with a different key than the task was issued to is rejected.
+    async fn test_rejects_submission_from_other_key() {
+        let (server, client) = start(DevOrchestratorConfig::default()).await;
+        let signing_key = SigningKey::generate(&mut rand_core::OsRng);
+        let other_key = SigningKey::generate(&mut rand_core::OsRng);
+
+        let result = client
+            .get_proof_task("42", signing_key.verifying_key(), TaskDifficulty::Small)
+            .await
+            .unwrap();
+
+        let err = client
+            .submit_proof(
+                &result.task.task_id,
+                "deadbeef",
+                Vec::new(),
+                Vec::new(),
+                other_key,
+                1,
+                TaskType::ProofHash,
+                &[],
+            )
+            .await
+            .expect_err("submission with the wrong key should fail");
+        assert!(matches!(err, OrchestratorError::KeyRejected { .. }));
+        assert!(server.submissions().is_empty());
+    }
+
+    #[tokio::test]
+    /// Once the queue is drained, task requests fail with 404.
+    async fn test_empty_queue_returns_not_found() {
+        let config = DevOrchestratorConfig {
+            num_tasks: 0,
+            ..Default::default()
+        };
+        let (_server, client) = start(config).await;
+        let signing_key = SigningKey::generate(&mut rand_core::OsRng);
+
+        let err = client
+            .get_proof_task("42", signing_key.verifying_key(), TaskDifficulty::Small)
+            .await
+            .expect_err("empty queue should not return a task");
+        assert!(matches!(err, OrchestratorError::Http { status: 404, .. }));
+    }
+
+    #[tokio::test]
+    /// Compressed request bodies are decoded; unknown encodings are refused.
+    async fn test_accepts_compressed_bodies() {
+        let (server, client) = start(DevOrchestratorConfig::default()).await;
+        let request = crate::nexus_orchestrator::RegisterUserRequest {
+            uuid: "user-1".to_string(),
+            wallet_address: WALLET.to_string(),
+        };
+        let body = ContentEncoding::Zstd
+            .encode(&request.encode_to_vec())
+            .unwrap();
+
+        let http = reqwest::Client::new();
+        let url = format!("{}/v3/users", server.url());
+        let response = http
+            .post(&url)
+            .header("Content-Encoding", "zstd")
+            .body(body.clone())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(client.get_user(WALLET).await.unwrap(), "user-1");
+
+        let response = http
+            .post(&url)
+            .header("Content-Encoding", "br")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 415);
+    }
+}
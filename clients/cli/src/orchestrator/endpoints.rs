@@ -0,0 +1,356 @@
+//! Orchestrator endpoint pool
+//!
+//! Tracks the health and latency of each orchestrator endpoint of an environment. Requests go
+//! to the active endpoint; when it stops answering the pool fails over to the next healthy one,
+//! and switches back to the preferred endpoint once the cool-down has passed and probes show it
+//! healthy again.
+
+use crate::consts::cli_consts::endpoints;
+use crate::environment::Environment;
+use crate::network::http;
+use crate::orchestrator::client::USER_AGENT;
+use reqwest::{Client, StatusCode};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// Health of one orchestrator endpoint, as last observed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointStatus {
+    pub url: String,
+    /// False after a failed request or probe, until the next success
+    pub healthy: bool,
+    /// Round-trip time of the last successful request or probe
+    pub latency: Option<Duration>,
+}
+
+#[derive(Debug)]
+struct PoolState {
+    endpoints: Vec<EndpointStatus>,
+    /// Index of the endpoint requests go to
+    active: usize,
+    /// When the pool last switched to a fallback endpoint
+    failed_over_at: Option<Instant>,
+}
+
+/// Orchestrator endpoints in order of preference, and the one currently in use
+#[derive(Debug)]
+pub struct EndpointPool {
+    state: Mutex<PoolState>,
+    cool_down: Duration,
+}
+
+impl EndpointPool {
+    /// Creates a pool over `urls`, preferred first, starting on the preferred endpoint.
+    pub fn new(urls: Vec<String>, cool_down: Duration) -> Self {
+        assert!(!urls.is_empty(), "an endpoint pool needs at least one URL");
+        let endpoints = urls
+            .into_iter()
+            .map(|url| EndpointStatus {
+                url,
+                healthy: true,
+                latency: None,
+            })
+            .collect();
+        Self {
+            state: Mutex::new(PoolState {
+                endpoints,
+                active: 0,
+                failed_over_at: None,
+            }),
+            cool_down,
+        }
+    }
+
+    /// Creates a pool over the environment's orchestrator URLs.
+    pub fn for_environment(environment: &Environment) -> Self {
+        let urls = environment
+            .orchestrator_urls()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        Self::new(urls, endpoints::fail_back_cool_down())
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, PoolState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Number of endpoints in the pool.
+    pub fn endpoint_count(&self) -> usize {
+        self.state().endpoints.len()
+    }
+
+    /// URL of the endpoint requests currently go to.
+    pub fn active_url(&self) -> String {
+        self.active().url
+    }
+
+    /// Status of the endpoint requests currently go to.
+    pub fn active(&self) -> EndpointStatus {
+        let state = self.state();
+        state.endpoints[state.active].clone()
+    }
+
+    /// Status of every endpoint, preferred first.
+    pub fn endpoints(&self) -> Vec<EndpointStatus> {
+        self.state().endpoints.clone()
+    }
+
+    /// Records that the endpoint answered, and how long it took.
+    pub fn record_success(&self, url: &str, latency: Duration) {
+        let mut state = self.state();
+        if let Some(endpoint) = state.endpoints.iter_mut().find(|e| e.url == url) {
+            endpoint.healthy = true;
+            endpoint.latency = Some(latency);
+        }
+    }
+
+    /// Records that the endpoint did not answer. If it was the active endpoint, switches to the
+    /// next healthy one, or simply the next one if none is known to be healthy. Returns true if
+    /// the active endpoint changed.
+    pub fn record_failure(&self, url: &str) -> bool {
+        let mut state = self.state();
+        let Some(index) = state.endpoints.iter().position(|e| e.url == url) else {
+            return false;
+        };
+        state.endpoints[index].healthy = false;
+
+        let count = state.endpoints.len();
+        if index != state.active || count == 1 {
+            return false;
+        }
+        let next = (1..count)
+            .map(|offset| (index + offset) % count)
+            .find(|&candidate| state.endpoints[candidate].healthy)
+            .unwrap_or((index + 1) % count);
+        state.active = next;
+        state.failed_over_at = Some(Instant::now());
+        true
+    }
+
+    /// Switches back to the most preferred healthy endpoint once the cool-down since the last
+    /// failover has passed. Returns true if the active endpoint changed.
+    pub fn fail_back(&self) -> bool {
+        self.fail_back_at(Instant::now())
+    }
+
+    fn fail_back_at(&self, now: Instant) -> bool {
+        let mut state = self.state();
+        if state
+            .failed_over_at
+            .is_some_and(|at| now.saturating_duration_since(at) < self.cool_down)
+        {
+            return false;
+        }
+        let Some(preferred) = state.endpoints[..state.active]
+            .iter()
+            .position(|endpoint| endpoint.healthy)
+        else {
+            return false;
+        };
+        state.active = preferred;
+        if preferred == 0 {
+            state.failed_over_at = None;
+        }
+        true
+    }
+
+    /// Probes every endpoint once, concurrently. A 2xx response from the base URL counts as
+    /// healthy, and so does a 404, which an orchestrator without a route there answers with.
+    /// Server errors and unreachable endpoints count as failures.
+    async fn probe_all(&self, client: &Client) {
+        let probes = self.endpoints().into_iter().map(|endpoint| async move {
+            let started = Instant::now();
+            let healthy = client
+                .get(&endpoint.url)
+                .header("User-Agent", USER_AGENT)
+                .send()
+                .await
+                .is_ok_and(|response| {
+                    response.status().is_success() || response.status() == StatusCode::NOT_FOUND
+                });
+            (endpoint.url, healthy.then(|| started.elapsed()))
+        });
+        for (url, latency) in futures::future::join_all(probes).await {
+            match latency {
+                Some(latency) => self.record_success(&url, latency),
+                None => {
+                    self.record_failure(&url);
+                }
+            }
+        }
+    }
+
+    /// Starts probing every endpoint periodically until shutdown, failing back to the preferred
+    /// endpoint when it is healthy again.
+    pub fn spawn_probes(self: &Arc<Self>, mut shutdown: broadcast::Receiver<()>) -> JoinHandle<()> {
+        let pool = Arc::clone(self);
+        tokio::spawn(async move {
+            let Ok(client) = http::client_builder()
+                .timeout(endpoints::probe_timeout())
+                .build()
+            else {
+                return;
+            };
+            let mut interval = tokio::time::interval(endpoints::probe_interval());
+            loop {
+                tokio::select! {
+                    _ = shutdown.recv() => break,
+                    _ = interval.tick() => {
+                        pool.probe_all(&client).await;
+                        pool.fail_back();
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::dev_server::{DevOrchestrator, DevOrchestratorConfig};
+    use crate::orchestrator::{Orchestrator, OrchestratorClient};
+    use std::net::SocketAddr;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    const COOL_DOWN: Duration = Duration::from_secs(60);
+
+    fn pool(urls: &[&str]) -> EndpointPool {
+        EndpointPool::new(urls.iter().map(|url| url.to_string()).collect(), COOL_DOWN)
+    }
+
+    /// A local URL with nothing listening on it.
+    fn unreachable_url() -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}", listener.local_addr().unwrap())
+    }
+
+    #[test]
+    fn test_failure_switches_to_next_healthy_endpoint() {
+        let pool = pool(&["http://a", "http://b", "http://c"]);
+        assert_eq!(pool.active_url(), "http://a");
+
+        // Failures of an endpoint that is not active do not move the pool
+        assert!(!pool.record_failure("http://b"));
+        assert_eq!(pool.active_url(), "http://a");
+
+        assert!(pool.record_failure("http://a"));
+        assert_eq!(pool.active_url(), "http://c");
+
+        // With nothing known to be healthy, keep rotating
+        assert!(pool.record_failure("http://c"));
+        assert_eq!(pool.active_url(), "http://a");
+    }
+
+    #[test]
+    fn test_single_endpoint_never_switches() {
+        let pool = pool(&["http://a"]);
+        assert!(!pool.record_failure("http://a"));
+        assert_eq!(pool.active_url(), "http://a");
+        assert!(!pool.active().healthy);
+    }
+
+    #[test]
+    fn test_fail_back_waits_for_cool_down_and_health() {
+        let pool = pool(&["http://a", "http://b"]);
+        pool.record_failure("http://a");
+        assert_eq!(pool.active_url(), "http://b");
+
+        // The preferred endpoint is still unhealthy
+        let later = Instant::now() + COOL_DOWN * 2;
+        assert!(!pool.fail_back_at(later));
+
+        // Healthy again, but still cooling down
+        pool.record_success("http://a", Duration::from_millis(12));
+        assert!(!pool.fail_back_at(Instant::now()));
+        assert_eq!(pool.active_url(), "http://b");
+
+        assert!(pool.fail_back_at(later));
+        assert_eq!(pool.active_url(), "http://a");
+        assert_eq!(pool.active().latency, Some(Duration::from_millis(12)));
+    }
+
+    #[tokio::test]
+    async fn test_probes_record_health_and_latency() {
+        let server = DevOrchestrator::bind(
+            SocketAddr::from(([127, 0, 0, 1], 0)),
+            DevOrchestratorConfig::default(),
+        )
+        .await
+        .unwrap();
+        let dead = unreachable_url();
+        let pool = EndpointPool::new(vec![server.url(), dead.clone()], COOL_DOWN);
+
+        pool.probe_all(&http::client_builder().build().unwrap())
+            .await;
+        let endpoints = pool.endpoints();
+        assert!(endpoints[0].healthy);
+        assert!(endpoints[0].latency.is_some());
+        assert!(!endpoints[1].healthy);
+        assert_eq!(pool.active_url(), server.url());
+    }
+
+    /// A local URL answering every request with 503 Service Unavailable.
+    async fn unavailable_url() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                    .await;
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_probes_count_server_errors_as_failures() {
+        let server = DevOrchestrator::bind(
+            SocketAddr::from(([127, 0, 0, 1], 0)),
+            DevOrchestratorConfig::default(),
+        )
+        .await
+        .unwrap();
+        let pool = EndpointPool::new(vec![unavailable_url().await, server.url()], COOL_DOWN);
+
+        pool.probe_all(&http::client_builder().build().unwrap())
+            .await;
+        let endpoints = pool.endpoints();
+        assert!(!endpoints[0].healthy);
+        assert!(endpoints[1].healthy);
+        assert_eq!(pool.active_url(), server.url());
+    }
+
+    #[tokio::test]
+    /// A client whose preferred endpoint is down should carry on against the next one.
+    async fn test_client_fails_over_to_reachable_endpoint() {
+        let server = DevOrchestrator::bind(
+            SocketAddr::from(([127, 0, 0, 1], 0)),
+            DevOrchestratorConfig::default(),
+        )
+        .await
+        .unwrap();
+        let environment = Environment::custom(vec![unreachable_url(), server.url()]).unwrap();
+        let client = OrchestratorClient::new(environment);
+
+        client
+            .register_user("user-1", "0x1234567890abcdef1234567890abcdef12345678")
+            .await
+            .expect("request should fail over to the reachable endpoint");
+
+        let pool = client
+            .endpoints()
+            .expect("HTTP clients use an endpoint pool");
+        assert_eq!(pool.active_url(), server.url());
+        assert!(pool.active().latency.is_some());
+        assert!(!pool.endpoints()[0].healthy);
+    }
+}
@@ -1,8 +1,10 @@
 //! Error handling for the orchestrator module
 
+use crate::network::retry_policy::{parse_rate_limit_reset, parse_retry_after};
 use prost::DecodeError;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
 use thiserror::Error;
 
 #[allow(non_snake_case)] // used for json parsing
@@ -13,6 +15,19 @@ struct RawError {
     httpCode: u16,
 }
 
+/// How a worker should react to a failed request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Remedy {
+    /// Wait and try again; the problem is expected to pass
+    BackOff,
+    /// Give up on the task, the orchestrator no longer accepts its proof
+    DropTask,
+    /// Stop the node until it has been registered again
+    ReRegister,
+    /// Stop the node, retrying cannot succeed
+    Stop,
+}
+
 #[derive(Debug, Error)]
 pub enum OrchestratorError {
     /// Failed to decode a Protobuf message from the server
@@ -30,6 +45,41 @@ pub enum OrchestratorError {
         message: String,
         headers: HashMap<String, String>,
     },
+
+    /// The circuit breaker is open, so the request was not sent.
+    #[error("Orchestrator unavailable, next probe in {}s", retry_in.as_secs())]
+    Unavailable { retry_in: Duration },
+
+    /// The node ID is not registered with the orchestrator.
+    #[error("Node not found: {message}")]
+    NodeNotFound { message: String },
+
+    /// The wallet address is not registered with the orchestrator.
+    #[error("User not found: {message}")]
+    UserNotFound { message: String },
+
+    /// Too many requests; the headers tell how long to wait.
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        message: String,
+        headers: HashMap<String, String>,
+    },
+
+    /// The orchestrator no longer accepts this CLI version.
+    #[error("CLI version rejected, please upgrade: {message}")]
+    VersionRejected { message: String },
+
+    /// The orchestrator did not accept the node's signing key or signature.
+    #[error("Signing key rejected: {message}")]
+    KeyRejected { message: String },
+
+    /// The task expired or is no longer assigned to the node.
+    #[error("Task expired: {message}")]
+    TaskExpired { message: String },
+
+    /// The node or its wallet has been banned.
+    #[error("Banned: {message}")]
+    Banned { message: String },
 }
 
 impl OrchestratorError {
@@ -49,30 +99,115 @@ impl OrchestratorError {
             .await
             .unwrap_or_else(|_| "Failed to read response text".to_string());
 
-        OrchestratorError::Http {
-            status,
-            message,
-            headers,
+        OrchestratorError::from_http(status, message, headers)
+    }
+
+    /// Decodes an error response into a typed variant when the orchestrator's error name
+    /// identifies one, and into `Http` otherwise, whatever the status. `body` is either the
+    /// `{ name, message, httpCode }` JSON or plain text. Unrecognized names stay `Http`, so
+    /// they are handled by status as before. The local orchestrator encodes its errors with
+    /// `http_parts`, so it always uses the names recognized here.
+    pub fn from_http(status: u16, body: String, headers: HashMap<String, String>) -> Self {
+        let Ok(RawError { name, message, .. }) = serde_json::from_str::<RawError>(&body) else {
+            return Self::Http {
+                status,
+                message: body,
+                headers,
+            };
+        };
+
+        match name.as_str() {
+            "RateLimitError" | "TooManyRequestsError" => Self::RateLimited { message, headers },
+            "VersionRejectedError" | "UpgradeRequiredError" => Self::VersionRejected { message },
+            "BannedError" => Self::Banned { message },
+            "KeyRejectedError" | "InvalidSignatureError" | "UnauthorizedError" => {
+                Self::KeyRejected { message }
+            }
+            "TaskExpiredError" => Self::TaskExpired { message },
+            "NodeNotFoundError" => Self::NodeNotFound { message },
+            "UserNotFoundError" => Self::UserNotFound { message },
+            _ => Self::Http {
+                status,
+                message: body,
+                headers,
+            },
         }
     }
 
-    /// Get the Retry-After header value in seconds, if present
-    pub fn get_retry_after_seconds(&self) -> Option<u32> {
-        match self {
+    /// Status, body and headers of the response the error was decoded from. Typed variants
+    /// are re-encoded with the name and status that decode to them again. `None` for errors
+    /// that never reached the orchestrator.
+    pub fn http_parts(&self) -> Option<(u16, String, HashMap<String, String>)> {
+        let (status, name, message) = match self {
             Self::Http {
-                status, headers, ..
-            } => {
-                if (500..=599).contains(status) {
-                    return Some(30);
-                }
-                headers
-                    .get("retry-after")
-                    .and_then(|value| value.parse::<u32>().ok())
+                status,
+                message,
+                headers,
+            } => return Some((*status, message.clone(), headers.clone())),
+            Self::RateLimited { message, headers } => {
+                let raw = RawError {
+                    name: "RateLimitError".to_string(),
+                    message: message.clone(),
+                    httpCode: 429,
+                };
+                let body = serde_json::to_string(&raw).unwrap_or_default();
+                return Some((429, body, headers.clone()));
+            }
+            Self::NodeNotFound { message } => (404, "NodeNotFoundError", message),
+            Self::UserNotFound { message } => (404, "UserNotFoundError", message),
+            Self::VersionRejected { message } => (426, "VersionRejectedError", message),
+            Self::KeyRejected { message } => (401, "KeyRejectedError", message),
+            Self::TaskExpired { message } => (410, "TaskExpiredError", message),
+            Self::Banned { message } => (403, "BannedError", message),
+            Self::Decode(_) | Self::Reqwest(_) | Self::Unavailable { .. } => return None,
+        };
+        let raw = RawError {
+            name: name.to_string(),
+            message: message.clone(),
+            httpCode: status,
+        };
+        Some((
+            status,
+            serde_json::to_string(&raw).unwrap_or_default(),
+            HashMap::new(),
+        ))
+    }
+
+    /// How a worker should react to the error.
+    pub fn remedy(&self) -> Remedy {
+        match self {
+            Self::NodeNotFound { .. } | Self::UserNotFound { .. } | Self::KeyRejected { .. } => {
+                Remedy::ReRegister
             }
+            Self::VersionRejected { .. } | Self::Banned { .. } => Remedy::Stop,
+            Self::TaskExpired { .. } => Remedy::DropTask,
+            Self::Decode(_)
+            | Self::Reqwest(_)
+            | Self::Http { .. }
+            | Self::Unavailable { .. }
+            | Self::RateLimited { .. } => Remedy::BackOff,
+        }
+    }
+
+    /// Response headers, for errors that carry them
+    fn headers(&self) -> Option<&HashMap<String, String>> {
+        match self {
+            Self::Http { headers, .. } | Self::RateLimited { headers, .. } => Some(headers),
             _ => None,
         }
     }
 
+    /// Delay requested by the server before the next attempt, from `Retry-After` (seconds or
+    /// HTTP-date) or from exhausted `X-RateLimit-*` headers
+    pub fn server_retry_delay(&self) -> Option<Duration> {
+        let headers = self.headers()?;
+        let now = SystemTime::now();
+        headers
+            .get("retry-after")
+            .and_then(|value| parse_retry_after(value, now))
+            .or_else(|| parse_rate_limit_reset(headers, now))
+    }
+
     pub fn to_pretty(&self) -> Option<String> {
         match self {
             Self::Http {
@@ -98,7 +233,7 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_get_retry_after_seconds() {
+    fn test_server_retry_delay() {
         let mut headers = HashMap::new();
         headers.insert("retry-after".to_string(), "120".to_string());
 
@@ -108,22 +243,22 @@ mod tests {
             headers,
         };
 
-        assert_eq!(error.get_retry_after_seconds(), Some(120));
+        assert_eq!(error.server_retry_delay(), Some(Duration::from_secs(120)));
     }
 
     #[test]
-    fn test_get_retry_after_seconds_missing_header() {
+    fn test_server_retry_delay_missing_header() {
         let error = OrchestratorError::Http {
             status: 429,
             message: "Rate limited".to_string(),
             headers: HashMap::new(),
         };
 
-        assert_eq!(error.get_retry_after_seconds(), None);
+        assert_eq!(error.server_retry_delay(), None);
     }
 
     #[test]
-    fn test_get_retry_after_seconds_invalid_value() {
+    fn test_server_retry_delay_invalid_value() {
         let mut headers = HashMap::new();
         headers.insert("retry-after".to_string(), "invalid".to_string());
 
@@ -133,6 +268,124 @@ mod tests {
             headers,
         };
 
-        assert_eq!(error.get_retry_after_seconds(), None);
+        assert_eq!(error.server_retry_delay(), None);
+    }
+
+    #[test]
+    fn test_server_retry_delay_from_rate_limit_headers() {
+        let mut headers = HashMap::new();
+        headers.insert("x-ratelimit-remaining".to_string(), "0".to_string());
+        headers.insert("x-ratelimit-reset".to_string(), "15".to_string());
+
+        let error = OrchestratorError::Http {
+            status: 429,
+            message: "Rate limited".to_string(),
+            headers,
+        };
+
+        assert_eq!(error.server_retry_delay(), Some(Duration::from_secs(15)));
+    }
+
+    fn decode(status: u16, name: &str, message: &str) -> OrchestratorError {
+        let body = serde_json::json!({ "name": name, "message": message, "httpCode": status });
+        OrchestratorError::from_http(status, body.to_string(), HashMap::new())
+    }
+
+    #[test]
+    fn test_from_http_decodes_typed_variants() {
+        assert!(matches!(
+            decode(404, "NodeNotFoundError", "Node not found"),
+            OrchestratorError::NodeNotFound { .. }
+        ));
+        assert!(matches!(
+            decode(404, "UserNotFoundError", "User not found"),
+            OrchestratorError::UserNotFound { .. }
+        ));
+        assert!(matches!(
+            decode(410, "TaskExpiredError", "Task not found"),
+            OrchestratorError::TaskExpired { .. }
+        ));
+        assert!(matches!(
+            decode(400, "VersionRejectedError", "Minimum version is 1.2.0"),
+            OrchestratorError::VersionRejected { .. }
+        ));
+        assert!(matches!(
+            decode(401, "UnauthorizedError", "Invalid signature"),
+            OrchestratorError::KeyRejected { .. }
+        ));
+        assert!(matches!(
+            decode(403, "BannedError", "Node is banned"),
+            OrchestratorError::Banned { .. }
+        ));
+        let error = OrchestratorError::from_http(
+            429,
+            r#"{"name":"RateLimitError","message":"slow down","httpCode":429}"#.to_string(),
+            HashMap::from([("retry-after".to_string(), "5".to_string())]),
+        );
+        assert!(matches!(error, OrchestratorError::RateLimited { .. }));
+        assert_eq!(error.server_retry_delay(), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_from_http_keeps_unknown_errors_raw() {
+        // The status and message alone do not identify a typed variant
+        for (status, name, message) in [
+            (404, "NotFoundError", "Node not found"),
+            (404, "NotFoundError", "No tasks available"),
+            (401, "AuthError", "Session expired"),
+            (403, "ForbiddenError", "Node is banned"),
+            (410, "GoneError", "expired"),
+        ] {
+            let error = decode(status, name, message);
+            assert!(
+                matches!(&error, OrchestratorError::Http { status: s, .. } if *s == status),
+                "{} {} decoded to {:?}",
+                status,
+                name,
+                error
+            );
+            assert_eq!(error.remedy(), Remedy::BackOff);
+        }
+
+        // Plain-text bodies carry no name
+        let error = OrchestratorError::from_http(
+            429,
+            "slow down".to_string(),
+            HashMap::from([("retry-after".to_string(), "5".to_string())]),
+        );
+        assert!(matches!(error, OrchestratorError::Http { status: 429, .. }));
+        assert_eq!(error.server_retry_delay(), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_http_parts_decode_to_the_same_variant() {
+        for error in [
+            decode(404, "NodeNotFoundError", "Node not found"),
+            decode(403, "BannedError", "banned"),
+            decode(426, "UpgradeRequiredError", "too old"),
+            decode(410, "TaskExpiredError", "expired"),
+        ] {
+            let (status, body, headers) = error.http_parts().unwrap();
+            let decoded = OrchestratorError::from_http(status, body, headers);
+            assert_eq!(decoded.to_string(), error.to_string());
+            assert_eq!(decoded.remedy(), error.remedy());
+        }
+    }
+
+    #[test]
+    fn test_remedies() {
+        assert_eq!(
+            decode(404, "NodeNotFoundError", "gone").remedy(),
+            Remedy::ReRegister
+        );
+        assert_eq!(decode(403, "BannedError", "banned").remedy(), Remedy::Stop);
+        assert_eq!(
+            decode(410, "TaskExpiredError", "expired").remedy(),
+            Remedy::DropTask
+        );
+        assert_eq!(
+            decode(503, "ServiceUnavailableError", "busy").remedy(),
+            Remedy::BackOff
+        );
     }
 }
@@ -0,0 +1,443 @@
+//! gRPC transport for the Nexus Orchestrator
+//!
+//! Selected for `grpc://` (plaintext) and `grpcs://` (TLS) orchestrator URLs. Requests and
+//! responses are the same protobuf messages the HTTP client sends, so tasks and submissions
+//! are built by the shared helpers in [`crate::orchestrator::client`].
+//!
+//! The service definition in proto/orchestrator_service.proto is a proposal, not one published
+//! by the orchestrator team, so this transport only talks to servers built from that file.
+//!
+//! gRPC connections do not go through the `--proxy` and `--ca-bundle` settings, which only
+//! apply to HTTP clients; TLS trusts the bundled web PKI roots.
+
+use crate::environment::Environment;
+use crate::nexus_orchestrator::{
+    GetProofTaskRequest, NodeType, RegisterNodeRequest, RegisterUserRequest, TaskDifficulty,
+    TaskType,
+};
+use crate::orchestrator::Orchestrator;
+use crate::orchestrator::client::{
+    BUILD_TIMESTAMP, ProofTaskResult, USER_AGENT, UserPage, detect_country_once,
+    submit_proof_request,
+};
+use crate::orchestrator::error::OrchestratorError;
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use std::collections::HashMap;
+use std::time::Duration;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tonic::{Code, Request, Status};
+
+/// Generated gRPC service stubs and the service's own request messages. Every other message
+/// is the HTTP client's, declared external in build.rs.
+#[allow(clippy::all)]
+pub(crate) mod proto {
+    tonic::include_proto!("nexus.orchestrator");
+}
+
+use proto::orchestrator_client::OrchestratorClient as ServiceClient;
+use proto::{GetNodeRequest, GetUserRequest};
+
+#[derive(Debug, Clone)]
+pub struct GrpcOrchestratorClient {
+    client: ServiceClient<Channel>,
+    environment: Environment,
+}
+
+impl GrpcOrchestratorClient {
+    /// Creates a client for a `grpc://` or `grpcs://` environment. The connection is opened
+    /// on first use, so an unreachable orchestrator surfaces as a request error.
+    pub fn new(environment: Environment) -> Result<Self, tonic::transport::Error> {
+        let url = environment.orchestrator_url();
+        let (uri, tls) = match url.split_once("://") {
+            Some((scheme, rest)) if scheme.eq_ignore_ascii_case("grpcs") => {
+                (format!("https://{}", rest), true)
+            }
+            Some((_, rest)) => (format!("http://{}", rest), false),
+            None => (format!("http://{}", url), false),
+        };
+
+        let mut endpoint = Endpoint::from_shared(uri)?
+            .connect_timeout(Duration::from_secs(10))
+            .timeout(Duration::from_secs(10))
+            .user_agent(USER_AGENT)?;
+        if tls {
+            endpoint = endpoint.tls_config(ClientTlsConfig::new().with_webpki_roots())?;
+        }
+
+        Ok(Self {
+            client: ServiceClient::new(endpoint.connect_lazy()),
+            environment,
+        })
+    }
+
+    /// Wraps a message with the metadata every orchestrator request carries.
+    fn request<T>(message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        if let Ok(value) = BUILD_TIMESTAMP.parse() {
+            request.metadata_mut().insert("x-build-timestamp", value);
+        }
+        request
+    }
+
+    /// Country code for submissions. A local orchestrator may be running offline.
+    async fn get_country(&self) -> String {
+        if self.environment.is_local() {
+            return "US".to_string();
+        }
+        detect_country_once().await
+    }
+}
+
+/// HTTP status equivalent of a gRPC status code, following the grpc-gateway mapping, so that
+/// retry policies and error handling treat both transports alike.
+fn http_status(code: Code) -> u16 {
+    match code {
+        Code::Ok => 200,
+        Code::InvalidArgument | Code::FailedPrecondition | Code::OutOfRange => 400,
+        Code::Unauthenticated => 401,
+        Code::PermissionDenied => 403,
+        Code::NotFound => 404,
+        Code::AlreadyExists | Code::Aborted => 409,
+        Code::ResourceExhausted => 429,
+        Code::Cancelled => 499,
+        Code::Unknown | Code::Internal | Code::DataLoss => 500,
+        Code::Unimplemented => 501,
+        Code::Unavailable => 503,
+        Code::DeadlineExceeded => 504,
+    }
+}
+
+impl From<Status> for OrchestratorError {
+    fn from(status: Status) -> Self {
+        // Response metadata plays the role of HTTP headers, e.g. `retry-after`
+        let headers: HashMap<String, String> = status
+            .metadata()
+            .clone()
+            .into_headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|value| (name.to_string().to_lowercase(), value.to_string()))
+            })
+            .collect();
+
+        OrchestratorError::from_http(
+            http_status(status.code()),
+            status.message().to_string(),
+            headers,
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl Orchestrator for GrpcOrchestratorClient {
+    fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    async fn get_user(&self, wallet_address: &str) -> Result<String, OrchestratorError> {
+        let request = GetUserRequest {
+            wallet_address: wallet_address.to_string(),
+        };
+        let response = self.client.clone().get_user(Self::request(request)).await?;
+        Ok(response.into_inner().user_id)
+    }
+
+    async fn get_user_page(&self, wallet_address: &str) -> Result<UserPage, OrchestratorError> {
+        let request = GetUserRequest {
+            wallet_address: wallet_address.to_string(),
+        };
+        let response = self.client.clone().get_user(Self::request(request)).await?;
+        Ok(UserPage::from(&response.into_inner()))
+    }
+
+    async fn register_user(
+        &self,
+        user_id: &str,
+        wallet_address: &str,
+    ) -> Result<(), OrchestratorError> {
+        let request = RegisterUserRequest {
+            uuid: user_id.to_string(),
+            wallet_address: wallet_address.to_string(),
+        };
+        self.client
+            .clone()
+            .register_user(Self::request(request))
+            .await?;
+        Ok(())
+    }
+
+    async fn register_node(&self, user_id: &str) -> Result<String, OrchestratorError> {
+        let request = RegisterNodeRequest {
+            node_type: NodeType::CliProver as i32,
+            user_id: user_id.to_string(),
+        };
+        let response = self
+            .client
+            .clone()
+            .register_node(Self::request(request))
+            .await?;
+        Ok(response.into_inner().node_id)
+    }
+
+    async fn get_node(&self, node_id: &str) -> Result<String, OrchestratorError> {
+        let request = GetNodeRequest {
+            node_id: node_id.to_string(),
+        };
+        let response = self.client.clone().get_node(Self::request(request)).await?;
+        Ok(response.into_inner().wallet_address)
+    }
+
+    async fn get_proof_task(
+        &self,
+        node_id: &str,
+        verifying_key: VerifyingKey,
+        max_difficulty: TaskDifficulty,
+    ) -> Result<ProofTaskResult, OrchestratorError> {
+        let request = GetProofTaskRequest {
+            node_id: node_id.to_string(),
+            node_type: NodeType::CliProver as i32,
+            ed25519_public_key: verifying_key.to_bytes().to_vec(),
+            max_difficulty: max_difficulty as i32,
+        };
+        let response = self
+            .client
+            .clone()
+            .get_proof_task(Self::request(request))
+            .await?;
+        Ok(ProofTaskResult::from(&response.into_inner()))
+    }
+
+    async fn submit_proof(
+        &self,
+        task_id: &str,
+        proof_hash: &str,
+        proof: Vec<u8>,
+        proofs: Vec<Vec<u8>>,
+        signing_key: SigningKey,
+        num_provers: usize,
+        task_type: TaskType,
+        individual_proof_hashes: &[String],
+    ) -> Result<(), OrchestratorError> {
+        let location = self.get_country().await;
+        let request = submit_proof_request(
+            task_id,
+            proof_hash,
+            proof,
+            proofs,
+            &signing_key,
+            num_provers,
+            task_type,
+            individual_proof_hashes,
+            location,
+        );
+        self.client
+            .clone()
+            .submit_proof(Self::request(request))
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nexus_orchestrator::{
+        GetNodeResponse, GetProofTaskResponse, RegisterNodeResponse, SubmitProofRequest, Task,
+        UserResponse,
+    };
+    use proto::orchestrator_server::{Orchestrator as OrchestratorService, OrchestratorServer};
+    use std::sync::{Arc, Mutex};
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::Response;
+    use tonic::metadata::MetadataValue;
+
+    /// A local orchestrator that hands out one task and records submissions.
+    #[derive(Default)]
+    struct TestService {
+        submissions: Arc<Mutex<Vec<SubmitProofRequest>>>,
+    }
+
+    #[tonic::async_trait]
+    impl OrchestratorService for TestService {
+        async fn get_user(
+            &self,
+            request: Request<GetUserRequest>,
+        ) -> Result<Response<UserResponse>, Status> {
+            let wallet_address = request.into_inner().wallet_address;
+            if wallet_address != "0xabc" {
+                return Err(Status::not_found("unknown wallet"));
+            }
+            Ok(Response::new(UserResponse {
+                user_id: "user-1".to_string(),
+                ..Default::default()
+            }))
+        }
+
+        async fn register_user(
+            &self,
+            _request: Request<RegisterUserRequest>,
+        ) -> Result<Response<()>, Status> {
+            Ok(Response::new(()))
+        }
+
+        async fn register_node(
+            &self,
+            _request: Request<RegisterNodeRequest>,
+        ) -> Result<Response<RegisterNodeResponse>, Status> {
+            Ok(Response::new(RegisterNodeResponse {
+                node_id: "42".to_string(),
+            }))
+        }
+
+        async fn get_node(
+            &self,
+            _request: Request<GetNodeRequest>,
+        ) -> Result<Response<GetNodeResponse>, Status> {
+            Ok(Response::new(GetNodeResponse {
+                wallet_address: "0xabc".to_string(),
+            }))
+        }
+
+        async fn get_proof_task(
+            &self,
+            request: Request<GetProofTaskRequest>,
+        ) -> Result<Response<GetProofTaskResponse>, Status> {
+            if request.into_inner().node_id == "busy" {
+                let mut status = Status::resource_exhausted("rate limited");
+                status
+                    .metadata_mut()
+                    .insert("retry-after", MetadataValue::from_static("7"));
+                return Err(status);
+            }
+            Ok(Response::new(GetProofTaskResponse {
+                task: Some(Task {
+                    task_id: "task-1".to_string(),
+                    program_id: "fib_input_initial".to_string(),
+                    public_inputs_list: vec![vec![1, 2, 3]],
+                    task_type: TaskType::ProofRequired as i32,
+                    difficulty: TaskDifficulty::Small as i32,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }))
+        }
+
+        async fn submit_proof(
+            &self,
+            request: Request<SubmitProofRequest>,
+        ) -> Result<Response<()>, Status> {
+            self.submissions.lock().unwrap().push(request.into_inner());
+            Ok(Response::new(()))
+        }
+    }
+
+    /// Serves `service` on an ephemeral local port and returns a client for it.
+    async fn start_server(service: TestService) -> GrpcOrchestratorClient {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            tonic::transport::Server::builder()
+                .add_service(OrchestratorServer::new(service))
+                .serve_with_incoming(TcpListenerStream::new(listener))
+                .await
+                .unwrap();
+        });
+
+        GrpcOrchestratorClient::new(Environment::Custom {
+            orchestrator_url: format!("grpc://{}", address),
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_proof_task_over_grpc() {
+        let client = start_server(TestService::default()).await;
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+
+        let result = client
+            .get_proof_task("1", signing_key.verifying_key(), TaskDifficulty::Large)
+            .await
+            .unwrap();
+
+        assert_eq!(result.task.task_id, "task-1");
+        assert_eq!(result.task.program_id, "fib_input_initial");
+        assert_eq!(result.actual_difficulty, TaskDifficulty::Small);
+    }
+
+    #[tokio::test]
+    async fn test_submit_proof_over_grpc() {
+        let service = TestService::default();
+        let submissions = service.submissions.clone();
+        let client = start_server(service).await;
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+
+        client
+            .submit_proof(
+                "task-1",
+                "hash",
+                vec![1, 2, 3],
+                vec![vec![1, 2, 3]],
+                signing_key.clone(),
+                1,
+                TaskType::ProofRequired,
+                &[],
+            )
+            .await
+            .unwrap();
+
+        let submissions = submissions.lock().unwrap();
+        assert_eq!(submissions.len(), 1);
+        assert_eq!(submissions[0].task_id, "task-1");
+        assert_eq!(
+            submissions[0].ed25519_public_key,
+            signing_key.verifying_key().to_bytes().to_vec()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_account_calls_over_grpc() {
+        let client = start_server(TestService::default()).await;
+
+        assert_eq!(client.get_user("0xabc").await.unwrap(), "user-1");
+        assert_eq!(
+            client.get_user_page("0xabc").await.unwrap().user_id,
+            "user-1"
+        );
+        assert_eq!(client.register_node("user-1").await.unwrap(), "42");
+        assert_eq!(client.get_node("42").await.unwrap(), "0xabc");
+        client.register_user("user-1", "0xabc").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_status_maps_to_http_error() {
+        let client = start_server(TestService::default()).await;
+
+        match client.get_user("0xdef").await {
+            Err(OrchestratorError::Http { status, .. }) => assert_eq!(status, 404),
+            other => panic!("expected HTTP-equivalent error, got {:?}", other),
+        }
+
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let error = client
+            .get_proof_task("busy", signing_key.verifying_key(), TaskDifficulty::Large)
+            .await
+            .unwrap_err();
+        match &error {
+            OrchestratorError::Http { status, .. } => assert_eq!(*status, 429),
+            other => panic!("expected HTTP-equivalent error, got {:?}", other),
+        }
+        assert_eq!(error.server_retry_delay(), Some(Duration::from_secs(7)));
+    }
+
+    #[test]
+    fn test_grpcs_uses_tls_endpoint() {
+        let client = GrpcOrchestratorClient::new(Environment::Custom {
+            orchestrator_url: "grpcs://orchestrator.example.com:443".to_string(),
+        });
+        assert!(client.is_ok());
+    }
+}
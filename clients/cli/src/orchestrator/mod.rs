@@ -1,10 +1,21 @@
-use crate::environment::Environment;
+use crate::environment::{Environment, Transport};
+use crate::network::upload::ContentEncoding;
+use crate::orchestrator::client::UserPage;
+use crate::orchestrator::endpoints::EndpointPool;
 use crate::orchestrator::error::OrchestratorError;
+use crate::orchestrator::traffic::{ReplayOrchestrator, TrafficRecorder};
 use ed25519_dalek::{SigningKey, VerifyingKey};
+use std::error::Error;
+use std::sync::Arc;
 
 pub(crate) mod client;
 pub use client::OrchestratorClient;
+pub mod dev_server;
+pub mod endpoints;
 pub mod error;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod traffic;
 
 #[cfg(test)]
 use mockall::{automock, predicate::*};
@@ -14,9 +25,18 @@ use mockall::{automock, predicate::*};
 pub trait Orchestrator: Send + Sync {
     fn environment(&self) -> &Environment;
 
+    /// The endpoints requests are spread over, for transports that fail over between them.
+    fn endpoints(&self) -> Option<Arc<EndpointPool>> {
+        None
+    }
+
     /// Get the user ID associated with a wallet address.
     async fn get_user(&self, wallet_address: &str) -> Result<String, OrchestratorError>;
 
+    /// Get a user and the first page of their registered nodes. Later pages are not requested,
+    /// since the query that selects them is not confirmed; `more_nodes` says whether they exist.
+    async fn get_user_page(&self, wallet_address: &str) -> Result<UserPage, OrchestratorError>;
+
     /// Registers a new user with the orchestrator.
     async fn register_user(
         &self,
@@ -52,3 +72,114 @@ pub trait Orchestrator: Send + Sync {
         individual_proof_hashes: &[String],
     ) -> Result<(), OrchestratorError>;
 }
+
+/// Shared orchestrator clients, so that one connection can serve several workers.
+#[async_trait::async_trait]
+impl<T: Orchestrator + ?Sized> Orchestrator for Arc<T> {
+    fn environment(&self) -> &Environment {
+        (**self).environment()
+    }
+
+    fn endpoints(&self) -> Option<Arc<EndpointPool>> {
+        (**self).endpoints()
+    }
+
+    async fn get_user(&self, wallet_address: &str) -> Result<String, OrchestratorError> {
+        (**self).get_user(wallet_address).await
+    }
+
+    async fn get_user_page(&self, wallet_address: &str) -> Result<UserPage, OrchestratorError> {
+        (**self).get_user_page(wallet_address).await
+    }
+
+    async fn register_user(
+        &self,
+        user_id: &str,
+        wallet_address: &str,
+    ) -> Result<(), OrchestratorError> {
+        (**self).register_user(user_id, wallet_address).await
+    }
+
+    async fn register_node(&self, user_id: &str) -> Result<String, OrchestratorError> {
+        (**self).register_node(user_id).await
+    }
+
+    async fn get_node(&self, node_id: &str) -> Result<String, OrchestratorError> {
+        (**self).get_node(node_id).await
+    }
+
+    async fn get_proof_task(
+        &self,
+        node_id: &str,
+        verifying_key: VerifyingKey,
+        max_difficulty: crate::nexus_orchestrator::TaskDifficulty,
+    ) -> Result<crate::orchestrator::client::ProofTaskResult, OrchestratorError> {
+        (**self)
+            .get_proof_task(node_id, verifying_key, max_difficulty)
+            .await
+    }
+
+    async fn submit_proof(
+        &self,
+        task_id: &str,
+        proof_hash: &str,
+        proof: Vec<u8>,
+        proofs: Vec<Vec<u8>>,
+        signing_key: SigningKey,
+        num_provers: usize,
+        task_type: crate::nexus_orchestrator::TaskType,
+        individual_proof_hashes: &[String],
+    ) -> Result<(), OrchestratorError> {
+        (**self)
+            .submit_proof(
+                task_id,
+                proof_hash,
+                proof,
+                proofs,
+                signing_key,
+                num_provers,
+                task_type,
+                individual_proof_hashes,
+            )
+            .await
+    }
+}
+
+/// Creates orchestrator clients with the request settings of the session
+#[derive(Debug, Clone, Default)]
+pub struct Connector {
+    /// Compression applied to HTTP request bodies
+    pub content_encoding: ContentEncoding,
+    /// Where HTTP exchanges are saved, when traffic is being recorded
+    pub recorder: Option<Arc<TrafficRecorder>>,
+    /// Recording that stands in for every orchestrator, when traffic is being replayed
+    pub replay: Option<Arc<ReplayOrchestrator>>,
+}
+
+impl Connector {
+    /// Creates an orchestrator client for the environment, using the transport its URL
+    /// selects.
+    pub fn connect(
+        &self,
+        environment: Environment,
+    ) -> Result<Arc<dyn Orchestrator>, Box<dyn Error>> {
+        if let Some(replay) = &self.replay {
+            return Ok(replay.clone());
+        }
+        match environment.transport() {
+            Transport::Http => Ok(Arc::new(
+                OrchestratorClient::new(environment)
+                    .with_content_encoding(self.content_encoding)
+                    .with_recorder(self.recorder.clone()),
+            )),
+            #[cfg(feature = "grpc")]
+            Transport::Grpc => Ok(Arc::new(grpc::GrpcOrchestratorClient::new(environment)?)),
+            #[cfg(not(feature = "grpc"))]
+            Transport::Grpc => Err(format!(
+                "{} requires gRPC support; rebuild with `--features grpc`",
+                environment.orchestrator_url()
+            )
+            .into()),
+        }
+    }
+}
@@ -0,0 +1,636 @@
+//! Recording and replay of orchestrator traffic
+//!
+//! With `--record-traffic <DIR>`, every request made by [`OrchestratorClient`] is saved as one
+//! numbered exchange: `<seq>.json` holds the method, endpoint, status, headers and the decoded
+//! request and response, and `<seq>.request.bin` / `<seq>.response.bin` hold the raw bytes.
+//!
+//! With `--replay-traffic <DIR>`, [`ReplayOrchestrator`] serves those exchanges back through the
+//! [`Orchestrator`] trait, in recorded order per endpoint, without touching the network.
+//!
+//! [`OrchestratorClient`]: crate::orchestrator::OrchestratorClient
+
+use crate::environment::Environment;
+use crate::nexus_orchestrator::{
+    GetNodeResponse, GetProofTaskRequest, GetProofTaskResponse, NodeTelemetry, NodeType,
+    RegisterNodeRequest, RegisterNodeResponse, RegisterUserRequest, SubmitProofRequest, Task,
+    TaskDifficulty, TaskType, UserResponse,
+};
+use crate::orchestrator::Orchestrator;
+use crate::orchestrator::client::{ProofTaskResult, UserPage, user_endpoint};
+use crate::orchestrator::error::OrchestratorError;
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TrafficError {
+    #[error("Could not access traffic recording {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid traffic recording {path}: {source}")]
+    Json {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+
+    #[error("No recorded traffic in {0}")]
+    Empty(PathBuf),
+}
+
+/// One request and its outcome, as stored in `<seq>.json`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Exchange {
+    pub sequence: u64,
+    /// RFC 3339 time the request completed
+    pub recorded_at: String,
+    pub method: String,
+    pub endpoint: String,
+    /// HTTP status, absent when no response arrived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// Transport error, when no response arrived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    /// Decoded request message, for reading. Replay uses the raw bytes.
+    pub request: Value,
+    /// Decoded response message, or the error body
+    pub response: Value,
+}
+
+impl Exchange {
+    fn file_name(sequence: u64, suffix: &str) -> String {
+        format!("{:06}.{}", sequence, suffix)
+    }
+}
+
+/// Outcome of one request, as seen by the HTTP client
+pub(crate) type ExchangeResult = Result<(u16, Vec<u8>), OrchestratorError>;
+
+/// Writes every orchestrator exchange to a directory
+#[derive(Debug)]
+pub struct TrafficRecorder {
+    dir: PathBuf,
+    next_sequence: AtomicU64,
+}
+
+impl TrafficRecorder {
+    /// Records into `dir`, continuing after any exchanges already there.
+    pub fn new(dir: &Path) -> Result<Self, TrafficError> {
+        fs::create_dir_all(dir).map_err(|source| TrafficError::Io {
+            path: dir.to_path_buf(),
+            source,
+        })?;
+        let last = load_exchanges(dir)?
+            .last()
+            .map(|exchange| exchange.sequence)
+            .unwrap_or(0);
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            next_sequence: AtomicU64::new(last + 1),
+        })
+    }
+
+    /// Saves one exchange. Raw bytes are written before the JSON file, so a listed exchange
+    /// is always complete.
+    pub(crate) fn record(
+        &self,
+        method: &str,
+        endpoint: &str,
+        request_body: Option<&[u8]>,
+        result: &ExchangeResult,
+        duration: Duration,
+    ) -> Result<(), TrafficError> {
+        let sequence = self.next_sequence.fetch_add(1, Ordering::SeqCst);
+        let request_body = request_body.unwrap_or_default();
+
+        let (status, headers, response_body, error) = match result {
+            Ok((status, body)) => (Some(*status), HashMap::new(), body.clone(), None),
+            Err(e) => match e.http_parts() {
+                Some((status, body, headers)) => (Some(status), headers, body.into_bytes(), None),
+                None => (None, HashMap::new(), Vec::new(), Some(e.to_string())),
+            },
+        };
+
+        let response = match result {
+            Ok(_) => message_json(method, endpoint, Direction::Response, &response_body),
+            Err(_) => serde_json::from_slice(&response_body)
+                .unwrap_or_else(|_| json!(String::from_utf8_lossy(&response_body))),
+        };
+        let exchange = Exchange {
+            sequence,
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            method: method.to_string(),
+            endpoint: endpoint.to_string(),
+            status,
+            headers,
+            error,
+            duration_ms: duration.as_millis() as u64,
+            request: message_json(method, endpoint, Direction::Request, request_body),
+            response,
+        };
+
+        self.write(&Exchange::file_name(sequence, "request.bin"), request_body)?;
+        self.write(
+            &Exchange::file_name(sequence, "response.bin"),
+            &response_body,
+        )?;
+        let json = serde_json::to_vec_pretty(&exchange).map_err(|source| TrafficError::Json {
+            path: self.dir.clone(),
+            source,
+        })?;
+        self.write(&Exchange::file_name(sequence, "json"), &json)
+    }
+
+    fn write(&self, name: &str, contents: &[u8]) -> Result<(), TrafficError> {
+        let path = self.dir.join(name);
+        fs::write(&path, contents).map_err(|source| TrafficError::Io { path, source })
+    }
+}
+
+/// All complete exchanges in `dir`, in sequence order
+fn load_exchanges(dir: &Path) -> Result<Vec<Exchange>, TrafficError> {
+    let io_error = |source| TrafficError::Io {
+        path: dir.to_path_buf(),
+        source,
+    };
+    let mut exchanges = Vec::new();
+    for entry in fs::read_dir(dir).map_err(io_error)? {
+        let path = entry.map_err(io_error)?.path();
+        if path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+        let contents = fs::read(&path).map_err(|source| TrafficError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        let exchange = serde_json::from_slice(&contents)
+            .map_err(|source| TrafficError::Json { path, source })?;
+        exchanges.push(exchange);
+    }
+    exchanges.sort_by_key(|exchange: &Exchange| exchange.sequence);
+    Ok(exchanges)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Request,
+    Response,
+}
+
+/// Decodes a request or response body into JSON, based on the endpoint it was sent to.
+/// Bytes fields are hex-encoded and enums use their protobuf names.
+fn message_json(method: &str, endpoint: &str, direction: Direction, bytes: &[u8]) -> Value {
+    fn decode<T: Message + Default>(bytes: &[u8], to_json: fn(&T) -> Value) -> Value {
+        match T::decode(bytes) {
+            Ok(message) => to_json(&message),
+            Err(_) => json!({ "undecoded": hex::encode(bytes) }),
+        }
+    }
+
+    let route = endpoint.trim_start_matches('/').trim_start_matches("v3/");
+    let is_get = method.eq_ignore_ascii_case("GET");
+    match (route, is_get, direction) {
+        ("users", false, Direction::Request) => decode(bytes, register_user_request_json),
+        (route, true, Direction::Response) if route.starts_with("users/") => {
+            decode(bytes, user_response_json)
+        }
+        ("nodes", false, Direction::Request) => decode(bytes, register_node_request_json),
+        ("nodes", false, Direction::Response) => decode(bytes, register_node_response_json),
+        (route, true, Direction::Response) if route.starts_with("nodes/") => {
+            decode(bytes, get_node_response_json)
+        }
+        ("tasks", false, Direction::Request) => decode(bytes, get_proof_task_request_json),
+        ("tasks", false, Direction::Response) => decode(bytes, get_proof_task_response_json),
+        ("tasks/submit", false, Direction::Request) => decode(bytes, submit_proof_request_json),
+        _ if bytes.is_empty() => Value::Null,
+        _ => json!({ "undecoded": hex::encode(bytes) }),
+    }
+}
+
+fn node_type_name(value: i32) -> Value {
+    NodeType::try_from(value)
+        .map(|node_type| json!(node_type.as_str_name()))
+        .unwrap_or_else(|_| json!(value))
+}
+
+fn task_type_name(value: i32) -> Value {
+    TaskType::try_from(value)
+        .map(|task_type| json!(task_type.as_str_name()))
+        .unwrap_or_else(|_| json!(value))
+}
+
+fn difficulty_name(value: i32) -> Value {
+    TaskDifficulty::try_from(value)
+        .map(|difficulty| json!(difficulty.as_str_name()))
+        .unwrap_or_else(|_| json!(value))
+}
+
+fn hex_list(list: &[Vec<u8>]) -> Value {
+    json!(list.iter().map(hex::encode).collect::<Vec<_>>())
+}
+
+fn register_user_request_json(request: &RegisterUserRequest) -> Value {
+    json!({
+        "uuid": request.uuid,
+        "wallet_address": request.wallet_address,
+    })
+}
+
+fn user_response_json(response: &UserResponse) -> Value {
+    json!({
+        "user_id": response.user_id,
+        "wallet_address": response.wallet_address,
+        "nodes": response.nodes.iter().map(|node| json!({
+            "node_id": node.node_id,
+            "node_type": node_type_name(node.node_type),
+        })).collect::<Vec<_>>(),
+        "nodes_next_cursor": response.nodes_next_cursor,
+    })
+}
+
+fn register_node_request_json(request: &RegisterNodeRequest) -> Value {
+    json!({
+        "node_type": node_type_name(request.node_type),
+        "user_id": request.user_id,
+    })
+}
+
+fn register_node_response_json(response: &RegisterNodeResponse) -> Value {
+    json!({ "node_id": response.node_id })
+}
+
+fn get_node_response_json(response: &GetNodeResponse) -> Value {
+    json!({ "wallet_address": response.wallet_address })
+}
+
+fn get_proof_task_request_json(request: &GetProofTaskRequest) -> Value {
+    json!({
+        "node_id": request.node_id,
+        "node_type": node_type_name(request.node_type),
+        "ed25519_public_key": hex::encode(&request.ed25519_public_key),
+        "max_difficulty": difficulty_name(request.max_difficulty),
+    })
+}
+
+fn task_json(task: &Task) -> Value {
+    json!({
+        "task_id": task.task_id,
+        "program_id": task.program_id,
+        "created_at": task.created_at.as_ref().map(|timestamp| timestamp.to_string()),
+        "public_inputs_list": hex_list(&task.public_inputs_list),
+        "task_type": task_type_name(task.task_type),
+        "difficulty": difficulty_name(task.difficulty),
+    })
+}
+
+fn get_proof_task_response_json(response: &GetProofTaskResponse) -> Value {
+    json!({ "task": response.task.as_ref().map(task_json) })
+}
+
+fn telemetry_json(telemetry: &NodeTelemetry) -> Value {
+    json!({
+        "flops_per_sec": telemetry.flops_per_sec,
+        "memory_used": telemetry.memory_used,
+        "memory_capacity": telemetry.memory_capacity,
+        "location": telemetry.location,
+    })
+}
+
+fn submit_proof_request_json(request: &SubmitProofRequest) -> Value {
+    json!({
+        "task_id": request.task_id,
+        "node_type": node_type_name(request.node_type),
+        "proof_hash": request.proof_hash,
+        "proof": hex::encode(&request.proof),
+        "proofs": hex_list(&request.proofs),
+        "all_proof_hashes": request.all_proof_hashes,
+        "node_telemetry": request.node_telemetry.as_ref().map(telemetry_json),
+        "ed25519_public_key": hex::encode(&request.ed25519_public_key),
+        "signature": hex::encode(&request.signature),
+    })
+}
+
+/// A recorded response waiting to be replayed
+#[derive(Debug)]
+struct RecordedResponse {
+    exchange: Exchange,
+    body: Vec<u8>,
+}
+
+impl RecordedResponse {
+    fn into_result(self) -> Result<Vec<u8>, OrchestratorError> {
+        match (self.exchange.status, self.exchange.error) {
+            (Some(status), _) if (200..300).contains(&status) => Ok(self.body),
+            (Some(status), _) => Err(OrchestratorError::from_http(
+                status,
+                String::from_utf8_lossy(&self.body).into_owned(),
+                self.exchange.headers,
+            )),
+            // Transport errors cannot be rebuilt; replay them as the server being unavailable
+            (None, error) => Err(OrchestratorError::Http {
+                status: 503,
+                message: format!(
+                    "Recorded transport error: {}",
+                    error.unwrap_or_else(|| "unknown".to_string())
+                ),
+                headers: HashMap::new(),
+            }),
+        }
+    }
+}
+
+/// Serves recorded exchanges back in place of a live orchestrator.
+///
+/// Each call takes the next recording for its method and endpoint, so fetches and submissions
+/// replay in their recorded order even when they interleave differently. Request contents are
+/// not compared with the recording.
+#[derive(Debug)]
+pub struct ReplayOrchestrator {
+    environment: Environment,
+    queues: Mutex<HashMap<(String, String), VecDeque<RecordedResponse>>>,
+}
+
+impl ReplayOrchestrator {
+    /// Loads every exchange recorded in `dir`.
+    pub fn load(dir: &Path, environment: Environment) -> Result<Self, TrafficError> {
+        let exchanges = load_exchanges(dir)?;
+        if exchanges.is_empty() {
+            return Err(TrafficError::Empty(dir.to_path_buf()));
+        }
+
+        let mut queues: HashMap<(String, String), VecDeque<RecordedResponse>> = HashMap::new();
+        for exchange in exchanges {
+            let path = dir.join(Exchange::file_name(exchange.sequence, "response.bin"));
+            let body = fs::read(&path).map_err(|source| TrafficError::Io { path, source })?;
+            queues
+                .entry((
+                    exchange.method.to_ascii_uppercase(),
+                    exchange.endpoint.clone(),
+                ))
+                .or_default()
+                .push_back(RecordedResponse { exchange, body });
+        }
+        Ok(Self {
+            environment,
+            queues: Mutex::new(queues),
+        })
+    }
+
+    fn next(&self, method: &str, endpoint: &str) -> Result<Vec<u8>, OrchestratorError> {
+        let recorded = self
+            .queues
+            .lock()
+            .unwrap()
+            .get_mut(&(method.to_string(), endpoint.to_string()))
+            .and_then(VecDeque::pop_front);
+        match recorded {
+            Some(recorded) => recorded.into_result(),
+            None => Err(OrchestratorError::Http {
+                status: 404,
+                message: format!("No recorded response left for {} {}", method, endpoint),
+                headers: HashMap::new(),
+            }),
+        }
+    }
+
+    fn next_message<T: Message + Default>(
+        &self,
+        method: &str,
+        endpoint: &str,
+    ) -> Result<T, OrchestratorError> {
+        let body = self.next(method, endpoint)?;
+        T::decode(body.as_slice()).map_err(OrchestratorError::Decode)
+    }
+}
+
+#[async_trait::async_trait]
+impl Orchestrator for ReplayOrchestrator {
+    fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    async fn get_user(&self, wallet_address: &str) -> Result<String, OrchestratorError> {
+        let endpoint = user_endpoint(wallet_address);
+        let response: UserResponse = self.next_message("GET", &endpoint)?;
+        Ok(response.user_id)
+    }
+
+    async fn get_user_page(&self, wallet_address: &str) -> Result<UserPage, OrchestratorError> {
+        let endpoint = user_endpoint(wallet_address);
+        let response: UserResponse = self.next_message("GET", &endpoint)?;
+        Ok(UserPage::from(&response))
+    }
+
+    async fn register_user(
+        &self,
+        _user_id: &str,
+        _wallet_address: &str,
+    ) -> Result<(), OrchestratorError> {
+        self.next("POST", "v3/users").map(|_| ())
+    }
+
+    async fn register_node(&self, _user_id: &str) -> Result<String, OrchestratorError> {
+        let response: RegisterNodeResponse = self.next_message("POST", "v3/nodes")?;
+        Ok(response.node_id)
+    }
+
+    async fn get_node(&self, node_id: &str) -> Result<String, OrchestratorError> {
+        let endpoint = format!("v3/nodes/{}", node_id);
+        let response: GetNodeResponse = self.next_message("GET", &endpoint)?;
+        Ok(response.wallet_address)
+    }
+
+    async fn get_proof_task(
+        &self,
+        _node_id: &str,
+        _verifying_key: VerifyingKey,
+        _max_difficulty: TaskDifficulty,
+    ) -> Result<ProofTaskResult, OrchestratorError> {
+        let response: GetProofTaskResponse = self.next_message("POST", "v3/tasks")?;
+        Ok(ProofTaskResult::from(&response))
+    }
+
+    async fn submit_proof(
+        &self,
+        _task_id: &str,
+        _proof_hash: &str,
+        _proof: Vec<u8>,
+        _proofs: Vec<Vec<u8>>,
+        _signing_key: SigningKey,
+        _num_provers: usize,
+        _task_type: TaskType,
+        _individual_proof_hashes: &[String],
+    ) -> Result<(), OrchestratorError> {
+        self.next("POST", "v3/tasks/submit").map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn task_response(task_id: &str) -> Vec<u8> {
+        GetProofTaskResponse {
+            task: Some(Task {
+                task_id: task_id.to_string(),
+                program_id: "fib_input_initial".to_string(),
+                public_inputs_list: vec![vec![1, 0, 0, 0]],
+                task_type: TaskType::ProofRequired as i32,
+                difficulty: TaskDifficulty::Small as i32,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+        .encode_to_vec()
+    }
+
+    fn record_session(recorder: &TrafficRecorder) {
+        let request = GetProofTaskRequest {
+            node_id: "7".to_string(),
+            node_type: NodeType::CliProver as i32,
+            ed25519_public_key: vec![0xab; 32],
+            max_difficulty: TaskDifficulty::Large as i32,
+        }
+        .encode_to_vec();
+        let rate_limited: ExchangeResult = Err(OrchestratorError::Http {
+            status: 429,
+            message: r#"{"name":"RateLimitError","message":"slow down","httpCode":429}"#
+                .to_string(),
+            headers: HashMap::from([("retry-after".to_string(), "3".to_string())]),
+        });
+
+        for (result, body) in [
+            (Ok((200, task_response("task-1"))), Some(request.as_slice())),
+            (rate_limited, Some(request.as_slice())),
+            (Ok((200, task_response("task-2"))), Some(request.as_slice())),
+        ] {
+            recorder
+                .record("POST", "v3/tasks", body, &result, Duration::from_millis(5))
+                .unwrap();
+        }
+        recorder
+            .record(
+                "POST",
+                "v3/tasks/submit",
+                None,
+                &Ok((200, Vec::new())),
+                Duration::from_millis(5),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_recorded_exchange_is_readable() {
+        let dir = tempdir().unwrap();
+        record_session(&TrafficRecorder::new(dir.path()).unwrap());
+
+        let exchanges = load_exchanges(dir.path()).unwrap();
+        assert_eq!(exchanges.len(), 4);
+        assert_eq!(
+            exchanges.iter().map(|e| e.sequence).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+
+        let first = &exchanges[0];
+        assert_eq!(first.request["node_id"], "7");
+        assert_eq!(first.request["max_difficulty"], "LARGE");
+        assert_eq!(first.response["task"]["task_id"], "task-1");
+        assert_eq!(first.response["task"]["public_inputs_list"][0], "01000000");
+        assert_eq!(
+            fs::read(dir.path().join("000001.response.bin")).unwrap(),
+            task_response("task-1")
+        );
+
+        let rate_limited = &exchanges[1];
+        assert_eq!(rate_limited.status, Some(429));
+        assert_eq!(rate_limited.response["name"], "RateLimitError");
+        assert_eq!(rate_limited.headers["retry-after"], "3");
+    }
+
+    #[test]
+    fn test_recorder_continues_existing_sequence() {
+        let dir = tempdir().unwrap();
+        record_session(&TrafficRecorder::new(dir.path()).unwrap());
+        record_session(&TrafficRecorder::new(dir.path()).unwrap());
+
+        let exchanges = load_exchanges(dir.path()).unwrap();
+        assert_eq!(exchanges.len(), 8);
+        assert_eq!(exchanges.last().unwrap().sequence, 8);
+    }
+
+    #[tokio::test]
+    async fn test_replay_serves_recorded_responses_in_order() {
+        let dir = tempdir().unwrap();
+        record_session(&TrafficRecorder::new(dir.path()).unwrap());
+        let replay = ReplayOrchestrator::load(dir.path(), Environment::Production).unwrap();
+        let verifying_key = SigningKey::from_bytes(&[1; 32]).verifying_key();
+
+        let first = replay
+            .get_proof_task("7", verifying_key, TaskDifficulty::Large)
+            .await
+            .unwrap();
+        assert_eq!(first.task.task_id, "task-1");
+
+        match replay
+            .get_proof_task("7", verifying_key, TaskDifficulty::Large)
+            .await
+        {
+            Err(error @ OrchestratorError::RateLimited { .. }) => {
+                assert_eq!(error.server_retry_delay(), Some(Duration::from_secs(3)));
+            }
+            other => panic!("expected recorded rate limit, got {:?}", other),
+        }
+
+        let second = replay
+            .get_proof_task("7", verifying_key, TaskDifficulty::Large)
+            .await
+            .unwrap();
+        assert_eq!(second.task.task_id, "task-2");
+
+        // Submissions replay independently of fetches
+        replay
+            .submit_proof(
+                "task-1",
+                "hash",
+                Vec::new(),
+                Vec::new(),
+                SigningKey::from_bytes(&[1; 32]),
+                1,
+                TaskType::ProofRequired,
+                &[],
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            replay
+                .get_proof_task("7", verifying_key, TaskDifficulty::Large)
+                .await,
+            Err(OrchestratorError::Http { status: 404, .. })
+        ));
+    }
+
+    #[test]
+    fn test_replay_of_empty_directory_fails() {
+        let dir = tempdir().unwrap();
+        assert!(matches!(
+            ReplayOrchestrator::load(dir.path(), Environment::Production),
+            Err(TrafficError::Empty(_))
+        ));
+    }
+}
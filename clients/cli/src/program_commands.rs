@@ -0,0 +1,76 @@
+//! `programs` subcommands for inspecting the guest program ELF cache.
+
+use crate::cli_messages::{print_error, print_info, print_success, print_warn};
+use crate::prover::program::ProgramRegistry;
+use crate::prover::program_cache::{ElfStatus, ProgramCache, sha256_hex};
+use std::error::Error;
+
+/// Lists the pinned ELFs of every registered program and where each is available from, then
+/// any cached ELFs without a pin.
+pub fn list_programs(cache: &ProgramCache) -> Result<(), Box<dyn Error>> {
+    let cached = cache.installed_elfs()?;
+    if let Some(dir) = cache.dir() {
+        print_info("Program cache", &dir.display().to_string());
+    }
+
+    let registry = ProgramRegistry::builtin();
+    for program_id in registry.ids() {
+        let program = registry.get(program_id)?;
+        let bundled = program.bundled_elf().map(sha256_hex);
+        println!("  {}", program_id);
+        for pin in cache.pins_for(program_id) {
+            let sha256 = pin.sha256.to_lowercase();
+            let available = if cached
+                .iter()
+                .any(|elf| elf.program_id == program_id && elf.sha256 == sha256)
+            {
+                "cached"
+            } else if bundled.as_deref() == Some(sha256.as_str()) {
+                "bundled"
+            } else {
+                "missing"
+            };
+            println!("    {}  {}", sha256, available);
+        }
+    }
+
+    for elf in &cached {
+        if matches!(cache.check(elf), ElfStatus::Unpinned) {
+            println!("  unpinned: {}", elf.path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Checks every cached ELF against its pinned hashes. Fails if any does not match.
+pub fn verify_programs(cache: &ProgramCache) -> Result<(), Box<dyn Error>> {
+    let cached = cache.installed_elfs()?;
+    let mut verified = 0;
+    let mut invalid = 0;
+
+    for elf in &cached {
+        match cache.check(elf) {
+            ElfStatus::Verified => verified += 1,
+            ElfStatus::Unpinned => {
+                print_warn("No pin for cached ELF", &elf.path.display().to_string())
+            }
+            ElfStatus::Invalid(e) => {
+                invalid += 1;
+                print_error("Cached ELF failed verification", Some(&e.to_string()));
+            }
+        }
+    }
+
+    if invalid > 0 {
+        return Err(format!("{} cached ELFs failed verification", invalid).into());
+    }
+    print_success(
+        "Program cache verified",
+        &format!(
+            "{} of {} cached ELFs match their pins",
+            verified,
+            cached.len()
+        ),
+    );
+    Ok(())
+}
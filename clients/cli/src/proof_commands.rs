@@ -0,0 +1,124 @@
+//! Offline `prove`, `verify` and `inspect-proof` subcommands, which need no orchestrator.
+
+use crate::cli_messages::{print_info, print_success};
+use crate::consts::cli_consts::prover_pool;
+use crate::prover::ProvingContext;
+use crate::prover::engine::ProvingEngine;
+use crate::prover::pipeline::ProvingPipeline;
+use crate::prover::program::ProgramRegistry;
+use nexus_sdk::stwo::seq::Proof;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Parses a public input given on the command line: comma-separated `u32` values, encoded
+/// little-endian like task inputs (e.g. `1000,1,1`), or the raw input bytes in hex.
+pub fn parse_input(value: &str) -> Result<Vec<u8>, String> {
+    if value.contains(',') {
+        return value
+            .split(',')
+            .map(|v| {
+                v.trim()
+                    .parse::<u32>()
+                    .map(u32::to_le_bytes)
+                    .map_err(|e| format!("invalid value '{}': {}", v.trim(), e))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(|values| values.concat());
+    }
+    hex::decode(value.trim())
+        .map_err(|e| format!("expected hex bytes or comma-separated values: {}", e))
+}
+
+/// Proves one input of a guest program and writes the postcard-encoded proof to `output`.
+pub async fn prove_offline(
+    proving: &ProvingContext,
+    program_id: &str,
+    input: &[u8],
+    output: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let program = ProgramRegistry::builtin().get(program_id)?;
+    let elf = Arc::new(proving.programs.resolve(program.as_ref()).await?);
+    print_info(
+        &format!("Proving {}", program_id),
+        &format!("Input: {}", program.describe_input(input)?),
+    );
+
+    let result = ProvingEngine::prove_input(proving, &program, &elf, input).await;
+    proving.pool.shutdown(prover_pool::stop_grace()).await;
+    let proof = result?;
+
+    let bytes = postcard::to_allocvec(&proof)?;
+    fs::write(output, &bytes)?;
+    print_success(
+        "Proof generated and verified",
+        &format!(
+            "{} ({} bytes, hash {})",
+            output.display(),
+            bytes.len(),
+            ProvingPipeline::generate_proof_hash(&proof)
+        ),
+    );
+    Ok(())
+}
+
+/// Verifies a proof file against the input it was generated for.
+pub async fn verify_offline(
+    proving: &ProvingContext,
+    program_id: &str,
+    input: &[u8],
+    proof_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let program = ProgramRegistry::builtin().get(program_id)?;
+    let elf = Arc::new(proving.programs.resolve(program.as_ref()).await?);
+    let proof = read_proof(proof_path)?;
+    let description = format!(
+        "{} for input {}",
+        proof_path.display(),
+        program.describe_input(input)?
+    );
+
+    let (_, verification_time) = proving
+        .verifier
+        .verify(program, elf, proof, input.to_vec())
+        .await?;
+    print_success(
+        "Proof verified",
+        &format!("{} in {:.1}s", description, verification_time.as_secs_f64()),
+    );
+    Ok(())
+}
+
+/// Prints the size of a proof file and the Keccak-256 hash a node would submit for it.
+pub fn inspect_proof(proof_path: &Path) -> Result<(), Box<dyn Error>> {
+    let proof = read_proof(proof_path)?;
+    let size = fs::metadata(proof_path)?.len();
+    print_info("Proof", &proof_path.display().to_string());
+    println!("  Size: {} bytes", size);
+    println!("  Hash: {}", ProvingPipeline::generate_proof_hash(&proof));
+    Ok(())
+}
+
+fn read_proof(path: &Path) -> Result<Proof, Box<dyn Error>> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    postcard::from_bytes(&bytes)
+        .map_err(|e| format!("{} is not a postcard-encoded proof: {}", path.display(), e).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_input() {
+        let expected: Vec<u8> = [1000u32, 1, 1]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        assert_eq!(parse_input("1000, 1,1").unwrap(), expected);
+        assert_eq!(parse_input(&hex::encode(&expected)).unwrap(), expected);
+        assert!(parse_input("1000,-1,1").is_err());
+        assert!(parse_input("not hex").is_err());
+    }
+}
@@ -0,0 +1,298 @@
+//! Per-input proof checkpoints of multi-input tasks
+//!
+//! Each proven input of a task is written to `<checkpoints dir>/<task ID>/<input index>.proof`
+//! as soon as it is done, so that a task interrupted by a crash or an upgrade only proves its
+//! missing inputs once resumed. A manifest next to the proofs fingerprints the task's program,
+//! type and inputs; checkpoints of a task whose fingerprint no longer matches are discarded.
+//! Resumed proofs are verified before use. A task resumes whenever it is fetched again, for
+//! instance when the orchestrator hands an unfinished task back out after a restart.
+//! Checkpoints are removed once the task's proof is submitted or the task fails for good, and
+//! expire otherwise.
+
+use crate::consts::cli_consts::checkpoints;
+use crate::fs_util::task_file_stem;
+use crate::network::spool::unix_now;
+use crate::task::Task;
+use nexus_sdk::stwo::seq::Proof;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// File name of a task's manifest
+const MANIFEST_FILE: &str = "task.manifest";
+
+/// File extension for checkpointed proofs
+const ENTRY_EXTENSION: &str = "proof";
+
+/// Get the checkpoints directory that sits next to the config file, typically
+/// ~/.nexus/checkpoints.
+pub fn get_checkpoint_dir(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .map(|parent| parent.join("checkpoints"))
+        .unwrap_or_else(|| PathBuf::from("checkpoints"))
+}
+
+/// The task a directory of checkpoints belongs to
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Manifest {
+    task_id: String,
+    /// [`TaskCheckpoints::fingerprint`] of the task
+    fingerprint: String,
+    /// Seconds since the Unix epoch when the first input was checkpointed
+    created_at: u64,
+}
+
+impl Manifest {
+    fn is_expired(&self) -> bool {
+        unix_now().saturating_sub(self.created_at) >= checkpoints::EXPIRY_SECS
+    }
+}
+
+/// On-disk proofs of the finished inputs of unfinished tasks
+pub struct TaskCheckpoints {
+    /// Checkpoints directory; without one nothing is checkpointed
+    dir: Option<PathBuf>,
+}
+
+impl TaskCheckpoints {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self { dir }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    /// Whether a task has enough inputs for checkpoints to be worth writing.
+    pub fn covers(task: &Task) -> bool {
+        task.all_inputs().len() > 1
+    }
+
+    /// SHA-256 of a task's program ID, type and inputs, so that checkpoints are only reused for
+    /// the task they were written for.
+    pub fn fingerprint(task: &Task) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(task.program_id.as_bytes());
+        hasher.update((task.task_type as i32).to_le_bytes());
+        for input in task.all_inputs() {
+            hasher.update((input.len() as u64).to_le_bytes());
+            hasher.update(input);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Proofs checkpointed for `task`, by input index. Checkpoints of an earlier task with the
+    /// same ID but a different fingerprint are discarded, and the task's manifest is written
+    /// so that proofs of its inputs can be checkpointed.
+    pub fn resume(&self, task: &Task) -> io::Result<HashMap<usize, Proof>> {
+        let Some(dir) = self.task_dir(&task.task_id) else {
+            return Ok(HashMap::new());
+        };
+        let fingerprint = Self::fingerprint(task);
+        match read_manifest(&dir) {
+            Some(manifest) if manifest.fingerprint == fingerprint => {}
+            _ => {
+                remove_dir(&dir)?;
+                fs::create_dir_all(&dir)?;
+                let manifest = Manifest {
+                    task_id: task.task_id.clone(),
+                    fingerprint,
+                    created_at: unix_now(),
+                };
+                let bytes = postcard::to_allocvec(&manifest)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                write_atomic(&dir.join(MANIFEST_FILE), &bytes)?;
+                return Ok(HashMap::new());
+            }
+        }
+
+        // Unreadable proofs are proven again
+        let mut proofs = HashMap::new();
+        for index in 0..task.all_inputs().len() {
+            let proof = fs::read(entry_path(&dir, index))
+                .ok()
+                .and_then(|bytes| postcard::from_bytes(&bytes).ok());
+            if let Some(proof) = proof {
+                proofs.insert(index, proof);
+            }
+        }
+        Ok(proofs)
+    }
+
+    /// Checkpoints the proof of one input of a task that [`Self::resume`] was called for.
+    pub fn store(&self, task_id: &str, index: usize, proof: &Proof) -> io::Result<()> {
+        let Some(dir) = self.task_dir(task_id) else {
+            return Ok(());
+        };
+        let bytes = postcard::to_allocvec(proof)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        write_atomic(&entry_path(&dir, index), &bytes)
+    }
+
+    /// Number of inputs of a task with a checkpointed proof that decodes.
+    pub fn proven_inputs(&self, task_id: &str) -> usize {
+        self.task_dir(task_id)
+            .and_then(|dir| fs::read_dir(dir).ok())
+            .map_or(0, |entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        path.extension().and_then(|ext| ext.to_str()) == Some(ENTRY_EXTENSION)
+                    })
+                    .filter(|path| {
+                        fs::read(path)
+                            .ok()
+                            .and_then(|bytes| postcard::from_bytes::<Proof>(&bytes).ok())
+                            .is_some()
+                    })
+                    .count()
+            })
+    }
+
+    /// Removes the checkpoints of a task, if there are any.
+    pub fn remove(&self, task_id: &str) -> io::Result<()> {
+        match self.task_dir(task_id) {
+            Some(dir) => remove_dir(&dir),
+            None => Ok(()),
+        }
+    }
+
+    /// Removes the checkpoints of tasks that expired, or whose manifest is unreadable.
+    pub fn remove_expired(&self) -> io::Result<()> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        for entry in entries {
+            let path = entry?.path();
+            if !path.is_dir() {
+                continue;
+            }
+            if !read_manifest(&path).is_some_and(|manifest| !manifest.is_expired()) {
+                remove_dir(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn task_dir(&self, task_id: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(task_file_stem(task_id)))
+    }
+}
+
+fn entry_path(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("{}.{}", index, ENTRY_EXTENSION))
+}
+
+fn read_manifest(dir: &Path) -> Option<Manifest> {
+    let bytes = fs::read(dir.join(MANIFEST_FILE)).ok()?;
+    postcard::from_bytes(&bytes).ok()
+}
+
+/// Writes under a temporary name and renames, so a crash never leaves a truncated file behind.
+fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, bytes)?;
+    fs::rename(&tmp, path)
+}
+
+fn remove_dir(dir: &Path) -> io::Result<()> {
+    match fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nexus_orchestrator::{TaskDifficulty, TaskType};
+    use tempfile::tempdir;
+
+    fn task(task_id: &str, inputs: &[&[u8]]) -> Task {
+        let mut task = Task::new(
+            task_id.to_string(),
+            "fib_input_initial".to_string(),
+            inputs[0].to_vec(),
+            TaskType::ProofRequired,
+            TaskDifficulty::Large,
+        );
+        task.public_inputs_list = inputs.iter().map(|input| input.to_vec()).collect();
+        task
+    }
+
+    #[test]
+    fn test_fingerprint_covers_program_type_and_inputs() {
+        let original = task("task-1", &[&[1, 2], &[3]]);
+        let fingerprint = TaskCheckpoints::fingerprint(&original);
+
+        // Moving a byte between inputs changes the fingerprint
+        assert_ne!(
+            fingerprint,
+            TaskCheckpoints::fingerprint(&task("task-1", &[&[1], &[2, 3]]))
+        );
+        let mut other_type = original.clone();
+        other_type.task_type = TaskType::ProofHash;
+        assert_ne!(fingerprint, TaskCheckpoints::fingerprint(&other_type));
+        let mut other_program = original.clone();
+        other_program.program_id = "other".to_string();
+        assert_ne!(fingerprint, TaskCheckpoints::fingerprint(&other_program));
+    }
+
+    #[test]
+    fn test_changed_task_discards_checkpoints() {
+        let dir = tempdir().unwrap();
+        let store = TaskCheckpoints::new(Some(dir.path().to_path_buf()));
+        let original = task("task-1", &[&[1], &[2]]);
+
+        assert!(store.resume(&original).unwrap().is_empty());
+        let task_dir = store.task_dir("task-1").unwrap();
+        fs::write(entry_path(&task_dir, 0), b"proof").unwrap();
+        // A checkpoint that does not decode is not counted as proven
+        assert_eq!(store.proven_inputs("task-1"), 0);
+
+        // Same task: the checkpoint is kept, though it is proven again
+        assert!(store.resume(&original).unwrap().is_empty());
+        assert!(entry_path(&task_dir, 0).exists());
+
+        // Same ID, different inputs
+        store.resume(&task("task-1", &[&[1], &[3]])).unwrap();
+        assert!(!entry_path(&task_dir, 0).exists());
+        store.remove_expired().unwrap();
+        assert!(task_dir.exists());
+
+        store.remove("task-1").unwrap();
+        assert!(!task_dir.exists());
+    }
+
+    #[test]
+    fn test_expired_checkpoints_are_removed() {
+        let dir = tempdir().unwrap();
+        let store = TaskCheckpoints::new(Some(dir.path().to_path_buf()));
+        store.resume(&task("task-1", &[&[1], &[2]])).unwrap();
+
+        let task_dir = store.task_dir("task-1").unwrap();
+        let mut manifest = read_manifest(&task_dir).unwrap();
+        manifest.created_at -= checkpoints::EXPIRY_SECS;
+        write_atomic(
+            &task_dir.join(MANIFEST_FILE),
+            &postcard::to_allocvec(&manifest).unwrap(),
+        )
+        .unwrap();
+
+        store.remove_expired().unwrap();
+        assert!(!task_dir.exists());
+    }
+}
@@ -0,0 +1,44 @@
+//! Prover subprocesses, caches and verifiers shared by the proofs of a session
+//!
+//! Built once at startup from the config file and command line, then handed to the workers
+//! through [`WorkerConfig`](crate::workers::core::WorkerConfig) and to the offline commands.
+
+use super::checkpoint::TaskCheckpoints;
+use super::pool::{PoolConfig, ProverPool};
+use super::program_cache::ProgramCache;
+use super::proof_cache::ProofCache;
+use super::verifier::{VerificationPolicy, VerificationPool};
+use crate::consts::cli_consts::verification;
+use std::sync::Arc;
+
+/// Shared proving state. Clones share the same subprocesses, caches and verifier threads.
+#[derive(Clone)]
+pub struct ProvingContext {
+    /// Prover subprocesses, under the configured limits
+    pub pool: Arc<ProverPool>,
+    /// Verifier threads, under the verification policy
+    pub verifier: Arc<VerificationPool>,
+    /// Verified guest program ELFs
+    pub programs: Arc<ProgramCache>,
+    /// Proofs of repeated inputs
+    pub proof_cache: Arc<ProofCache>,
+    /// Proofs of the inputs of unfinished tasks
+    pub checkpoints: Arc<TaskCheckpoints>,
+}
+
+impl Default for ProvingContext {
+    /// Subprocesses without limits, every proof verified, bundled ELFs only, and neither a proof
+    /// cache nor checkpoints
+    fn default() -> Self {
+        Self {
+            pool: Arc::new(ProverPool::new(PoolConfig::default())),
+            verifier: Arc::new(VerificationPool::new(
+                VerificationPolicy::default(),
+                verification::THREADS,
+            )),
+            programs: Arc::new(ProgramCache::new(None)),
+            proof_cache: Arc::new(ProofCache::new(None)),
+            checkpoints: Arc::new(TaskCheckpoints::new(None)),
+        }
+    }
+}
@@ -1,103 +1,247 @@
 //! Core proving engine
 
-use crate::prover::verifier;
-
+use super::context::ProvingContext;
+use super::limits::{ProverLimits, apply_memory_limit, arm_cpu_limit};
+use super::program::{GuestProgram, ProgramRegistry};
+use super::program_cache::{ProgramCache, ProgramElf};
+use super::protocol::{JobError, Request, Response, read_frame, write_frame};
 use super::types::ProverError;
 use crate::analytics::track_likely_oom_error;
+use crate::consts::cli_consts::prover_pool;
 use crate::environment::Environment;
 use crate::task::Task;
-use nexus_sdk::{
-    Local, Prover,
-    stwo::seq::{Proof, Stwo},
-};
-use postcard::from_bytes;
-use serde_json;
-use std::env;
-use std::process::Stdio;
+use nexus_sdk::{Local, stwo::seq::Proof, stwo::seq::Stwo};
+use std::collections::HashMap;
+use std::io::{self, Stdout};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
 
 /// Core proving engine for ZK proof generation
 pub struct ProvingEngine;
 
 impl ProvingEngine {
-    /// Create a Stwo prover instance for the fibonacci program
-    pub fn create_fib_prover() -> Result<Stwo<Local>, ProverError> {
-        let elf_bytes = include_bytes!("../../assets/fib_input_initial");
-        Stwo::<Local>::new_from_bytes(elf_bytes).map_err(|e| {
-            ProverError::Stwo(format!(
-                "Failed to load fib_input_initial guest program: {}",
-                e
-            ))
-        })
+    /// Subprocess entrypoint: serve prove jobs from the parent until it closes stdin or asks
+    /// for a shutdown, loading ELFs from `programs`. Proofs are not verified here; the exit
+    /// code is checked here.
+    pub fn serve_subprocess(
+        limits: ProverLimits,
+        programs: ProgramCache,
+    ) -> Result<(), ProverError> {
+        if let Some(memory_mb) = limits.memory_mb {
+            apply_memory_limit(memory_mb)?;
+        }
+        let stdout = Arc::new(Mutex::new(io::stdout()));
+        let mut stdin = io::stdin().lock();
+        send(
+            &stdout,
+            &Response::Ready {
+                pid: std::process::id(),
+            },
+        )?;
+        Self::spawn_heartbeats(Arc::clone(&stdout));
+
+        // ELFs stay loaded between jobs, keyed by program ID and hash
+        let mut elfs: HashMap<(String, String), Arc<[u8]>> = HashMap::new();
+        while let Some(request) = read_frame(&mut stdin)? {
+            match request {
+                Request::Prove {
+                    job_id,
+                    program_id,
+                    elf_sha256,
+                    input,
+                } => match Self::prove_job(
+                    &mut elfs, &programs, &limits, program_id, elf_sha256, &input,
+                ) {
+                    Ok(proof) => Self::send_proof(&stdout, job_id, &proof)?,
+                    Err(e) => send(
+                        &stdout,
+                        &Response::Failed {
+                            job_id,
+                            error: JobError::from(&e),
+                        },
+                    )?,
+                },
+                Request::Shutdown => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// Proves one input with the ELF the parent resolved and verified.
+    fn prove_job(
+        elfs: &mut HashMap<(String, String), Arc<[u8]>>,
+        programs: &ProgramCache,
+        limits: &ProverLimits,
+        program_id: String,
+        elf_sha256: String,
+        input: &[u8],
+    ) -> Result<Proof, ProverError> {
+        if let Some(cpu_secs) = limits.cpu_secs {
+            arm_cpu_limit(cpu_secs)?;
+        }
+        let program = ProgramRegistry::builtin().get(&program_id)?;
+        let elf = match elfs.get(&(program_id.clone(), elf_sha256.clone())) {
+            Some(elf) => Arc::clone(elf),
+            None => {
+                let elf = programs.load(program.as_ref(), &elf_sha256)?;
+                elfs.insert((program_id, elf_sha256), Arc::clone(&elf));
+                elf
+            }
+        };
+        program.prove(&elf, input)
     }
 
-    /// Subprocess entrypoint: generate proof without verification
-    pub fn prove_fib_subprocess(inputs: &(u32, u32, u32)) -> Result<Proof, ProverError> {
-        let prover = Self::create_fib_prover()?;
-        let (view, proof) = prover
-            .prove_with_input::<(), (u32, u32, u32)>(&(), inputs)
-            .map_err(|e| {
-                ProverError::Stwo(format!(
-                    "Failed to generate proof for inputs {:?}: {}",
-                    inputs, e
-                ))
-            })?;
-        // Check exit code in subprocess
-        verifier::ProofVerifier::check_exit_code(&view)?;
+    /// Streams a proof back to the parent in chunks.
+    fn send_proof(stdout: &Mutex<Stdout>, job_id: u64, proof: &Proof) -> Result<(), ProverError> {
+        let bytes = postcard::to_allocvec(proof)?;
+        for chunk in bytes.chunks(prover_pool::PROOF_CHUNK_BYTES) {
+            send(
+                stdout,
+                &Response::ProofChunk {
+                    job_id,
+                    bytes: chunk.to_vec(),
+                },
+            )?;
+        }
+        send(
+            stdout,
+            &Response::ProofDone {
+                job_id,
+                proof_len: bytes.len() as u64,
+                rss_bytes: resident_memory(),
+            },
+        )
+    }
 
-        Ok(proof)
+    /// Sends heartbeats for as long as the parent is reading them.
+    fn spawn_heartbeats(stdout: Arc<Mutex<Stdout>>) {
+        std::thread::spawn(move || {
+            loop {
+                std::thread::sleep(prover_pool::heartbeat_interval());
+                let heartbeat = Response::Heartbeat {
+                    rss_bytes: resident_memory(),
+                };
+                if send(&stdout, &heartbeat).is_err() {
+                    break;
+                }
+            }
+        });
     }
 
-    /// Generate proof for given inputs using the fibonacci program in a subprocess
+    /// Generate a proof for one public input of a task in a pooled subprocess, verified if the
+    /// verification policy asks for it. Returns the proof and the time verification took, if
+    /// it was verified.
     pub async fn prove_and_validate(
-        inputs: &(u32, u32, u32),
+        proving: &ProvingContext,
+        program: &Arc<dyn GuestProgram>,
+        elf: &Arc<ProgramElf>,
+        input: &[u8],
         task: &Task,
         environment: &Environment,
         client_id: &str,
+    ) -> Result<(Proof, Option<Duration>), ProverError> {
+        let verify = proving.verifier.is_due();
+        let result = Self::prove_with(proving, program, elf, input, verify).await;
+        if let Err(ProverError::OutOfMemory(_)) = &result {
+            // Killed by the kernel or aborted at the memory limit; track analytics event
+            tokio::spawn(track_likely_oom_error(
+                task.clone(),
+                environment.clone(),
+                client_id.to_string(),
+            ));
+        }
+        result
+    }
+
+    /// Generate and verify a proof for one public input in a pooled subprocess, whatever the
+    /// verification policy
+    pub async fn prove_input(
+        proving: &ProvingContext,
+        program: &Arc<dyn GuestProgram>,
+        elf: &Arc<ProgramElf>,
+        input: &[u8],
     ) -> Result<Proof, ProverError> {
-        // Spawn a subprocess for proof generation to isolate memory usage
-        let exe_path = env::current_exe()?;
-        let mut cmd = tokio::process::Command::new(exe_path);
-        cmd.arg("prove-fib-subprocess")
-            .arg("--inputs")
-            .arg(serde_json::to_string(inputs)?)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::inherit());
-
-        let output = cmd.output().await?;
-
-        if !output.status.success() {
-            if let Some(code) = output.status.code() {
-                if code == crate::consts::cli_consts::SUBPROCESS_SUSPECTED_OOM_CODE {
-                    // 128 + 9 = 137 means external sigkill, so likely killed by kernel due to OOM; track analytics event
-                    tokio::spawn(track_likely_oom_error(
-                        task.clone(),
-                        environment.clone(),
-                        client_id.to_string(),
-                    ));
-                }
+        let (proof, _) = Self::prove_with(proving, program, elf, input, true).await?;
+        Ok(proof)
+    }
 
-                if code == crate::consts::cli_consts::SUBPROCESS_INTERNAL_ERROR_CODE {
-                    // error happened inside the subprocess, and so we know that it may be useful information to the user
-                    return Err(ProverError::Subprocess(format!(
-                        "Error while proving within subprocess, captured error: [{}]",
-                        &String::from_utf8_lossy(&output.stderr)
-                    )));
-                }
-            }
+    async fn prove_with(
+        proving: &ProvingContext,
+        program: &Arc<dyn GuestProgram>,
+        elf: &Arc<ProgramElf>,
+        input: &[u8],
+        verify: bool,
+    ) -> Result<(Proof, Option<Duration>), ProverError> {
+        // Reject malformed inputs before handing them to a subprocess
+        program.describe_input(input)?;
 
-            return Err(ProverError::Subprocess(format!(
-                "Prover subprocess failed with status: {}",
-                output.status
-            )));
+        let proof = proving.pool.prove(program.id(), &elf.sha256, input).await?;
+        if !verify {
+            return Ok((proof, None));
         }
 
-        // Deserialize proof from subprocess stdout
-        let proof: Proof = from_bytes(&output.stdout)?;
+        // Verify proof in main process, off the async runtime
+        let (proof, verification_time) = proving
+            .verifier
+            .verify(Arc::clone(program), Arc::clone(elf), proof, input.to_vec())
+            .await?;
+        Ok((proof, Some(verification_time)))
+    }
 
-        // Verify proof in main process
-        let verify_prover = Self::create_fib_prover()?;
-        verifier::ProofVerifier::verify_proof(&proof, inputs, &verify_prover)?;
+    /// Verify a proof of a run of the program on the public input encoded in `input`. Blocks
+    /// while the verifier is loaded and the proof checked; async callers go through the
+    /// [`VerificationPool`](super::verifier::VerificationPool).
+    pub fn verify(
+        program: &dyn GuestProgram,
+        elf: &ProgramElf,
+        proof: &Proof,
+        input: &[u8],
+    ) -> Result<(), ProverError> {
+        program.verify(&Self::verifier(program, elf)?, proof, input)
+    }
 
-        Ok(proof)
+    /// Prover loaded with the ELF, kept for verifying later proofs of the same program.
+    fn verifier(
+        program: &dyn GuestProgram,
+        elf: &ProgramElf,
+    ) -> Result<Arc<Stwo<Local>>, ProverError> {
+        static VERIFIERS: OnceLock<Mutex<HashMap<String, Arc<Stwo<Local>>>>> = OnceLock::new();
+        let verifiers = || {
+            VERIFIERS
+                .get_or_init(Default::default)
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+        };
+        if let Some(verifier) = verifiers().get(&elf.sha256) {
+            return Ok(Arc::clone(verifier));
+        }
+        // Loaded without holding the lock, so that verifications with ELFs already loaded
+        // don't wait for it. When threads race to load the same ELF, the first one stored wins.
+        let verifier = Arc::new(program.load(&elf.bytes)?);
+        Ok(Arc::clone(
+            verifiers().entry(elf.sha256.clone()).or_insert(verifier),
+        ))
     }
 }
+
+/// Writes one frame to the parent, shared with the heartbeat thread.
+fn send(stdout: &Mutex<Stdout>, response: &Response) -> Result<(), ProverError> {
+    let mut stdout = stdout
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    write_frame(&mut *stdout, response)?;
+    Ok(())
+}
+
+/// Resident memory of this process in bytes
+fn resident_memory() -> u64 {
+    let pid = Pid::from(std::process::id() as usize);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing().with_memory(),
+    );
+    system.process(pid).map_or(0, |process| process.memory())
+}
@@ -1,17 +1,30 @@
 //! High-level proving interface
 
+use super::context::ProvingContext;
 use super::pipeline::ProvingPipeline;
-use super::types::ProverError;
+use super::types::{ProverError, ProverResult};
 use crate::environment::Environment;
 use crate::task::Task;
-use nexus_sdk::stwo::seq::Proof;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
 
 /// Proves a program with authenticated task inputs
 pub async fn authenticated_proving(
+    proving: &ProvingContext,
     task: &Task,
     environment: &Environment,
     client_id: &str,
-    num_workers: usize,
-) -> Result<(Vec<Proof>, String, Vec<String>), ProverError> {
-    ProvingPipeline::prove_authenticated(task, environment, client_id, num_workers).await
+    proving_permits: &Arc<Semaphore>,
+    cancellation: &CancellationToken,
+) -> Result<ProverResult, ProverError> {
+    ProvingPipeline::prove_authenticated(
+        proving,
+        task,
+        environment,
+        client_id,
+        proving_permits,
+        cancellation,
+    )
+    .await
 }
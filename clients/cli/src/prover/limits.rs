@@ -0,0 +1,188 @@
+//! Resource limits of prover subprocesses
+//!
+//! Each subprocess can be capped in address space (`RLIMIT_AS`) and in CPU time per job
+//! (`RLIMIT_CPU`), and each job in wall-clock time. The rlimits are set by the subprocess on
+//! itself, on Unix only; the wall-clock timeout is enforced by the parent on every platform.
+//! How a subprocess died tells which limit it breached, so each maps to its own
+//! [`ProverError`].
+
+use super::types::ProverError;
+use crate::consts::cli_consts::{SUBPROCESS_INTERNAL_ERROR_CODE, SUBPROCESS_SUSPECTED_OOM_CODE};
+#[cfg(unix)]
+use libc::{SIGABRT, SIGKILL, SIGXCPU};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::process::ExitStatus;
+use std::time::Duration;
+
+/// Limits on each prover subprocess, configurable under `prover_limits` in the config file
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ProverLimits {
+    /// Address space a subprocess may map (MiB)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<u64>,
+    /// CPU time a single job may use (seconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_secs: Option<u64>,
+    /// Wall-clock time a single job may take (seconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+impl ProverLimits {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+
+    /// The error for a subprocess that exited in the middle of a job.
+    pub fn exit_error(&self, status: Option<ExitStatus>) -> ProverError {
+        let Some(status) = status else {
+            return ProverError::Crashed("exit status unknown".to_string());
+        };
+        match (status.code(), signal(&status)) {
+            // 128 + 9 = 137 means external sigkill, so likely killed by kernel due to OOM
+            (Some(SUBPROCESS_SUSPECTED_OOM_CODE), _) | (_, Some(SIGKILL)) => {
+                ProverError::OutOfMemory(
+                    "killed by SIGKILL, likely by the kernel OOM killer".to_string(),
+                )
+            }
+            // A failed allocation aborts; so does a panic in release builds
+            (_, Some(SIGABRT)) if self.memory_mb.is_some() => ProverError::OutOfMemory(format!(
+                "aborted, likely on reaching the memory limit of {} MiB",
+                self.memory_mb.unwrap_or_default()
+            )),
+            (_, Some(SIGXCPU)) => {
+                ProverError::CpuTimeExceeded(Duration::from_secs(self.cpu_secs.unwrap_or_default()))
+            }
+            (Some(SUBPROCESS_INTERNAL_ERROR_CODE), _) => {
+                ProverError::Crashed("internal error, see the subprocess output above".to_string())
+            }
+            _ => ProverError::Crashed(status.to_string()),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+const SIGKILL: i32 = 9;
+#[cfg(not(unix))]
+const SIGABRT: i32 = 6;
+#[cfg(not(unix))]
+const SIGXCPU: i32 = 24;
+
+#[cfg(unix)]
+fn signal(status: &ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
+/// Caps the address space of the current process.
+#[cfg(unix)]
+pub fn apply_memory_limit(memory_mb: u64) -> io::Result<()> {
+    set_soft_limit(libc::RLIMIT_AS, memory_mb.saturating_mul(1024 * 1024))
+}
+
+/// Caps the CPU time of the next job: the soft limit is set to the CPU time used so far plus
+/// `cpu_secs`, so that the limit is per job rather than per subprocess.
+#[cfg(unix)]
+pub fn arm_cpu_limit(cpu_secs: u64) -> io::Result<()> {
+    // SAFETY: getrusage only writes to the struct it is given
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    if unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let used = (usage.ru_utime.tv_sec + usage.ru_stime.tv_sec).max(0) as u64;
+    // Round up so a job never starts with less than `cpu_secs`
+    set_soft_limit(libc::RLIMIT_CPU, used + 1 + cpu_secs)
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type Resource = libc::__rlimit_resource_t;
+#[cfg(all(unix, not(all(target_os = "linux", target_env = "gnu"))))]
+type Resource = libc::c_int;
+
+#[cfg(unix)]
+fn set_soft_limit(resource: Resource, value: u64) -> io::Result<()> {
+    // SAFETY: getrlimit and setrlimit only access the struct they are given
+    let mut limit: libc::rlimit = unsafe { std::mem::zeroed() };
+    if unsafe { libc::getrlimit(resource, &mut limit) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // The soft limit cannot exceed the hard limit
+    limit.rlim_cur = (value as libc::rlim_t).min(limit.rlim_max);
+    if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Resource limits are not supported on this platform; only the wall-clock timeout applies.
+#[cfg(not(unix))]
+pub fn apply_memory_limit(_memory_mb: u64) -> io::Result<()> {
+    Ok(())
+}
+
+/// Resource limits are not supported on this platform; only the wall-clock timeout applies.
+#[cfg(not(unix))]
+pub fn arm_cpu_limit(_cpu_secs: u64) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+
+    fn killed_by(signal: i32) -> Option<ExitStatus> {
+        Some(ExitStatus::from_raw(signal))
+    }
+
+    fn exited_with(code: i32) -> Option<ExitStatus> {
+        Some(ExitStatus::from_raw(code << 8))
+    }
+
+    #[test]
+    fn test_each_breach_maps_to_its_own_error() {
+        let limits = ProverLimits {
+            memory_mb: Some(4096),
+            cpu_secs: Some(600),
+            timeout_secs: None,
+        };
+        assert!(matches!(
+            limits.exit_error(killed_by(SIGKILL)),
+            ProverError::OutOfMemory(_)
+        ));
+        assert!(matches!(
+            limits.exit_error(exited_with(SUBPROCESS_SUSPECTED_OOM_CODE)),
+            ProverError::OutOfMemory(_)
+        ));
+        assert!(matches!(
+            limits.exit_error(killed_by(SIGABRT)),
+            ProverError::OutOfMemory(_)
+        ));
+        assert!(matches!(
+            limits.exit_error(killed_by(SIGXCPU)),
+            ProverError::CpuTimeExceeded(limit) if limit == Duration::from_secs(600)
+        ));
+        assert!(matches!(
+            limits.exit_error(exited_with(101)),
+            ProverError::Crashed(_)
+        ));
+    }
+
+    #[test]
+    fn test_abort_without_memory_limit_is_a_crash() {
+        assert!(matches!(
+            ProverLimits::default().exit_error(killed_by(SIGABRT)),
+            ProverError::Crashed(_)
+        ));
+    }
+}
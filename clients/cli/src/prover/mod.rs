@@ -1,9 +1,18 @@
+pub mod checkpoint;
+pub mod context;
 pub mod engine;
 pub mod handlers;
 pub mod input;
+pub mod limits;
 pub mod pipeline;
+pub mod pool;
+pub mod program;
+pub mod program_cache;
+pub mod proof_cache;
+pub mod protocol;
 pub mod types;
 pub mod verifier;
 
+pub use context::ProvingContext;
 pub use handlers::authenticated_proving;
 pub use types::{ProverError, ProverResult};
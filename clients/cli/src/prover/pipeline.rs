@@ -2,46 +2,69 @@
 
 use std::sync::Arc;
 
+use super::checkpoint::TaskCheckpoints;
+use super::context::ProvingContext;
 use super::engine::ProvingEngine;
-use super::input::InputParser;
-use super::types::ProverError;
+use super::program::{GuestProgram, ProgramRegistry};
+use super::program_cache::ProgramElf;
+use super::proof_cache::ProofCache;
+use super::types::{ProverError, ProverResult};
+use super::verifier::VerificationPool;
 use crate::analytics::track_verification_failed;
 use crate::environment::Environment;
 use crate::task::Task;
 use futures::future::join_all;
 use nexus_sdk::stwo::seq::Proof;
 use sha3::{Digest, Keccak256};
+use std::time::Duration;
+use tokio::sync::Semaphore;
 use tokio_util::sync::CancellationToken;
 
 /// Orchestrates the complete proving pipeline
 pub struct ProvingPipeline;
 
 impl ProvingPipeline {
-    /// Execute authenticated proving for a task
+    /// Execute authenticated proving for a task, with the guest program registered for its
+    /// program ID
+    ///
+    /// Each input holds one of `proving_permits` while it proves, so the permits bound the
+    /// number of concurrent provers across every task sharing the semaphore. Cancelling
+    /// `cancellation`, or dropping the returned future, stops every input still proving and
+    /// kills its prover subprocess.
     pub async fn prove_authenticated(
+        proving: &ProvingContext,
         task: &Task,
         environment: &Environment,
         client_id: &str,
-        num_workers: usize,
-    ) -> Result<(Vec<Proof>, String, Vec<String>), ProverError> {
-        match task.program_id.as_str() {
-            "fib_input_initial" => {
-                Self::prove_fib_task(task, environment, client_id, num_workers).await
-            }
-            _ => Err(ProverError::MalformedTask(format!(
-                "Unsupported program ID: {}",
-                task.program_id
-            ))),
-        }
+        proving_permits: &Arc<Semaphore>,
+        cancellation: &CancellationToken,
+    ) -> Result<ProverResult, ProverError> {
+        let program = ProgramRegistry::builtin().get(&task.program_id)?;
+        let elf = proving.programs.resolve(program.as_ref()).await?;
+        Self::prove_task(
+            proving,
+            program,
+            elf,
+            task,
+            environment,
+            client_id,
+            proving_permits,
+            cancellation,
+        )
+        .await
     }
 
-    /// Process fibonacci proving task with multiple inputs
-    async fn prove_fib_task(
+    /// Process a proving task with multiple inputs
+    async fn prove_task(
+        proving: &ProvingContext,
+        program: Arc<dyn GuestProgram>,
+        elf: ProgramElf,
         task: &Task,
         environment: &Environment,
         client_id: &str,
-        num_workers: usize,
-    ) -> Result<(Vec<Proof>, String, Vec<String>), ProverError> {
+        proving_permits: &Arc<Semaphore>,
+        cancellation: &CancellationToken,
+    ) -> Result<ProverResult, ProverError> {
         let all_inputs = task.all_inputs();
 
         if all_inputs.is_empty() {
@@ -54,55 +77,125 @@ impl ProvingPipeline {
         let task_shared = Arc::new(task.clone());
         let environment_shared = Arc::new(environment.clone());
         let client_id_shared = Arc::new(client_id.to_string());
+        let elf_shared = Arc::new(elf);
 
-        // Create a semaphore with a specific number of permits
-        let semaphore = Arc::new(tokio::sync::Semaphore::new(num_workers));
+        // Inputs already proven, here or in an earlier task, are served from the proof cache
+        // when the task type allows it
+        let reuse_proofs = ProofCache::serves(task.task_type) && proving.proof_cache.is_enabled();
 
-        // Create cancellation token for graceful shutdown
-        let cancellation_token = CancellationToken::new();
+        // Inputs proven before a crash or restart are resumed from their checkpoints, which are
+        // verified like cache hits. Failing to read, write or verify checkpoints only costs
+        // re-proving, so it is not an error.
+        let checkpoint_inputs = TaskCheckpoints::covers(task) && proving.checkpoints.is_enabled();
+        let mut checkpointed = if checkpoint_inputs {
+            proving.checkpoints.resume(task).unwrap_or_default()
+        } else {
+            Default::default()
+        };
+
+        // Cancelled on shutdown, on a critical error, or when this future is dropped, since
+        // the spawned inputs would otherwise outlive it
+        let cancellation_token = cancellation.child_token();
+        let _cancel_on_drop = cancellation_token.clone().drop_guard();
 
         // Spawn all tasks in parallel
         let handles: Vec<_> = all_inputs
             .iter()
             .enumerate()
             .map(|(input_index, input_data)| {
+                let proving_ref = proving.clone();
+                let program_ref = Arc::clone(&program);
+                let elf_ref = Arc::clone(&elf_shared);
                 let task_ref = Arc::clone(&task_shared);
                 let environment_ref = Arc::clone(&environment_shared);
                 let client_id_ref = Arc::clone(&client_id_shared);
                 let input_data = input_data.clone();
-                let semaphore_ref = Arc::clone(&semaphore);
+                let semaphore_ref = Arc::clone(proving_permits);
                 let cancellation_ref = cancellation_token.clone();
+                let checkpointed_proof = checkpointed.remove(&input_index);
 
                 tokio::spawn(async move {
-                    // Check for cancellation before starting
-                    if cancellation_ref.is_cancelled() {
-                        return Err(ProverError::MalformedTask("Task cancelled".to_string()));
-                    }
+                    // On cancellation the proof future is dropped, which kills its subprocess
+                    let proving = async move {
+                        if let Some(proof) = checkpointed_proof {
+                            if let Some((proof, verification_time)) = Self::verify_checkpoint(
+                                &proving_ref.verifier,
+                                &program_ref,
+                                &elf_ref,
+                                proof,
+                                &input_data,
+                            )
+                            .await
+                            {
+                                let proof_hash = Self::generate_proof_hash(&proof);
+                                return Ok((proof, proof_hash, verification_time, input_index));
+                            }
+                        }
 
-                    // Acquire a permit from the semaphore. This waits if the limit is reached.
-                    let _permit = semaphore_ref.acquire_owned().await;
+                        let cache = &proving_ref.proof_cache;
+                        let key = ProofCache::key(&elf_ref, &input_data);
 
-                    // Check for cancellation after acquiring permit
-                    if cancellation_ref.is_cancelled() {
-                        return Err(ProverError::MalformedTask("Task cancelled".to_string()));
-                    }
+                        // A duplicate input waits for the first to be proven, then is served its
+                        // proof from the cache
+                        let _claim = if reuse_proofs {
+                            let claim = cache.claim(&key).await;
+                            if let Some((proof, proof_hash, verification_time)) = cache
+                                .get(
+                                    &proving_ref.verifier,
+                                    &program_ref,
+                                    &elf_ref,
+                                    &key,
+                                    &input_data,
+                                )
+                                .await
+                            {
+                                return Ok((proof, proof_hash, verification_time, input_index));
+                            }
+                            Some(claim)
+                        } else {
+                            None
+                        };
+
+                        // Acquire a permit from the semaphore. This waits if the limit is reached.
+                        let _permit = semaphore_ref.acquire_owned().await;
 
-                    // Step 1: Parse and validate input
-                    let inputs = InputParser::parse_triple_input(&input_data)?;
+                        // Generate and verify proof; the program decodes and validates the input
+                        let (proof, verification_time) = ProvingEngine::prove_and_validate(
+                            &proving_ref,
+                            &program_ref,
+                            &elf_ref,
+                            &input_data,
+                            &task_ref,
+                            &environment_ref,
+                            &client_id_ref,
+                        )
+                        .await?;
 
-                    // Step 2: Generate and verify proof
-                    let proof = ProvingEngine::prove_and_validate(
-                        &inputs,
-                        &task_ref,
-                        &environment_ref,
-                        &client_id_ref,
-                    )
-                    .await?;
+                        // Proofs of every task type are stored for later hash-only tasks. The
+                        // cache only saves work, so failing to store a proof is not an error.
+                        // Proofs the verification policy skipped are not stored, since the cache
+                        // holds verified proofs only.
+                        if verification_time.is_some() {
+                            let _ = cache.insert(&key, &proof);
+                        }
+                        if checkpoint_inputs {
+                            let _ = proving_ref.checkpoints.store(
+                                &task_ref.task_id,
+                                input_index,
+                                &proof,
+                            );
+                        }
 
-                    // Step 3: Generate proof hash
-                    let proof_hash = Self::generate_proof_hash(&proof);
+                        // Generate proof hash
+                        let proof_hash = Self::generate_proof_hash(&proof);
 
-                    Ok((proof, proof_hash, input_index))
+                        let verification_time = verification_time.unwrap_or_default();
+                        Ok::<_, ProverError>((proof, proof_hash, verification_time, input_index))
+                    };
+                    cancellation_ref
+                        .run_until_cancelled(proving)
+                        .await
+                        .unwrap_or(Err(ProverError::Cancelled))
                 })
             })
             .collect();
@@ -113,13 +206,15 @@ impl ProvingPipeline {
         // Process results and collect verification failures for batch handling
         let mut all_proofs = Vec::new();
         let mut proof_hashes = Vec::new();
+        let mut verification_time = Duration::ZERO;
         let mut verification_failures = Vec::new();
 
         for (result_index, result) in results.into_iter().enumerate() {
             match result {
-                Ok(Ok((proof, proof_hash, _input_index))) => {
+                Ok(Ok((proof, proof_hash, input_verification_time, _input_index))) => {
                     all_proofs.push(proof);
                     proof_hashes.push(proof_hash);
+                    verification_time += input_verification_time;
                 }
                 Ok(Err(e)) => {
                     // Collect verification failures for batch processing
@@ -166,11 +261,31 @@ impl ProvingPipeline {
 
         let final_proof_hash = Self::combine_proof_hashes(&task_shared, &proof_hashes);
 
-        Ok((all_proofs, final_proof_hash, proof_hashes))
+        Ok(ProverResult {
+            proofs: all_proofs,
+            combined_hash: final_proof_hash,
+            individual_proof_hashes: proof_hashes,
+            verification_time,
+        })
+    }
+
+    /// Verifies a checkpointed proof against its input, whatever the verification policy.
+    /// Returns `None` if it does not verify, so that the input is proven again.
+    async fn verify_checkpoint(
+        verifier: &VerificationPool,
+        program: &Arc<dyn GuestProgram>,
+        elf: &Arc<ProgramElf>,
+        proof: Proof,
+        input: &[u8],
+    ) -> Option<(Proof, Duration)> {
+        verifier
+            .verify(Arc::clone(program), Arc::clone(elf), proof, input.to_vec())
+            .await
+            .ok()
     }
 
     /// Generate hash for a proof
-    fn generate_proof_hash(proof: &Proof) -> String {
+    pub fn generate_proof_hash(proof: &Proof) -> String {
         let proof_bytes = postcard::to_allocvec(proof).expect("Failed to serialize proof");
         format!("{:x}", Keccak256::digest(&proof_bytes))
     }
@@ -186,3 +301,117 @@ impl ProvingPipeline {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nexus_orchestrator::{TaskDifficulty, TaskType};
+    use crate::prover::pool::{PoolConfig, ProverPool};
+    use tempfile::tempdir;
+
+    fn fib_input(n: u32) -> Vec<u8> {
+        [n, 1, 2].iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    fn task(task_id: &str, inputs: &[&[u8]]) -> Task {
+        let mut task = Task::new(
+            task_id.to_string(),
+            "fib_input_initial".to_string(),
+            inputs[0].to_vec(),
+            TaskType::ProofRequired,
+            TaskDifficulty::Small,
+        );
+        task.public_inputs_list = inputs.iter().map(|input| input.to_vec()).collect();
+        task
+    }
+
+    async fn prove(proving: &ProvingContext, task: &Task) -> Result<ProverResult, ProverError> {
+        ProvingPipeline::prove_authenticated(
+            proving,
+            task,
+            &Environment::default(),
+            "client",
+            &Arc::new(Semaphore::new(1)),
+            &CancellationToken::new(),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_task_stops_before_proving() {
+        let input: Vec<u8> = [10u32, 1, 2].iter().flat_map(|v| v.to_le_bytes()).collect();
+        let task = Task::new(
+            "task-1".to_string(),
+            "fib_input_initial".to_string(),
+            input,
+            TaskType::ProofHash,
+            TaskDifficulty::Small,
+        );
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+
+        // Every input stops at its first await, so no prover subprocess is started
+        let result = ProvingPipeline::prove_authenticated(
+            &ProvingContext::default(),
+            &task,
+            &Environment::default(),
+            "client",
+            &Arc::new(Semaphore::new(1)),
+            &cancellation,
+        )
+        .await;
+        assert!(matches!(result, Err(ProverError::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn test_resumed_task_proves_only_missing_inputs() {
+        let dir = tempdir().unwrap();
+        // Missing inputs are sent to a subprocess that cannot start, so proving them fails
+        let pool = ProverPool::new(PoolConfig {
+            worker_exe: Some(dir.path().join("no-such-prover")),
+            ..PoolConfig::default()
+        });
+        let proving = ProvingContext {
+            pool: Arc::new(pool),
+            checkpoints: Arc::new(TaskCheckpoints::new(Some(dir.path().to_path_buf()))),
+            ..ProvingContext::default()
+        };
+        let (store, pool) = (&proving.checkpoints, &proving.pool);
+
+        let program = ProgramRegistry::builtin().get("fib_input_initial").unwrap();
+        let elf = proving.programs.resolve(program.as_ref()).await.unwrap();
+        let (first, second) = (fib_input(10), fib_input(12));
+        let proof = program.prove(&elf.bytes, &first).unwrap();
+        let proof_hash = ProvingPipeline::generate_proof_hash(&proof);
+
+        // Every input checkpointed: the proofs are verified and nothing is proven
+        let resumed = task("task-1", &[&first, &first]);
+        store.resume(&resumed).unwrap();
+        store.store("task-1", 0, &proof).unwrap();
+        store.store("task-1", 1, &proof).unwrap();
+        let jobs = pool.jobs_started();
+        let result = prove(&proving, &resumed).await.unwrap();
+        assert_eq!(pool.jobs_started(), jobs);
+        assert_eq!(result.individual_proof_hashes, vec![proof_hash.clone(); 2]);
+        assert!(result.verification_time > Duration::ZERO);
+
+        // One input missing: only that input is proven
+        let partial = task("task-2", &[&first, &second]);
+        store.resume(&partial).unwrap();
+        store.store("task-2", 0, &proof).unwrap();
+        let jobs = pool.jobs_started();
+        let result = prove(&proving, &partial).await;
+        assert!(matches!(result, Err(ProverError::Subprocess(_))));
+        assert_eq!(pool.jobs_started(), jobs + 1);
+
+        // A checkpoint that does not verify against its input is proven again
+        let tampered = task("task-3", &[&first, &second]);
+        store.resume(&tampered).unwrap();
+        store.store("task-3", 0, &proof).unwrap();
+        store.store("task-3", 1, &proof).unwrap();
+        let jobs = pool.jobs_started();
+        let result = prove(&proving, &tampered).await;
+        assert!(matches!(result, Err(ProverError::Subprocess(_))));
+        assert_eq!(pool.jobs_started(), jobs + 1);
+    }
+}
@@ -0,0 +1,426 @@
+//! Pool of persistent prover subprocesses
+//!
+//! Proving runs in subprocesses so that a guest program exhausting memory cannot take the CLI
+//! down with it. Each subprocess serves jobs over the framed [`protocol`](super::protocol) and
+//! keeps its loaded ELFs between them, so only the first job pays the startup cost. A
+//! subprocess is replaced after a fixed number of jobs, or once its resident memory has grown
+//! too far since its first job. Subprocesses run under the configured
+//! [`ProverLimits`], and a job that outlives its wall-clock timeout is killed.
+
+use super::limits::ProverLimits;
+use super::protocol::{Request, Response, read_frame_async, write_frame_async};
+use super::types::ProverError;
+use crate::consts::cli_consts::prover_pool;
+use futures::future::join_all;
+use nexus_sdk::stwo::seq::Proof;
+use std::env;
+use std::io;
+use std::path::PathBuf;
+use std::process::{ExitStatus, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::time;
+
+/// Hidden CLI command a prover subprocess is started with
+pub const WORKER_COMMAND: &str = "prover-worker";
+
+/// Why a job produced no proof
+#[derive(Debug, Error)]
+enum JobFailure {
+    /// The prover reported an error; its subprocess stays in the pool
+    #[error(transparent)]
+    Prover(ProverError),
+
+    #[error("Prover subprocess exited with status: {}", .0.map_or("unknown".to_string(), |s| s.to_string()))]
+    Exited(Option<ExitStatus>),
+
+    #[error("Prover subprocess sent nothing for {0:?} and was killed")]
+    Unresponsive(Duration),
+
+    #[error("Job exceeded its timeout of {0:?} and was killed")]
+    TimedOut(Duration),
+
+    #[error("Prover subprocess I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Prover subprocess protocol error: {0}")]
+    Protocol(String),
+}
+
+impl JobFailure {
+    fn into_prover_error(self, limits: &ProverLimits) -> ProverError {
+        match self {
+            JobFailure::Prover(e) => e,
+            JobFailure::Exited(status) => limits.exit_error(status),
+            JobFailure::TimedOut(timeout) => ProverError::TimedOut(timeout),
+            other => ProverError::Subprocess(other.to_string()),
+        }
+    }
+}
+
+/// Limits on the lifetime of a prover subprocess
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolConfig {
+    pub max_jobs_per_process: u32,
+    pub max_rss_growth_bytes: u64,
+    pub heartbeat_timeout: Duration,
+    pub startup_timeout: Duration,
+    pub limits: ProverLimits,
+    /// Executable started as the subprocess; the running CLI when unset
+    pub worker_exe: Option<PathBuf>,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_jobs_per_process: prover_pool::MAX_JOBS_PER_PROCESS,
+            max_rss_growth_bytes: prover_pool::MAX_RSS_GROWTH_BYTES,
+            heartbeat_timeout: prover_pool::heartbeat_timeout(),
+            startup_timeout: prover_pool::startup_timeout(),
+            limits: ProverLimits::default(),
+            worker_exe: None,
+        }
+    }
+}
+
+impl PoolConfig {
+    pub fn with_limits(mut self, limits: ProverLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+/// Job count and memory of a prover subprocess, deciding when it is replaced
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct ProcessStats {
+    jobs: u32,
+    /// Resident memory at the end of the first job
+    baseline_rss: Option<u64>,
+    last_rss: u64,
+    /// Highest resident memory reported during the current job
+    job_peak_rss: u64,
+}
+
+impl ProcessStats {
+    fn record_rss(&mut self, rss_bytes: u64) {
+        self.last_rss = rss_bytes;
+        self.job_peak_rss = self.job_peak_rss.max(rss_bytes);
+    }
+
+    fn finish_job(&mut self, rss_bytes: u64) {
+        self.jobs += 1;
+        self.record_rss(rss_bytes);
+        self.baseline_rss.get_or_insert(rss_bytes);
+    }
+
+    fn due_for_recycling(&self, config: &PoolConfig) -> bool {
+        let grown = self.baseline_rss.is_some_and(|baseline| {
+            self.last_rss.saturating_sub(baseline) > config.max_rss_growth_bytes
+        });
+        self.jobs >= config.max_jobs_per_process || grown
+    }
+}
+
+/// One prover subprocess, killed when dropped
+struct ProverProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: ChildStdout,
+    stats: ProcessStats,
+}
+
+impl ProverProcess {
+    /// Starts a subprocess and waits until it is ready for jobs.
+    async fn spawn(config: &PoolConfig) -> Result<Self, JobFailure> {
+        let exe = match &config.worker_exe {
+            Some(exe) => exe.clone(),
+            None => env::current_exe()?,
+        };
+        let mut command = Command::new(exe);
+        command.arg(WORKER_COMMAND);
+        // The subprocess applies its rlimits itself, before it accepts a job
+        if let Some(memory_mb) = config.limits.memory_mb {
+            command.arg("--memory-limit-mb").arg(memory_mb.to_string());
+        }
+        if let Some(cpu_secs) = config.limits.cpu_secs {
+            command.arg("--cpu-limit-secs").arg(cpu_secs.to_string());
+        }
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(JobFailure::Protocol(
+                "subprocess pipes unavailable".to_string(),
+            ));
+        };
+        let mut process = Self {
+            child,
+            stdin,
+            stdout,
+            stats: ProcessStats::default(),
+        };
+
+        match process.next_frame(config.startup_timeout).await? {
+            Response::Ready { .. } => Ok(process),
+            _ => Err(JobFailure::Protocol(
+                "expected a ready frame at startup".to_string(),
+            )),
+        }
+    }
+
+    /// Whether the subprocess is still running, e.g. after sitting idle.
+    fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// Runs one job, killing the subprocess if it outlives the wall-clock timeout.
+    async fn run(
+        &mut self,
+        job_id: u64,
+        request: &Request,
+        config: &PoolConfig,
+    ) -> Result<Proof, JobFailure> {
+        self.stats.job_peak_rss = 0;
+        if let Err(e) = write_frame_async(&mut self.stdin, request).await {
+            return Err(match self.child.try_wait() {
+                Ok(Some(status)) => JobFailure::Exited(Some(status)),
+                _ => JobFailure::Io(e),
+            });
+        }
+
+        let Some(timeout) = config.limits.timeout() else {
+            return self.collect_proof(job_id, config).await;
+        };
+        let result = time::timeout(timeout, self.collect_proof(job_id, config)).await;
+        result.unwrap_or_else(|_| {
+            let _ = self.child.start_kill();
+            Err(JobFailure::TimedOut(timeout))
+        })
+    }
+
+    /// Collects the streamed proof of a job.
+    async fn collect_proof(
+        &mut self,
+        job_id: u64,
+        config: &PoolConfig,
+    ) -> Result<Proof, JobFailure> {
+        let mut proof_bytes = Vec::new();
+        loop {
+            match self.next_frame(config.heartbeat_timeout).await? {
+                Response::Heartbeat { rss_bytes } => self.stats.record_rss(rss_bytes),
+                Response::ProofChunk { job_id: id, bytes } if id == job_id => {
+                    proof_bytes.extend_from_slice(&bytes);
+                }
+                Response::ProofDone {
+                    job_id: id,
+                    proof_len,
+                    rss_bytes,
+                } if id == job_id => {
+                    self.stats.finish_job(rss_bytes);
+                    if proof_bytes.len() as u64 != proof_len {
+                        return Err(JobFailure::Protocol(format!(
+                            "received {} proof bytes, expected {}",
+                            proof_bytes.len(),
+                            proof_len
+                        )));
+                    }
+                    return postcard::from_bytes(&proof_bytes)
+                        .map_err(|e| JobFailure::Prover(ProverError::Serialization(e)));
+                }
+                Response::Failed { job_id: id, error } if id == job_id => {
+                    let rss_bytes = self.stats.last_rss;
+                    self.stats.finish_job(rss_bytes);
+                    return Err(JobFailure::Prover(error.into()));
+                }
+                _ => {
+                    return Err(JobFailure::Protocol(format!(
+                        "unexpected frame during job {}",
+                        job_id
+                    )));
+                }
+            }
+        }
+    }
+
+    /// Next frame from the subprocess. Silence for longer than `timeout` means it has hung.
+    async fn next_frame(&mut self, timeout: Duration) -> Result<Response, JobFailure> {
+        match time::timeout(timeout, read_frame_async(&mut self.stdout)).await {
+            Ok(Ok(Some(frame))) => Ok(frame),
+            Ok(Ok(None)) => Err(self.exit_status().await),
+            Ok(Err(e)) => Err(JobFailure::Io(e)),
+            Err(_) => {
+                let _ = self.child.start_kill();
+                Err(JobFailure::Unresponsive(timeout))
+            }
+        }
+    }
+
+    /// Asks the subprocess to exit, killing it if it has not within `grace`.
+    async fn stop(mut self, grace: Duration) {
+        let _ = write_frame_async(&mut self.stdin, &Request::Shutdown).await;
+        if time::timeout(grace, self.child.wait()).await.is_err() {
+            let _ = self.child.kill().await;
+        }
+    }
+
+    /// Exit status once the subprocess has closed its stdout.
+    async fn exit_status(&mut self) -> JobFailure {
+        match time::timeout(Duration::from_secs(5), self.child.wait()).await {
+            Ok(Ok(status)) => JobFailure::Exited(Some(status)),
+            _ => {
+                let _ = self.child.start_kill();
+                JobFailure::Exited(None)
+            }
+        }
+    }
+}
+
+/// Persistent prover subprocesses shared by every proving task in the process
+///
+/// The pool does not bound concurrency itself: callers hold a proving permit while a job
+/// runs, so it grows to as many subprocesses as there are permits.
+pub struct ProverPool {
+    config: PoolConfig,
+    idle: Mutex<Vec<ProverProcess>>,
+    next_job_id: AtomicU64,
+    peak_rss: AtomicU64,
+}
+
+impl ProverPool {
+    pub fn new(config: PoolConfig) -> Self {
+        Self {
+            config,
+            idle: Mutex::new(Vec::new()),
+            next_job_id: AtomicU64::new(0),
+            peak_rss: AtomicU64::new(0),
+        }
+    }
+
+    /// Proves one public input in an idle subprocess, starting one if none is idle.
+    pub async fn prove(
+        &self,
+        program_id: &str,
+        elf_sha256: &str,
+        input: &[u8],
+    ) -> Result<Proof, ProverError> {
+        self.run_job(program_id, elf_sha256, input)
+            .await
+            .map_err(|failure| failure.into_prover_error(&self.config.limits))
+    }
+
+    async fn run_job(
+        &self,
+        program_id: &str,
+        elf_sha256: &str,
+        input: &[u8],
+    ) -> Result<Proof, JobFailure> {
+        let job_id = self.next_job_id.fetch_add(1, Ordering::Relaxed);
+        let mut process = match self.checkout() {
+            Some(process) => process,
+            None => ProverProcess::spawn(&self.config).await?,
+        };
+        let request = Request::Prove {
+            job_id,
+            program_id: program_id.to_string(),
+            elf_sha256: elf_sha256.to_string(),
+            input: input.to_vec(),
+        };
+
+        let result = process.run(job_id, &request, &self.config).await;
+        self.peak_rss
+            .fetch_max(process.stats.job_peak_rss, Ordering::Relaxed);
+        // A subprocess that only reported an error is still healthy; any other failure leaves
+        // it in an unknown state, so it is dropped and killed
+        if matches!(result, Ok(_) | Err(JobFailure::Prover(_))) {
+            self.checkin(process);
+        }
+        result
+    }
+
+    /// Number of jobs started, whether or not they succeeded.
+    #[cfg(test)]
+    pub fn jobs_started(&self) -> u64 {
+        self.next_job_id.load(Ordering::Relaxed)
+    }
+
+    /// Highest resident memory of any subprocess during a job since the last call (bytes).
+    pub fn take_peak_rss(&self) -> u64 {
+        self.peak_rss.swap(0, Ordering::Relaxed)
+    }
+
+    fn idle(&self) -> std::sync::MutexGuard<'_, Vec<ProverProcess>> {
+        self.idle
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Stops every idle subprocess. Subprocesses busy with a job are killed when the job is
+    /// cancelled.
+    pub async fn shutdown(&self, grace: Duration) {
+        let idle = std::mem::take(&mut *self.idle());
+        join_all(idle.into_iter().map(|process| process.stop(grace))).await;
+    }
+
+    /// Takes an idle subprocess that is still running.
+    fn checkout(&self) -> Option<ProverProcess> {
+        let mut idle = self.idle();
+        while let Some(mut process) = idle.pop() {
+            if process.is_alive() {
+                return Some(process);
+            }
+        }
+        None
+    }
+
+    /// Returns a subprocess after a job, unless it is due to be replaced. Dropping it kills it;
+    /// between jobs it holds nothing worth a graceful shutdown.
+    fn checkin(&self, process: ProverProcess) {
+        if !process.stats.due_for_recycling(&self.config) {
+            self.idle().push(process);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> PoolConfig {
+        PoolConfig {
+            max_jobs_per_process: 3,
+            max_rss_growth_bytes: 100,
+            ..PoolConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_recycled_after_max_jobs() {
+        let mut stats = ProcessStats::default();
+        for _ in 0..2 {
+            stats.finish_job(1_000);
+            assert!(!stats.due_for_recycling(&config()));
+        }
+        stats.finish_job(1_000);
+        assert!(stats.due_for_recycling(&config()));
+    }
+
+    #[test]
+    fn test_recycled_on_memory_growth_since_first_job() {
+        let mut stats = ProcessStats::default();
+        // Memory in use at the end of the first job is the baseline, however large
+        stats.finish_job(5_000);
+        assert!(!stats.due_for_recycling(&config()));
+
+        // Heartbeats mid-job update the last reading too
+        stats.last_rss = 5_100;
+        assert!(!stats.due_for_recycling(&config()));
+        stats.last_rss = 5_101;
+        assert!(stats.due_for_recycling(&config()));
+    }
+}
@@ -0,0 +1,203 @@
+//! Guest programs the CLI can prove
+//!
+//! Each [`GuestProgram`] decodes the public inputs of its tasks itself, and proves and verifies
+//! runs on them with [`prove_run`] and [`ProofVerifier::verify_proof`]. The
+//! [`ProgramRegistry`] dispatches on the task's `program_id`, so a new program only needs an
+//! implementation and a registration. ELFs come from the verified
+//! [`ProgramCache`](super::program_cache::ProgramCache).
+
+use super::input::InputParser;
+use super::types::ProverError;
+use super::verifier::ProofVerifier;
+use nexus_sdk::{
+    KnownExitCodes, Local, Prover,
+    stwo::seq::{Proof, Stwo},
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::{Arc, OnceLock};
+
+/// Proves a run of the guest program loaded in `prover` on a decoded public input, checking
+/// its exit code.
+pub fn prove_run<T: Serialize + Debug>(
+    prover: Stwo<Local>,
+    input: &T,
+    expected_exit_code: u32,
+) -> Result<Proof, ProverError> {
+    let (view, proof) = prover.prove_with_input::<(), T>(&(), input).map_err(|e| {
+        ProverError::Stwo(format!(
+            "Failed to generate proof for inputs {:?}: {}",
+            input, e
+        ))
+    })?;
+    ProofVerifier::check_exit_code(&view, expected_exit_code)?;
+    Ok(proof)
+}
+
+/// A guest program the CLI can prove tasks for
+pub trait GuestProgram: Send + Sync {
+    /// Program ID the orchestrator uses in tasks
+    fn id(&self) -> &'static str;
+
+    /// Copy of the compiled guest program built into the CLI, if any
+    fn bundled_elf(&self) -> Option<&'static [u8]> {
+        None
+    }
+
+    /// Decodes one of a task's public inputs for display, failing if it is malformed.
+    fn describe_input(&self, bytes: &[u8]) -> Result<String, ProverError>;
+
+    /// Exit code of a successful run
+    fn expected_exit_code(&self) -> u32 {
+        KnownExitCodes::ExitSuccess as u32
+    }
+
+    /// Loads a verified ELF of this program into a prover.
+    fn load(&self, elf: &[u8]) -> Result<Stwo<Local>, ProverError> {
+        Stwo::<Local>::new_from_bytes(elf).map_err(|e| {
+            ProverError::Stwo(format!("Failed to load {} guest program: {}", self.id(), e))
+        })
+    }
+
+    /// Proves a run on the public input encoded in `input`, checking the exit code.
+    fn prove(&self, elf: &[u8], input: &[u8]) -> Result<Proof, ProverError>;
+
+    /// Verifies a proof of a run on the public input encoded in `input` against the ELF
+    /// loaded in `prover`.
+    fn verify(&self, prover: &Stwo<Local>, proof: &Proof, input: &[u8]) -> Result<(), ProverError>;
+}
+
+/// Fibonacci with a chosen starting pair; inputs are `(n, init_a, init_b)`
+pub struct FibInputInitial;
+
+impl GuestProgram for FibInputInitial {
+    fn id(&self) -> &'static str {
+        "fib_input_initial"
+    }
+
+    fn bundled_elf(&self) -> Option<&'static [u8]> {
+        Some(include_bytes!("../../assets/fib_input_initial"))
+    }
+
+    fn describe_input(&self, bytes: &[u8]) -> Result<String, ProverError> {
+        Ok(format!("{:?}", InputParser::parse_triple_input(bytes)?))
+    }
+
+    fn prove(&self, elf: &[u8], input: &[u8]) -> Result<Proof, ProverError> {
+        let input = InputParser::parse_triple_input(input)?;
+        prove_run(self.load(elf)?, &input, self.expected_exit_code())
+    }
+
+    fn verify(&self, prover: &Stwo<Local>, proof: &Proof, input: &[u8]) -> Result<(), ProverError> {
+        let input = InputParser::parse_triple_input(input)?;
+        ProofVerifier::verify_proof(proof, &input, self.expected_exit_code(), prover)
+    }
+}
+
+/// Guest programs by program ID
+#[derive(Default)]
+pub struct ProgramRegistry {
+    programs: BTreeMap<&'static str, Arc<dyn GuestProgram>>,
+}
+
+impl ProgramRegistry {
+    /// Registry of the programs built into the CLI, shared by the whole process
+    pub fn builtin() -> &'static ProgramRegistry {
+        static BUILTIN: OnceLock<ProgramRegistry> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            let mut registry = ProgramRegistry::default();
+            registry.register(Arc::new(FibInputInitial));
+            registry
+        })
+    }
+
+    /// Adds a program, replacing any registered under the same ID.
+    pub fn register(&mut self, program: Arc<dyn GuestProgram>) {
+        self.programs.insert(program.id(), program);
+    }
+
+    /// The program tasks with `program_id` are proven with.
+    pub fn get(&self, program_id: &str) -> Result<Arc<dyn GuestProgram>, ProverError> {
+        self.programs.get(program_id).cloned().ok_or_else(|| {
+            ProverError::MalformedTask(format!("Unsupported program ID: {}", program_id))
+        })
+    }
+
+    /// IDs of every registered program, in order.
+    pub fn ids(&self) -> Vec<&'static str> {
+        self.programs.keys().copied().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_dispatches_on_program_id() {
+        let registry = ProgramRegistry::builtin();
+        assert_eq!(registry.ids(), vec!["fib_input_initial"]);
+
+        let program = registry.get("fib_input_initial").unwrap();
+        let bytes: Vec<u8> = [10u32, 1, 2].iter().flat_map(|v| v.to_le_bytes()).collect();
+        assert_eq!(program.describe_input(&bytes).unwrap(), "(10, 1, 2)");
+        assert!(program.describe_input(&bytes[..8]).is_err());
+
+        assert!(matches!(
+            registry.get("keccak"),
+            Err(ProverError::MalformedTask(_))
+        ));
+    }
+
+    /// A program with its own input format, which nothing outside it needs to know about
+    struct Keccak;
+
+    impl GuestProgram for Keccak {
+        fn id(&self) -> &'static str {
+            "keccak"
+        }
+
+        fn describe_input(&self, bytes: &[u8]) -> Result<String, ProverError> {
+            Ok(hex::encode(bytes))
+        }
+
+        fn prove(&self, _elf: &[u8], _input: &[u8]) -> Result<Proof, ProverError> {
+            Err(ProverError::Stwo("not built".to_string()))
+        }
+
+        fn verify(
+            &self,
+            _prover: &Stwo<Local>,
+            _proof: &Proof,
+            _input: &[u8],
+        ) -> Result<(), ProverError> {
+            Err(ProverError::Stwo("not built".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_registered_programs_decode_their_own_inputs() {
+        let mut registry = ProgramRegistry::default();
+        registry.register(Arc::new(FibInputInitial));
+        registry.register(Arc::new(Keccak));
+
+        let bytes = [0xab; 12];
+        assert_eq!(
+            registry
+                .get("keccak")
+                .unwrap()
+                .describe_input(&bytes)
+                .unwrap(),
+            "abababababababababababab"
+        );
+        assert_eq!(
+            registry
+                .get("fib_input_initial")
+                .unwrap()
+                .describe_input(&bytes)
+                .unwrap(),
+            format!("{:?}", (0xababababu32, 0xababababu32, 0xababababu32))
+        );
+    }
+}
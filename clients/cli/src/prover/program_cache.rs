@@ -0,0 +1,500 @@
+//! Verified on-disk cache of guest program ELFs
+//!
+//! ELFs live under `<programs dir>/<program_id>/<sha256>.elf`. Before an ELF is loaded it is
+//! checked against a pinned manifest: the hashes built into the CLI, plus any pinned in the
+//! config file. An ELF missing from the cache is taken from the CLI's bundled copy when that
+//! matches a pin, or fetched from the configured program URL.
+
+use super::program::GuestProgram;
+use crate::consts::cli_consts::programs;
+use crate::network::http;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// ELF hashes built into this release
+const BUILTIN_PINS: &[(&str, &str, &str)] = &[(
+    "fib_input_initial",
+    "69f1c40224ec9627ca950b4208f46da44d169900a2cb9916eddd0c1de9a4a423",
+    "7861fb7d0fba469022aa928bea0ec47f7e3e93232f38bbfea31c63ac564d1b52",
+)];
+
+/// Get the directory guest program ELFs are cached in, next to the config file.
+pub fn get_programs_dir(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .map(|parent| parent.join("programs"))
+        .unwrap_or_else(|| PathBuf::from("programs"))
+}
+
+#[derive(Debug, Error)]
+pub enum ProgramCacheError {
+    #[error(
+        "No verified ELF for program {0}. Pin one in the config file and install it in the program cache, or set a program URL"
+    )]
+    NotInstalled(String),
+
+    #[error("{algorithm} of {what} is {actual}, expected {expected}")]
+    HashMismatch {
+        what: String,
+        algorithm: &'static str,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("I/O error on {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to fetch {url}: {message}")]
+    Fetch { url: String, message: String },
+}
+
+/// Hashes an ELF must match before it is loaded
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProgramPin {
+    pub program_id: String,
+    /// Hex SHA-256 of the ELF, also its key in the cache
+    pub sha256: String,
+    /// Hex Keccak-256 of the ELF, checked as well when present
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keccak256: Option<String>,
+}
+
+impl ProgramPin {
+    /// Checks `bytes` against the pinned hashes.
+    pub fn verify(&self, bytes: &[u8], what: &str) -> Result<(), ProgramCacheError> {
+        check_hash("SHA-256", &self.sha256, &sha256_hex(bytes), what)?;
+        if let Some(keccak256) = &self.keccak256 {
+            let actual = format!("{:x}", Keccak256::digest(bytes));
+            check_hash("Keccak-256", keccak256, &actual, what)?;
+        }
+        Ok(())
+    }
+}
+
+fn check_hash(
+    algorithm: &'static str,
+    expected: &str,
+    actual: &str,
+    what: &str,
+) -> Result<(), ProgramCacheError> {
+    if expected.eq_ignore_ascii_case(actual) {
+        return Ok(());
+    }
+    Err(ProgramCacheError::HashMismatch {
+        what: what.to_string(),
+        algorithm,
+        expected: expected.to_lowercase(),
+        actual: actual.to_string(),
+    })
+}
+
+/// Hex SHA-256 of `bytes`
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Program settings from the config file
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProgramsConfig {
+    /// Base URL to fetch missing ELFs from, as `<url>/<program_id>/<sha256>.elf`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Pins in addition to those built into the CLI, preferred over them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pins: Vec<ProgramPin>,
+}
+
+impl ProgramsConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Where a resolved ELF came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElfSource {
+    Cache(PathBuf),
+    Bundled,
+    Downloaded(PathBuf),
+}
+
+/// A verified guest program ELF
+#[derive(Debug, Clone)]
+pub struct ProgramElf {
+    pub sha256: String,
+    pub bytes: Arc<[u8]>,
+    pub source: ElfSource,
+}
+
+/// An ELF found in the cache directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedElf {
+    pub program_id: String,
+    pub sha256: String,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Result of checking a cached ELF against the manifest
+#[derive(Debug)]
+pub enum ElfStatus {
+    Verified,
+    /// No pin for this program and hash
+    Unpinned,
+    Invalid(ProgramCacheError),
+}
+
+/// Guest program ELFs keyed by program ID and content hash
+#[derive(Debug, Clone)]
+pub struct ProgramCache {
+    /// Cache directory; without one only bundled ELFs are available
+    dir: Option<PathBuf>,
+    pins: Vec<ProgramPin>,
+    url: Option<String>,
+}
+
+impl ProgramCache {
+    /// Creates a cache over `dir` with the pins built into the CLI.
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        let pins = BUILTIN_PINS
+            .iter()
+            .map(|(program_id, sha256, keccak256)| ProgramPin {
+                program_id: program_id.to_string(),
+                sha256: sha256.to_string(),
+                keccak256: Some(keccak256.to_string()),
+            })
+            .collect();
+        Self {
+            dir,
+            pins,
+            url: None,
+        }
+    }
+
+    /// Adds pins from the config file, preferred over the built-in ones.
+    pub fn with_pins(mut self, pins: Vec<ProgramPin>) -> Self {
+        self.pins.splice(0..0, pins);
+        self
+    }
+
+    /// Fetch missing ELFs from `url`.
+    pub fn with_url(mut self, url: Option<String>) -> Self {
+        self.url = url.map(|url| url.trim_end_matches('/').to_string());
+        self
+    }
+
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    /// Pins for a program, preferred first.
+    pub fn pins_for<'a>(&'a self, program_id: &'a str) -> impl Iterator<Item = &'a ProgramPin> {
+        self.pins
+            .iter()
+            .filter(move |pin| pin.program_id == program_id)
+    }
+
+    fn path_for(&self, program_id: &str, sha256: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(program_id).join(format!("{}.elf", sha256)))
+    }
+
+    /// Finds a verified ELF for the program. Pins are tried in order of preference, each from
+    /// the cache, the bundled copy and finally the program URL.
+    pub async fn resolve(
+        &self,
+        program: &dyn GuestProgram,
+    ) -> Result<ProgramElf, ProgramCacheError> {
+        let mut last_error = None;
+        for pin in self.pins_for(program.id()) {
+            if let Some(elf) = self.local(program, pin) {
+                return Ok(elf);
+            }
+            if self.url.is_some() {
+                match self.fetch(pin).await {
+                    Ok(elf) => return Ok(elf),
+                    Err(e) => last_error = Some(e),
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| ProgramCacheError::NotInstalled(program.id().to_string())))
+    }
+
+    /// The pinned ELF from the cache or the bundled copy, if either verifies.
+    fn local(&self, program: &dyn GuestProgram, pin: &ProgramPin) -> Option<ProgramElf> {
+        if let Some(path) = self.path_for(&pin.program_id, &pin.sha256) {
+            // A corrupt file is skipped here and replaced if the ELF can be fetched again
+            if let Ok(bytes) = fs::read(&path) {
+                if pin.verify(&bytes, &path.display().to_string()).is_ok() {
+                    return Some(ProgramElf {
+                        sha256: pin.sha256.to_lowercase(),
+                        bytes: bytes.into(),
+                        source: ElfSource::Cache(path),
+                    });
+                }
+            }
+        }
+        let bundled = program.bundled_elf()?;
+        pin.verify(bundled, "bundled ELF").ok()?;
+        Some(ProgramElf {
+            sha256: pin.sha256.to_lowercase(),
+            bytes: bundled.into(),
+            source: ElfSource::Bundled,
+        })
+    }
+
+    /// Downloads the pinned ELF, verifies it and stores it in the cache.
+    async fn fetch(&self, pin: &ProgramPin) -> Result<ProgramElf, ProgramCacheError> {
+        let Some(base_url) = &self.url else {
+            return Err(ProgramCacheError::NotInstalled(pin.program_id.clone()));
+        };
+        let url = format!("{}/{}/{}.elf", base_url, pin.program_id, pin.sha256);
+        let fetch_error = |message: String| ProgramCacheError::Fetch {
+            url: url.clone(),
+            message,
+        };
+
+        let client = http::client_builder()
+            .timeout(programs::fetch_timeout())
+            .build()
+            .map_err(|e| fetch_error(e.to_string()))?;
+        let response = client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| fetch_error(e.to_string()))?;
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| fetch_error(e.to_string()))?;
+        pin.verify(&bytes, &url)?;
+
+        let bytes: Arc<[u8]> = bytes.to_vec().into();
+        let source = match self.path_for(&pin.program_id, &pin.sha256) {
+            Some(path) => {
+                store(&path, &bytes)?;
+                ElfSource::Downloaded(path)
+            }
+            None => ElfSource::Bundled,
+        };
+        Ok(ProgramElf {
+            sha256: pin.sha256.to_lowercase(),
+            bytes,
+            source,
+        })
+    }
+
+    /// Loads the ELF with the given SHA-256, which the caller has already resolved and
+    /// verified against the manifest. Used by prover subprocesses.
+    pub fn load(
+        &self,
+        program: &dyn GuestProgram,
+        sha256: &str,
+    ) -> Result<Arc<[u8]>, ProgramCacheError> {
+        if let Some(bundled) = program.bundled_elf() {
+            if sha256_hex(bundled).eq_ignore_ascii_case(sha256) {
+                return Ok(bundled.into());
+            }
+        }
+        let path = self
+            .path_for(program.id(), &sha256.to_lowercase())
+            .ok_or_else(|| ProgramCacheError::NotInstalled(program.id().to_string()))?;
+        let bytes = fs::read(&path).map_err(|source| ProgramCacheError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        check_hash(
+            "SHA-256",
+            sha256,
+            &sha256_hex(&bytes),
+            &path.display().to_string(),
+        )?;
+        Ok(bytes.into())
+    }
+
+    /// Every ELF in the cache directory.
+    pub fn installed_elfs(&self) -> Result<Vec<CachedElf>, ProgramCacheError> {
+        let Some(dir) = &self.dir else {
+            return Ok(Vec::new());
+        };
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let io_error = |path: &Path| {
+            let path = path.to_path_buf();
+            move |source| ProgramCacheError::Io { path, source }
+        };
+
+        let mut elfs = Vec::new();
+        for program_dir in fs::read_dir(dir).map_err(io_error(dir))? {
+            let program_dir = program_dir.map_err(io_error(dir))?.path();
+            if !program_dir.is_dir() {
+                continue;
+            }
+            let Some(program_id) = program_dir.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            for file in fs::read_dir(&program_dir).map_err(io_error(&program_dir))? {
+                let path = file.map_err(io_error(&program_dir))?.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some("elf") {
+                    continue;
+                }
+                let Some(sha256) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                    continue;
+                };
+                let size = fs::metadata(&path).map_err(io_error(&path))?.len();
+                elfs.push(CachedElf {
+                    program_id: program_id.to_string(),
+                    sha256: sha256.to_string(),
+                    path,
+                    size,
+                });
+            }
+        }
+        elfs.sort_by(|a, b| (&a.program_id, &a.sha256).cmp(&(&b.program_id, &b.sha256)));
+        Ok(elfs)
+    }
+
+    /// Checks a cached ELF against its pin.
+    pub fn check(&self, elf: &CachedElf) -> ElfStatus {
+        let Some(pin) = self
+            .pins_for(&elf.program_id)
+            .find(|pin| pin.sha256.eq_ignore_ascii_case(&elf.sha256))
+        else {
+            return ElfStatus::Unpinned;
+        };
+        let bytes = match fs::read(&elf.path) {
+            Ok(bytes) => bytes,
+            Err(source) => {
+                return ElfStatus::Invalid(ProgramCacheError::Io {
+                    path: elf.path.clone(),
+                    source,
+                });
+            }
+        };
+        match pin.verify(&bytes, &elf.path.display().to_string()) {
+            Ok(()) => ElfStatus::Verified,
+            Err(e) => ElfStatus::Invalid(e),
+        }
+    }
+}
+
+/// Writes an ELF into the cache, through a temporary file so readers never see a partial one.
+fn store(path: &Path, bytes: &[u8]) -> Result<(), ProgramCacheError> {
+    let io_error = |source| ProgramCacheError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    let tmp = path.with_extension("elf.tmp");
+    fs::write(&tmp, bytes).map_err(io_error)?;
+    fs::rename(&tmp, path).map_err(io_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prover::program::FibInputInitial;
+    use tempfile::tempdir;
+
+    const FAKE_ELF: &[u8] = b"\x7fELF not really a program";
+
+    fn pin_for(program_id: &str, bytes: &[u8]) -> ProgramPin {
+        ProgramPin {
+            program_id: program_id.to_string(),
+            sha256: sha256_hex(bytes),
+            keccak256: Some(format!("{:x}", Keccak256::digest(bytes))),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bundled_elf_matches_builtin_pin() {
+        let cache = ProgramCache::new(None);
+        let elf = cache.resolve(&FibInputInitial).await.unwrap();
+        assert_eq!(elf.source, ElfSource::Bundled);
+        assert_eq!(
+            elf.sha256,
+            sha256_hex(FibInputInitial.bundled_elf().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cached_elf_is_preferred_when_pinned() {
+        let dir = tempdir().unwrap();
+        let pin = pin_for("fib_input_initial", FAKE_ELF);
+        let path = dir
+            .path()
+            .join("fib_input_initial")
+            .join(format!("{}.elf", pin.sha256));
+        store(&path, FAKE_ELF).unwrap();
+
+        let cache = ProgramCache::new(Some(dir.path().to_path_buf())).with_pins(vec![pin.clone()]);
+        let elf = cache.resolve(&FibInputInitial).await.unwrap();
+        assert_eq!(elf.source, ElfSource::Cache(path.clone()));
+        assert_eq!(&*elf.bytes, FAKE_ELF);
+        assert_eq!(
+            &*cache.load(&FibInputInitial, &pin.sha256).unwrap(),
+            FAKE_ELF
+        );
+
+        // A tampered file no longer verifies; the bundled ELF is used instead
+        fs::write(&path, b"tampered").unwrap();
+        let elf = cache.resolve(&FibInputInitial).await.unwrap();
+        assert_eq!(elf.source, ElfSource::Bundled);
+        assert!(cache.load(&FibInputInitial, &pin.sha256).is_err());
+    }
+
+    #[test]
+    fn test_installed_elfs_are_checked_against_pins() {
+        let dir = tempdir().unwrap();
+        let pinned = pin_for("fib_input_initial", FAKE_ELF);
+        let unpinned = sha256_hex(b"other");
+        for (sha256, bytes) in [(&pinned.sha256, FAKE_ELF), (&unpinned, b"other".as_slice())] {
+            let path = dir
+                .path()
+                .join("fib_input_initial")
+                .join(format!("{}.elf", sha256));
+            store(&path, bytes).unwrap();
+        }
+
+        let cache = ProgramCache::new(Some(dir.path().to_path_buf())).with_pins(vec![pinned]);
+        let elfs = cache.installed_elfs().unwrap();
+        assert_eq!(elfs.len(), 2);
+        let statuses: Vec<_> = elfs
+            .iter()
+            .map(|elf| (elf.sha256 == unpinned, cache.check(elf)))
+            .collect();
+        for (is_unpinned, status) in statuses {
+            if is_unpinned {
+                assert!(matches!(status, ElfStatus::Unpinned));
+            } else {
+                assert!(matches!(status, ElfStatus::Verified));
+            }
+        }
+    }
+
+    #[test]
+    fn test_pin_rejects_keccak_mismatch() {
+        let mut pin = pin_for("fib_input_initial", FAKE_ELF);
+        pin.keccak256 = Some("00".repeat(32));
+        assert!(matches!(
+            pin.verify(FAKE_ELF, "test"),
+            Err(ProgramCacheError::HashMismatch {
+                algorithm: "Keccak-256",
+                ..
+            })
+        ));
+    }
+}
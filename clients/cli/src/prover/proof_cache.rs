@@ -0,0 +1,360 @@
+//! Content-addressed cache of verified proofs
+//!
+//! Proofs live under `<proofs dir>/<key>.proof`, where the key is the SHA-256 of the ELF's hash
+//! and the input bytes, so a repeated input is found whichever task it came from. Only verified
+//! proofs are stored, and each is verified again before it is served, so a damaged entry costs
+//! a re-prove rather than a rejected submission. The cache is bounded in size and evicts the
+//! least recently used entries first.
+
+use super::pipeline::ProvingPipeline;
+use super::program::GuestProgram;
+use super::program_cache::ProgramElf;
+use super::types::ProverError;
+use super::verifier::VerificationPool;
+use crate::consts::cli_consts::proof_cache;
+use crate::nexus_orchestrator::TaskType;
+use nexus_sdk::stwo::seq::Proof;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::sync::OwnedMutexGuard;
+
+/// File extension for cache entries
+const ENTRY_EXTENSION: &str = "proof";
+
+/// Locks of the inputs being proven, by cache key
+type InFlight = HashMap<String, Arc<tokio::sync::Mutex<()>>>;
+
+/// Get the directory proofs are cached in, next to the config file.
+pub fn get_proof_cache_dir(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .map(|parent| parent.join("proofs"))
+        .unwrap_or_else(|| PathBuf::from("proofs"))
+}
+
+/// Proof cache settings, configurable under `proof_cache` in the config file
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ProofCacheConfig {
+    /// Size limit of the cache (MiB); 0 disables it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_mb: Option<u64>,
+}
+
+impl ProofCacheConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        self.max_mb
+            .unwrap_or(proof_cache::DEFAULT_MAX_MB)
+            .saturating_mul(1024 * 1024)
+    }
+}
+
+/// Lookups since startup
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ProofCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Bounded on-disk cache of verified proofs, keyed by ELF and input
+pub struct ProofCache {
+    /// Cache directory; without one nothing is cached
+    dir: Option<PathBuf>,
+    max_bytes: u64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    /// Inputs being proven, so that a duplicate waits for the first instead of proving too
+    in_flight: Mutex<InFlight>,
+}
+
+impl ProofCache {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir,
+            max_bytes: proof_cache::DEFAULT_MAX_MB * 1024 * 1024,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Bounds the total size of the cached proofs; 0 disables the cache.
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.dir.is_some() && self.max_bytes > 0
+    }
+
+    /// Whether proofs of tasks of this type may be served from the cache. Proof-required tasks
+    /// are always proven afresh, as the orchestrator checks the proofs themselves rather than
+    /// their hashes; their proofs are still stored for later hash-only tasks.
+    pub fn serves(task_type: TaskType) -> bool {
+        matches!(task_type, TaskType::ProofHash | TaskType::AllProofHashes)
+    }
+
+    /// Cache key of a proof: the SHA-256 of the ELF's hash and the input bytes.
+    pub fn key(elf: &ProgramElf, input: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(elf.sha256.as_bytes());
+        hasher.update(input);
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Waits until no other input with this key is being proven. Holding the returned claim
+    /// while proving means a duplicate input proves once and finds the proof in the cache.
+    pub async fn claim(self: &Arc<Self>, key: &str) -> ProofClaim {
+        let lock = self.in_flight().entry(key.to_string()).or_default().clone();
+        ProofClaim {
+            cache: Arc::clone(self),
+            key: key.to_string(),
+            _guard: lock.lock_owned().await,
+        }
+    }
+
+    fn in_flight(&self) -> std::sync::MutexGuard<'_, InFlight> {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The cached proof of `input`, its hash and the time verifying it again took. Entries are
+    /// verified on `verifier` whatever its policy, and entries that no longer verify are removed.
+    pub async fn get(
+        &self,
+        verifier: &VerificationPool,
+        program: &Arc<dyn GuestProgram>,
+        elf: &Arc<ProgramElf>,
+        key: &str,
+        input: &[u8],
+    ) -> Option<(Proof, String, Duration)> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let hit = match self
+            .read_entry(key)
+            .and_then(|bytes| postcard::from_bytes::<Proof>(&bytes).ok())
+        {
+            Some(proof) => {
+                let verified = verifier
+                    .verify(Arc::clone(program), Arc::clone(elf), proof, input.to_vec())
+                    .await;
+                if verified.is_err() {
+                    self.remove_entry(key);
+                }
+                verified.ok()
+            }
+            None => None,
+        };
+        match hit {
+            Some((proof, verification_time)) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                let proof_hash = ProvingPipeline::generate_proof_hash(&proof);
+                Some((proof, proof_hash, verification_time))
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Stores a verified proof, evicting the least recently used entries beyond the size limit.
+    pub fn insert(&self, key: &str, proof: &Proof) -> Result<(), ProverError> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        let bytes = postcard::to_allocvec(proof)?;
+        self.write_entry(key, &bytes)?;
+        Ok(())
+    }
+
+    pub fn stats(&self) -> ProofCacheStats {
+        ProofCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    fn path_for(&self, key: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.{}", key, ENTRY_EXTENSION)))
+    }
+
+    /// Bytes of an entry, marking it as recently used.
+    fn read_entry(&self, key: &str) -> Option<Vec<u8>> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let path = self.path_for(key)?;
+        let bytes = fs::read(&path).ok()?;
+        if let Ok(file) = File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(bytes)
+    }
+
+    fn write_entry(&self, key: &str, bytes: &[u8]) -> io::Result<()> {
+        let (Some(dir), Some(path)) = (&self.dir, self.path_for(key)) else {
+            return Ok(());
+        };
+        // A proof larger than the whole cache would only evict everything else
+        if bytes.len() as u64 > self.max_bytes {
+            return Ok(());
+        }
+        fs::create_dir_all(dir)?;
+        let tmp = path.with_extension("proof.tmp");
+        fs::write(&tmp, bytes)?;
+        fs::rename(&tmp, &path)?;
+        self.evict(dir)
+    }
+
+    fn remove_entry(&self, key: &str) {
+        if let Some(path) = self.path_for(key) {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    /// Removes the least recently used entries until the cache is within its size limit.
+    fn evict(&self, dir: &Path) -> io::Result<()> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(ENTRY_EXTENSION) {
+                continue;
+            }
+            let metadata = fs::metadata(&path)?;
+            let used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            entries.push((used, metadata.len(), path));
+        }
+
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        entries.sort();
+        for (_, len, path) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            fs::remove_file(&path)?;
+            total -= len;
+        }
+        Ok(())
+    }
+}
+
+/// Exclusive right to prove one input, held until its proof is in the cache
+pub struct ProofClaim {
+    cache: Arc<ProofCache>,
+    key: String,
+    _guard: OwnedMutexGuard<()>,
+}
+
+impl Drop for ProofClaim {
+    fn drop(&mut self) {
+        let mut in_flight = self.cache.in_flight();
+        // Forget the key once nobody else holds or waits for it: the map and this claim's
+        // guard are the only references left
+        if in_flight
+            .get(&self.key)
+            .is_some_and(|lock| Arc::strong_count(lock) <= 2)
+        {
+            in_flight.remove(&self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prover::program_cache::ElfSource;
+    use tempfile::tempdir;
+
+    fn set_last_used(cache: &ProofCache, key: &str, secs_ago: u64) {
+        let file = File::options()
+            .write(true)
+            .open(cache.path_for(key).unwrap())
+            .unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(secs_ago))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_least_recently_used_entries_are_evicted() {
+        let dir = tempdir().unwrap();
+        let cache = ProofCache::new(Some(dir.path().to_path_buf())).with_max_bytes(100);
+
+        cache.write_entry("a", &[1; 40]).unwrap();
+        set_last_used(&cache, "a", 30);
+        cache.write_entry("b", &[2; 40]).unwrap();
+        set_last_used(&cache, "b", 20);
+
+        // Reading `a` makes `b` the least recently used
+        assert_eq!(cache.read_entry("a"), Some(vec![1; 40]));
+        cache.write_entry("c", &[3; 40]).unwrap();
+
+        assert!(cache.read_entry("a").is_some());
+        assert!(cache.read_entry("b").is_none());
+        assert!(cache.read_entry("c").is_some());
+    }
+
+    #[test]
+    fn test_disabled_cache_stores_nothing() {
+        let dir = tempdir().unwrap();
+        let cache = ProofCache::new(Some(dir.path().to_path_buf())).with_max_bytes(0);
+        assert!(!cache.is_enabled());
+
+        cache.write_entry("a", &[1; 40]).unwrap();
+        assert!(cache.read_entry("a").is_none());
+        assert!(fs::read_dir(dir.path()).unwrap().next().is_none());
+    }
+
+    #[test]
+    fn test_key_covers_elf_and_input() {
+        let elf = |sha256: &str| ProgramElf {
+            sha256: sha256.to_string(),
+            bytes: Arc::from(&[][..]),
+            source: ElfSource::Bundled,
+        };
+        let key = ProofCache::key(&elf("aa"), &[1, 2, 3]);
+        assert_eq!(key, ProofCache::key(&elf("aa"), &[1, 2, 3]));
+        assert_ne!(key, ProofCache::key(&elf("bb"), &[1, 2, 3]));
+        assert_ne!(key, ProofCache::key(&elf("aa"), &[1, 2, 4]));
+    }
+
+    #[test]
+    fn test_only_hash_tasks_are_served() {
+        assert!(ProofCache::serves(TaskType::ProofHash));
+        assert!(ProofCache::serves(TaskType::AllProofHashes));
+        assert!(!ProofCache::serves(TaskType::ProofRequired));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_claims_wait_for_the_first() {
+        let cache = Arc::new(ProofCache::new(None));
+        let first = cache.claim("key").await;
+
+        let waiting = tokio::spawn({
+            let cache = Arc::clone(&cache);
+            async move { cache.claim("key").await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        drop(first);
+        let second = waiting.await.unwrap();
+        drop(second);
+        assert!(cache.in_flight().is_empty());
+    }
+}
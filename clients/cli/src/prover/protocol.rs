@@ -0,0 +1,231 @@
+//! Framed protocol between the CLI and its prover subprocesses
+//!
+//! Each frame is a big-endian `u32` length followed by a postcard-encoded message. The parent
+//! sends [`Request`]s on the subprocess's stdin; the subprocess answers on stdout with
+//! [`Response`]s, streaming each proof back in chunks and sending heartbeats while it works.
+
+use super::types::ProverError;
+use crate::consts::cli_consts::prover_pool;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Parent to subprocess
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    /// Prove one public input of a guest program
+    Prove {
+        job_id: u64,
+        program_id: String,
+        /// SHA-256 of the verified ELF to load
+        elf_sha256: String,
+        input: Vec<u8>,
+    },
+    /// Exit once the current job, if any, is done
+    Shutdown,
+}
+
+/// Subprocess to parent
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    /// Sent once at startup
+    Ready { pid: u32 },
+    /// Sent periodically, busy or not
+    Heartbeat { rss_bytes: u64 },
+    /// Part of a postcard-encoded proof
+    ProofChunk { job_id: u64, bytes: Vec<u8> },
+    /// Every chunk of the proof has been sent
+    ProofDone {
+        job_id: u64,
+        proof_len: u64,
+        rss_bytes: u64,
+    },
+    /// The job failed; the subprocess is ready for the next one
+    Failed { job_id: u64, error: JobError },
+}
+
+/// Which [`ProverError`] a failed job maps back to in the parent
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobErrorKind {
+    Stwo,
+    GuestProgram,
+    MalformedTask,
+    Other,
+}
+
+/// Error of a failed job, carried across the process boundary
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct JobError {
+    pub kind: JobErrorKind,
+    pub message: String,
+}
+
+impl From<&ProverError> for JobError {
+    fn from(error: &ProverError) -> Self {
+        let (kind, message) = match error {
+            ProverError::Stwo(message) => (JobErrorKind::Stwo, message.clone()),
+            ProverError::GuestProgram(message) => (JobErrorKind::GuestProgram, message.clone()),
+            ProverError::MalformedTask(message) => (JobErrorKind::MalformedTask, message.clone()),
+            other => (JobErrorKind::Other, other.to_string()),
+        };
+        JobError { kind, message }
+    }
+}
+
+impl From<JobError> for ProverError {
+    fn from(error: JobError) -> Self {
+        match error.kind {
+            JobErrorKind::Stwo => ProverError::Stwo(error.message),
+            JobErrorKind::GuestProgram => ProverError::GuestProgram(error.message),
+            JobErrorKind::MalformedTask => ProverError::MalformedTask(error.message),
+            JobErrorKind::Other => ProverError::Subprocess(error.message),
+        }
+    }
+}
+
+fn encode<T: Serialize>(message: &T) -> io::Result<Vec<u8>> {
+    let body = postcard::to_allocvec(message)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let len = u32::try_from(body.len())
+        .ok()
+        .filter(|len| *len <= prover_pool::MAX_FRAME_BYTES)
+        .ok_or_else(|| frame_too_large(body.len() as u64))?;
+    let mut frame = Vec::with_capacity(4 + body.len());
+    frame.extend_from_slice(&len.to_be_bytes());
+    frame.extend_from_slice(&body);
+    Ok(frame)
+}
+
+fn decode<T: DeserializeOwned>(body: &[u8]) -> io::Result<T> {
+    postcard::from_bytes(body).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn body_len(header: [u8; 4]) -> io::Result<usize> {
+    let len = u32::from_be_bytes(header);
+    if len > prover_pool::MAX_FRAME_BYTES {
+        return Err(frame_too_large(len as u64));
+    }
+    Ok(len as usize)
+}
+
+fn frame_too_large(len: u64) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+            "frame of {} bytes exceeds the limit of {}",
+            len,
+            prover_pool::MAX_FRAME_BYTES
+        ),
+    )
+}
+
+/// Writes one frame and flushes it.
+pub fn write_frame<W: Write, T: Serialize>(writer: &mut W, message: &T) -> io::Result<()> {
+    writer.write_all(&encode(message)?)?;
+    writer.flush()
+}
+
+/// Reads one frame; `None` once the other side has closed the stream.
+pub fn read_frame<R: Read, T: DeserializeOwned>(reader: &mut R) -> io::Result<Option<T>> {
+    let mut header = [0u8; 4];
+    match reader.read_exact(&mut header) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let mut body = vec![0u8; body_len(header)?];
+    reader.read_exact(&mut body)?;
+    decode(&body).map(Some)
+}
+
+/// Writes one frame and flushes it.
+pub async fn write_frame_async<W: AsyncWrite + Unpin, T: Serialize>(
+    writer: &mut W,
+    message: &T,
+) -> io::Result<()> {
+    writer.write_all(&encode(message)?).await?;
+    writer.flush().await
+}
+
+/// Reads one frame; `None` once the other side has closed the stream.
+pub async fn read_frame_async<R: AsyncRead + Unpin, T: DeserializeOwned>(
+    reader: &mut R,
+) -> io::Result<Option<T>> {
+    let mut header = [0u8; 4];
+    match reader.read_exact(&mut header).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let mut body = vec![0u8; body_len(header)?];
+    reader.read_exact(&mut body).await?;
+    decode(&body).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames_round_trip() {
+        let messages = vec![
+            Response::Ready { pid: 42 },
+            Response::ProofChunk {
+                job_id: 1,
+                bytes: vec![7; 1000],
+            },
+            Response::Failed {
+                job_id: 2,
+                error: JobError::from(&ProverError::GuestProgram("exit code 1".to_string())),
+            },
+        ];
+        let mut stream = Vec::new();
+        for message in &messages {
+            write_frame(&mut stream, message).unwrap();
+        }
+
+        let mut reader = stream.as_slice();
+        let mut decoded = Vec::new();
+        while let Some(message) = read_frame::<_, Response>(&mut reader).unwrap() {
+            decoded.push(message);
+        }
+        assert_eq!(decoded, messages);
+    }
+
+    #[tokio::test]
+    async fn test_async_frames_round_trip() {
+        let (mut parent, mut child) = tokio::io::duplex(1024);
+        let request = Request::Prove {
+            job_id: 3,
+            program_id: "fib_input_initial".to_string(),
+            elf_sha256: "ab".repeat(32),
+            input: vec![1, 0, 0, 0],
+        };
+        write_frame_async(&mut parent, &request).await.unwrap();
+        drop(parent);
+
+        let received: Option<Request> = read_frame_async(&mut child).await.unwrap();
+        assert_eq!(received, Some(request));
+        let closed: Option<Request> = read_frame_async(&mut child).await.unwrap();
+        assert_eq!(closed, None);
+    }
+
+    #[test]
+    fn test_oversized_frames_are_rejected() {
+        let mut stream = (prover_pool::MAX_FRAME_BYTES + 1).to_be_bytes().to_vec();
+        stream.extend_from_slice(&[0; 16]);
+        let result = read_frame::<_, Response>(&mut stream.as_slice());
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_job_errors_keep_their_kind() {
+        let error: ProverError = JobError::from(&ProverError::Stwo("bad".to_string())).into();
+        assert!(matches!(error, ProverError::Stwo(message) if message == "bad"));
+
+        let error: ProverError =
+            JobError::from(&ProverError::Subprocess("gone".to_string())).into();
+        assert!(matches!(error, ProverError::Subprocess(_)));
+    }
+}
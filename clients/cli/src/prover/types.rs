@@ -1,6 +1,8 @@
 //! Proof types and error definitions
 
+use super::program_cache::ProgramCacheError;
 use nexus_sdk::stwo::seq::Proof;
+use std::time::Duration;
 use thiserror::Error;
 use tokio::task::JoinError;
 
@@ -29,6 +31,27 @@ pub enum ProverError {
 
     #[error("Task Join Error: {0}")]
     JoinError(JoinError),
+
+    #[error("Guest program ELF error: {0}")]
+    Program(#[from] ProgramCacheError),
+
+    #[error("Prover subprocess ran out of memory: {0}")]
+    OutOfMemory(String),
+
+    #[error("Prover subprocess exceeded its CPU time limit of {0:?}")]
+    CpuTimeExceeded(Duration),
+
+    #[error("Proving timed out after {0:?}")]
+    TimedOut(Duration),
+
+    #[error("Prover subprocess crashed: {0}")]
+    Crashed(String),
+
+    #[error("Proving cancelled")]
+    Cancelled,
+
+    #[error("Proof verifier threads are not running")]
+    VerifierUnavailable,
 }
 
 /// Result of a proof generation, including combined hash for multiple inputs
@@ -36,4 +59,6 @@ pub struct ProverResult {
     pub proofs: Vec<Proof>,
     pub combined_hash: String,
     pub individual_proof_hashes: Vec<String>,
+    /// Time spent verifying the proofs, summed over inputs
+    pub verification_time: Duration,
 }
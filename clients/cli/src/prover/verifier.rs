@@ -1,46 +1,205 @@
 //! Proof verification
+//!
+//! Proofs are verified on a [`VerificationPool`] of dedicated threads, off the async runtime,
+//! with the verifier state of each ELF loaded once and reused. The [`VerificationPolicy`]
+//! decides how many proofs of tasks are verified before they are submitted.
 
+use super::engine::ProvingEngine;
+use super::program::GuestProgram;
+use super::program_cache::ProgramElf;
 use super::types::ProverError;
-use nexus_sdk::{KnownExitCodes, Verifiable, Viewable, stwo::seq::Proof};
+use crate::consts::cli_consts::verification;
+use nexus_sdk::{Verifiable, Viewable, stwo::seq::Proof};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+/// Which proofs of tasks are verified before submission, configurable under `verification` in
+/// the config file
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum VerificationPolicy {
+    /// Every proof
+    #[default]
+    Full,
+    /// One proof in every `SAMPLE_INTERVAL`
+    Sampled,
+    /// None; for trusted hardware only
+    None,
+}
+
+impl VerificationPolicy {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl FromStr for VerificationPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "full" => Ok(VerificationPolicy::Full),
+            "sampled" => Ok(VerificationPolicy::Sampled),
+            "none" => Ok(VerificationPolicy::None),
+            _ => Err(format!(
+                "unknown verification policy '{}', expected full, sampled or none",
+                s
+            )),
+        }
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Dedicated threads verifying proofs under a [`VerificationPolicy`]
+pub struct VerificationPool {
+    policy: VerificationPolicy,
+    jobs: mpsc::Sender<Job>,
+    /// Proofs the policy has been asked about, for sampling
+    proofs_seen: AtomicU64,
+}
+
+impl VerificationPool {
+    pub fn new(policy: VerificationPolicy, threads: usize) -> Self {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for index in 0..threads.max(1) {
+            let receiver = Arc::clone(&receiver);
+            thread::Builder::new()
+                .name(format!("proof-verifier-{}", index))
+                .spawn(move || {
+                    loop {
+                        let job = receiver
+                            .lock()
+                            .unwrap_or_else(|poisoned| poisoned.into_inner())
+                            .recv();
+                        match job {
+                            Ok(job) => job(),
+                            Err(_) => break,
+                        }
+                    }
+                })
+                .expect("Failed to spawn proof verifier thread");
+        }
+        Self {
+            policy,
+            jobs,
+            proofs_seen: AtomicU64::new(0),
+        }
+    }
+
+    /// Whether the next proof of a task should be verified.
+    pub fn is_due(&self) -> bool {
+        match self.policy {
+            VerificationPolicy::Full => true,
+            VerificationPolicy::Sampled => {
+                self.proofs_seen.fetch_add(1, Ordering::Relaxed) % verification::SAMPLE_INTERVAL
+                    == 0
+            }
+            VerificationPolicy::None => false,
+        }
+    }
+
+    /// Verifies a proof of a run on the public input encoded in `input` on one of the pool's
+    /// threads. Returns the proof and the time verification took, not counting time queued.
+    pub async fn verify(
+        &self,
+        program: Arc<dyn GuestProgram>,
+        elf: Arc<ProgramElf>,
+        proof: Proof,
+        input: Vec<u8>,
+    ) -> Result<(Proof, Duration), ProverError> {
+        let (sender, receiver) = oneshot::channel();
+        let job: Job = Box::new(move || {
+            let started = Instant::now();
+            let result = ProvingEngine::verify(program.as_ref(), &elf, &proof, &input);
+            let _ = sender.send(result.map(|()| (proof, started.elapsed())));
+        });
+        self.jobs
+            .send(job)
+            .map_err(|_| ProverError::VerifierUnavailable)?;
+        receiver
+            .await
+            .map_err(|_| ProverError::VerifierUnavailable)?
+    }
+}
 
 /// Proof verifier for validating generated proofs
 pub struct ProofVerifier;
 
 impl ProofVerifier {
-    /// Verify a proof with expected inputs and exit code
-    pub fn verify_proof(
+    /// Verify a proof with expected decoded inputs and exit code
+    pub fn verify_proof<T: Serialize + Debug>(
         proof: &Proof,
-        inputs: &(u32, u32, u32),
+        input: &T,
+        expected_exit_code: u32,
         prover: &nexus_sdk::stwo::seq::Stwo<nexus_sdk::Local>,
     ) -> Result<(), ProverError> {
-        match proof.verify_expected::<(u32, u32, u32), ()>(
-            inputs,
-            KnownExitCodes::ExitSuccess as u32,
-            &(),
-            &prover.elf,
-            &[],
-        ) {
+        let result =
+            proof.verify_expected::<T, ()>(input, expected_exit_code, &(), &prover.elf, &[]);
+        match result {
             Ok(_) => Ok(()),
             Err(e) => Err(ProverError::Stwo(format!(
                 "Proof verification failed: {} for inputs: {:?}",
-                e, inputs
+                e, input
             ))),
         }
     }
 
     /// Check exit code from proof execution
-    pub fn check_exit_code<T: Viewable>(view: &T) -> Result<(), ProverError> {
+    pub fn check_exit_code<T: Viewable>(view: &T, expected: u32) -> Result<(), ProverError> {
         let exit_code = view.exit_code().map_err(|e| {
             ProverError::GuestProgram(format!("Failed to deserialize exit code: {}", e))
         })?;
 
-        if exit_code != KnownExitCodes::ExitSuccess as u32 {
+        if exit_code != expected {
             return Err(ProverError::GuestProgram(format!(
-                "Prover exited with non-zero exit code: {}",
-                exit_code
+                "Prover exited with unexpected exit code: {} (expected {})",
+                exit_code, expected
             )));
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_parsing() {
+        assert_eq!(
+            " Sampled ".parse::<VerificationPolicy>(),
+            Ok(VerificationPolicy::Sampled)
+        );
+        assert_eq!("none".parse(), Ok(VerificationPolicy::None));
+        assert!("some".parse::<VerificationPolicy>().is_err());
+        assert_eq!(
+            serde_json::to_string(&VerificationPolicy::Full).unwrap(),
+            "\"full\""
+        );
+    }
+
+    #[test]
+    fn test_sampled_policy_verifies_one_proof_per_interval() {
+        let due = |policy| {
+            let pool = VerificationPool::new(policy, 1);
+            (0..3 * verification::SAMPLE_INTERVAL)
+                .filter(|_| pool.is_due())
+                .count() as u64
+        };
+        assert_eq!(
+            due(VerificationPolicy::Full),
+            3 * verification::SAMPLE_INTERVAL
+        );
+        assert_eq!(due(VerificationPolicy::Sampled), 3);
+        assert_eq!(due(VerificationPolicy::None), 0);
+    }
+}
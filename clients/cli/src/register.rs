@@ -1,9 +1,13 @@
 //! Registering a new user and node with the orchestrator.
 
-use crate::cli_messages::{print_error, print_info, print_success};
+use crate::cli_messages::{print_error, print_info, print_success, print_warn};
 use crate::config::Config;
 use crate::keys;
+use crate::nexus_orchestrator::NodeType;
 use crate::orchestrator::Orchestrator;
+use crate::orchestrator::client::RegisteredNode;
+use crate::orchestrator::error::OrchestratorError;
+use std::io::{BufRead, Write};
 use std::path::Path;
 
 /// Registers a user with the orchestrator.
@@ -58,29 +62,40 @@ pub async fn register_user(
     }
 
     // Check if the wallet address is already registered with the orchestrator.
-    if let Ok(user_id) = orchestrator.get_user(wallet_address).await {
-        print_info(
-            "Wallet address is already registered",
-            &format!("User ID: {}, Wallet Address: {}", user_id, wallet_address),
-        );
-        let config = Config::new(
-            user_id,
-            wallet_address.to_string(),
-            String::new(), // node_id is empty for now
-            orchestrator.environment().clone(),
-        );
-        // Save the configuration file with the user ID and wallet address.
-        config.save(config_path).inspect_err(|e| {
-            print_error("Failed to save config", Some(&e.to_string()));
-        })?;
+    match orchestrator.get_user(wallet_address).await {
+        Ok(user_id) => {
+            print_info(
+                "Wallet address is already registered",
+                &format!("User ID: {}, Wallet Address: {}", user_id, wallet_address),
+            );
+            let config = Config::new(
+                user_id,
+                wallet_address.to_string(),
+                String::new(), // node_id is empty for now
+                orchestrator.environment().clone(),
+            );
+            // Save the configuration file with the user ID and wallet address.
+            config.save(config_path).inspect_err(|e| {
+                print_error("Failed to save config", Some(&e.to_string()));
+            })?;
 
-        // Guide user to next step
-        print_success(
-            "User registration complete!",
-            "Next step - register a node: nexus-cli register-node",
-        );
+            // Guide user to next step
+            print_success(
+                "User registration complete!",
+                "Next step - register a node: nexus-cli register-node",
+            );
 
-        return Ok(());
+            return Ok(());
+        }
+        // Not registered yet, register below
+        Err(OrchestratorError::UserNotFound { .. }) => {}
+        Err(e) => {
+            print_error(
+                "Failed to look up wallet address",
+                Some(&failure_detail(&e)),
+            );
+            return Err(e.into());
+        }
     }
 
     // Otherwise, register the user with the orchestrator.
@@ -93,13 +108,7 @@ pub async fn register_user(
             );
         }
         Err(e) => {
-            // Check if this looks like an orchestrator traffic issue
-            if let Some(pretty_error) = e.to_pretty() {
-                print_error("Failed to register user", Some(&pretty_error));
-            } else {
-                print_error("Failed to register user", Some(&e.to_string()));
-            }
-
+            print_error("Failed to register user", Some(&failure_detail(&e)));
             return Err(e.into());
         }
     }
@@ -130,10 +139,12 @@ pub async fn register_user(
 /// * `node_id` - Optional node ID. If provided, it will be used to register the node.
 /// * `config_path` - The path to the configuration file where node details will be saved.
 /// * `orchestrator` - The orchestrator client to communicate with the orchestrator.
+/// * `interactive` - Whether to offer the user's existing CLI nodes before registering a new one.
 pub async fn register_node(
     node_id: Option<u64>,
     config_path: &Path,
     orchestrator: Box<dyn Orchestrator>,
+    interactive: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Register a new node, or link an existing node to a user.
     // Requires: a config file with a registered user.
@@ -151,10 +162,15 @@ pub async fn register_node(
             "No user registered. Please register a user first.",
         ));
     }
+    let node_id = match node_id {
+        Some(node_id) => Some(node_id.to_string()),
+        None if interactive => pick_existing_node(&config, orchestrator.as_ref()).await,
+        None => None,
+    };
     if let Some(node_id) = node_id {
-        // If a node_id is provided, update the config with it.
+        // If a node_id is provided or picked, update the config with it.
         println!("Registering node ID: {}", node_id);
-        config.node_id = node_id.to_string();
+        config.node_id = node_id.clone();
         config.save(config_path).inspect_err(|e| {
             print_error("Failed to save updated config", Some(&e.to_string()));
         })?;
@@ -195,13 +211,102 @@ pub async fn register_node(
                 Ok(())
             }
             Err(e) => {
-                print_error("Failed to register node", Some(&e.to_string()));
+                print_error("Failed to register node", Some(&failure_detail(&e)));
                 Err(e.into())
             }
         }
     }
 }
 
+/// Describes a failed registration request, with what to do about it where that is known.
+fn failure_detail(error: &OrchestratorError) -> String {
+    // Raw responses may carry the orchestrator's error JSON
+    let detail = error.to_pretty().unwrap_or_else(|| error.to_string());
+    match error {
+        OrchestratorError::UserNotFound { .. } => format!(
+            "{}. Register your wallet first: nexus-cli register-user --wallet-address <your-wallet-address>",
+            detail
+        ),
+        OrchestratorError::VersionRejected { .. } => {
+            format!("{}. Upgrade the CLI and try again", detail)
+        }
+        _ => detail,
+    }
+}
+
+/// Offers the user's existing CLI nodes. Returns the picked node ID, or `None` to register a
+/// new node, including when the lookup fails.
+async fn pick_existing_node(config: &Config, orchestrator: &dyn Orchestrator) -> Option<String> {
+    if config.wallet_address.is_empty() {
+        return None;
+    }
+    let nodes: Vec<RegisteredNode> = match orchestrator.get_user_page(&config.wallet_address).await
+    {
+        Ok(page) => page.nodes,
+        Err(e) => {
+            print_warn("Could not look up existing nodes", &e.to_string());
+            return None;
+        }
+    };
+    let cli_nodes: Vec<RegisteredNode> = nodes
+        .into_iter()
+        .filter(|node| node.node_type == NodeType::CliProver)
+        .collect();
+    if cli_nodes.is_empty() {
+        return None;
+    }
+
+    let stdin = std::io::stdin();
+    choose_existing_node(&cli_nodes, &mut stdin.lock(), &mut std::io::stdout()).unwrap_or(None)
+}
+
+/// Prompts for one of `nodes` by number or node ID. An empty answer or end of input means
+/// registering a new node.
+fn choose_existing_node(
+    nodes: &[RegisteredNode],
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> std::io::Result<Option<String>> {
+    writeln!(
+        output,
+        "This wallet already has {} CLI node(s):",
+        nodes.len()
+    )?;
+    for (index, node) in nodes.iter().enumerate() {
+        writeln!(output, "  [{}] {}", index + 1, node.node_id)?;
+    }
+
+    loop {
+        write!(
+            output,
+            "Pick a node to use, or press Enter to register a new one: "
+        )?;
+        output.flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let choice = line.trim();
+        if choice.is_empty() {
+            return Ok(None);
+        }
+        if let Ok(number) = choice.parse::<usize>() {
+            if (1..=nodes.len()).contains(&number) {
+                return Ok(Some(nodes[number - 1].node_id.clone()));
+            }
+        }
+        if let Some(node) = nodes.iter().find(|node| node.node_id == choice) {
+            return Ok(Some(node.node_id.clone()));
+        }
+        writeln!(
+            output,
+            "Enter a number between 1 and {}, or a node ID",
+            nodes.len()
+        )?;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,10 +334,8 @@ mod tests {
             .expect_get_user()
             .with(eq(WALLET))
             .returning(|_| {
-                Err(OrchestratorError::Http {
-                    status: 404,
+                Err(OrchestratorError::UserNotFound {
                     message: "User not found".to_string(),
-                    headers: std::collections::HashMap::new(),
                 })
             });
 
@@ -252,6 +355,31 @@ mod tests {
         assert!(!cfg.user_id.is_empty());
     }
 
+    /// A failed lookup must not be mistaken for an unregistered wallet.
+    #[tokio::test]
+    async fn does_not_register_when_lookup_fails() {
+        const WALLET: &str = "0x1234567890123456789012345678901234567890";
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.json");
+
+        let mut orchestrator = MockOrchestrator::new();
+        orchestrator.expect_get_user().returning(|_| {
+            Err(OrchestratorError::Http {
+                status: 503,
+                message: "Service unavailable".to_string(),
+                headers: std::collections::HashMap::new(),
+            })
+        });
+        orchestrator.expect_register_user().never();
+
+        assert!(
+            register_user(WALLET, &path, Box::new(orchestrator))
+                .await
+                .is_err()
+        );
+        assert!(!path.exists());
+    }
+
     #[tokio::test]
     /// Config file already exists with a registered user.
     async fn skips_registration_if_config_matches_wallet_and_user_id() {
@@ -298,4 +426,61 @@ mod tests {
             wallet_address.to_lowercase()
         );
     }
+
+    fn cli_node(node_id: &str) -> RegisteredNode {
+        RegisteredNode {
+            node_id: node_id.to_string(),
+            node_type: NodeType::CliProver,
+        }
+    }
+
+    #[test]
+    /// Existing nodes can be picked by number or by ID; an empty answer registers a new one.
+    fn choose_existing_node_by_number_or_id() {
+        let nodes = vec![cli_node("1000000"), cli_node("1000001")];
+        let mut output = Vec::new();
+
+        let mut input = std::io::Cursor::new("2\n");
+        let choice = choose_existing_node(&nodes, &mut input, &mut output).unwrap();
+        assert_eq!(choice.as_deref(), Some("1000001"));
+
+        let mut input = std::io::Cursor::new("9\n1000000\n");
+        let choice = choose_existing_node(&nodes, &mut input, &mut output).unwrap();
+        assert_eq!(choice.as_deref(), Some("1000000"));
+
+        let mut input = std::io::Cursor::new("\n");
+        let choice = choose_existing_node(&nodes, &mut input, &mut output).unwrap();
+        assert_eq!(choice, None);
+    }
+
+    #[tokio::test]
+    /// Non-interactive runs skip the node lookup and register a new node.
+    async fn register_node_without_prompt_registers_new_node() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        Config::new(
+            "user-1".to_string(),
+            "0x1234567890123456789012345678901234567890".to_string(),
+            String::new(),
+            Environment::Production,
+        )
+        .save(&path)
+        .unwrap();
+
+        let mut orchestrator = MockOrchestrator::new();
+        orchestrator
+            .expect_environment()
+            .return_const(Environment::Production);
+        orchestrator.expect_get_user_page().never();
+        orchestrator
+            .expect_register_node()
+            .with(eq("user-1"))
+            .returning(|_| Ok("1000002".to_string()));
+
+        register_node(None, &path, Box::new(orchestrator), false)
+            .await
+            .expect("registration should succeed");
+
+        assert_eq!(Config::load_from_file(&path).unwrap().node_id, "1000002");
+    }
 }
@@ -2,25 +2,38 @@
 
 use crate::environment::Environment;
 use crate::events::Event;
-use crate::orchestrator::OrchestratorClient;
+use crate::network::{BreakerState, CircuitBreaker, CircuitBreakerConfig, RetryPolicies};
+use crate::orchestrator::Orchestrator;
+use crate::prover::ProvingContext;
 use crate::workers::authenticated_worker::AuthenticatedWorker;
-use crate::workers::core::WorkerConfig;
+use crate::workers::core::{EventSender, WorkerConfig};
 use ed25519_dalek::SigningKey;
-use tokio::sync::{broadcast, mpsc};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{Semaphore, broadcast, mpsc};
 use tokio::task::JoinHandle;
 
-/// Start single authenticated worker
+/// Start one authenticated worker per node
+///
+/// All workers share one event channel and one pool of `num_workers` proving slots. Each node
+/// keeps its own signing key and rate-limit state, while one circuit breaker guards all of their
+/// requests. With `max_tasks`, every node proves up to that many tasks and the returned sender
+/// fires once all of them are done.
 #[allow(clippy::too_many_arguments)]
-pub async fn start_authenticated_worker(
-    node_id: u64,
-    signing_key: SigningKey,
-    orchestrator: OrchestratorClient,
+pub async fn start_authenticated_workers(
+    nodes: Vec<(u64, SigningKey)>,
+    orchestrator: Arc<dyn Orchestrator>,
     shutdown: broadcast::Receiver<()>,
     environment: Environment,
     client_id: String,
     max_tasks: Option<u32>,
     max_difficulty: Option<crate::nexus_orchestrator::TaskDifficulty>,
+    initial_difficulty: Option<crate::nexus_orchestrator::TaskDifficulty>,
     num_workers: usize,
+    spool_dir: Option<PathBuf>,
+    retry_policies: RetryPolicies,
+    circuit_breaker: CircuitBreakerConfig,
+    proving: ProvingContext,
 ) -> (
     mpsc::Receiver<Event>,
     Vec<JoinHandle<()>>,
@@ -28,23 +41,73 @@ pub async fn start_authenticated_worker(
 ) {
     let mut config = WorkerConfig::new(environment, client_id);
     config.max_difficulty = max_difficulty;
+    config.initial_difficulty = initial_difficulty;
     config.num_workers = num_workers;
+    config.spool_dir = spool_dir;
+    config.retry_policies = retry_policies;
+    config.proving_permits = Arc::new(Semaphore::new(num_workers));
+    config.circuit_breaker = Arc::new(CircuitBreaker::new(circuit_breaker));
+    config.proving = proving;
+
+    // Cancel in-flight proving as soon as shutdown is requested
+    let cancellation = config.cancellation.clone();
+    let mut shutdown_requested = shutdown.resubscribe();
+    tokio::spawn(async move {
+        let _ = shutdown_requested.recv().await;
+        cancellation.cancel();
+    });
     let (event_sender, event_receiver) =
         mpsc::channel::<Event>(crate::consts::cli_consts::EVENT_QUEUE_SIZE);
 
+    // Report breaker state changes; ends once the workers drop the breaker
+    let mut breaker_states = config.circuit_breaker.subscribe();
+    let breaker_events = EventSender::new(event_sender.clone());
+    tokio::spawn(async move {
+        while breaker_states.changed().await.is_ok() {
+            let state: BreakerState = *breaker_states.borrow_and_update();
+            breaker_events
+                .send_event(Event::circuit_breaker(state))
+                .await;
+        }
+    });
+
     // Create a separate shutdown sender for max tasks completion
     let (shutdown_sender, _) = broadcast::channel(1);
 
-    let worker = AuthenticatedWorker::new(
-        node_id,
-        signing_key,
-        orchestrator,
-        config,
-        event_sender,
-        max_tasks,
-        shutdown_sender.clone(),
-    );
-
-    let join_handles = worker.run(shutdown).await;
+    // Only attribute events to nodes when there is more than one to tell apart
+    let tag_events = nodes.len() > 1;
+
+    let mut join_handles = Vec::new();
+    let mut node_done_receivers = Vec::new();
+    for (node_id, signing_key) in nodes {
+        let (node_done_sender, node_done_receiver) = broadcast::channel(1);
+        node_done_receivers.push(node_done_receiver);
+
+        let mut worker_events = EventSender::new(event_sender.clone());
+        if tag_events {
+            worker_events = worker_events.for_node(node_id);
+        }
+
+        let worker = AuthenticatedWorker::new(
+            node_id,
+            signing_key,
+            orchestrator.clone(),
+            config.clone(),
+            worker_events,
+            max_tasks,
+            node_done_sender,
+        );
+        join_handles.extend(worker.run(shutdown.resubscribe()).await);
+    }
+
+    // Signal max tasks completion once every node has finished
+    let all_done_sender = shutdown_sender.clone();
+    tokio::spawn(async move {
+        for mut node_done_receiver in node_done_receivers {
+            let _ = node_done_receiver.recv().await;
+        }
+        let _ = all_done_sender.send(());
+    });
+
     (event_receiver, join_handles, shutdown_sender)
 }
@@ -2,10 +2,16 @@
 
 use super::{
     SessionData,
-    messages::{print_session_exit_success, print_session_shutdown, print_session_starting},
+    messages::{
+        print_node_summary, print_session_exit_success, print_session_shutdown,
+        print_session_starting,
+    },
+    wait_for_workers,
 };
+use crate::events::NodeActivity;
 use crate::print_cmd_info;
 use crate::version::checker::check_for_new_version;
+use std::collections::BTreeMap;
 use std::error::Error;
 
 /// Runs the application in headless mode
@@ -23,15 +29,18 @@ use std::error::Error;
 /// * `Err` - Headless mode failed
 pub async fn run_headless_mode(mut session: SessionData) -> Result<(), Box<dyn Error>> {
     // Print session start message
-    print_session_starting("headless", session.node_id);
+    print_session_starting("headless", &session.node_ids);
 
     // Check for new version and inform user
     let current_version = env!("CARGO_PKG_VERSION");
 
     // First check constraint violations
-    if let Some(message) = check_for_new_version(current_version).await {
-        // If no constraints violated, check for newer versions available
-        print_cmd_info!("Version check", "{}", message);
+    // Skipped for a local orchestrator so that offline runs don't wait on GitHub
+    if !session.environment.is_local() {
+        if let Some(message) = check_for_new_version(current_version).await {
+            // If no constraints violated, check for newer versions available
+            print_cmd_info!("Version check", "{}", message);
+        }
     }
 
     // Trigger shutdown on Ctrl+C
@@ -45,10 +54,16 @@ pub async fn run_headless_mode(mut session: SessionData) -> Result<(), Box<dyn E
     let mut shutdown_receiver = session.shutdown_sender.subscribe();
     let mut max_tasks_shutdown_receiver = session.max_tasks_shutdown_sender.subscribe();
 
+    // Per-node totals, reported on exit when several nodes share the session
+    let mut node_activity: BTreeMap<u64, NodeActivity> = BTreeMap::new();
+
     // Event loop: log events to console until shutdown
     loop {
         tokio::select! {
             Some(event) = session.event_receiver.recv() => {
+                if let Some(node_id) = event.node_id {
+                    node_activity.entry(node_id).or_default().record(&event);
+                }
                 println!("{}", event);
             }
             _ = shutdown_receiver.recv() => {
@@ -62,8 +77,9 @@ pub async fn run_headless_mode(mut session: SessionData) -> Result<(), Box<dyn E
 
     // Wait for workers to finish
     print_session_shutdown();
-    for handle in session.join_handles {
-        let _ = handle.await;
+    wait_for_workers(session.join_handles, &session.prover_pool).await;
+    for (node_id, activity) in &node_activity {
+        print_node_summary(*node_id, activity);
     }
     print_session_exit_success();
 
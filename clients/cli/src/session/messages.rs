@@ -1,5 +1,7 @@
 //! Unified messaging system for session operations
 
+use crate::events::NodeActivity;
+
 // ANSI Color Codes for session messages
 pub const COLOR_INFO: &str = "\x1b[1;36m"; // Bold Cyan
 pub const COLOR_SUCCESS: &str = "\x1b[1;32m"; // Bold Green
@@ -39,8 +41,31 @@ impl SessionMessage {
 }
 
 /// Print session startup message
-pub fn print_session_starting(mode: &str, node_id: u64) {
-    SessionMessage::info(format!("Starting {} mode with Node ID: {}", mode, node_id)).print();
+pub fn print_session_starting(mode: &str, node_ids: &[u64]) {
+    let node_list = node_ids
+        .iter()
+        .map(|node_id| node_id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let label = if node_ids.len() == 1 {
+        "Node ID"
+    } else {
+        "Node IDs"
+    };
+    SessionMessage::info(format!(
+        "Starting {} mode with {}: {}",
+        mode, label, node_list
+    ))
+    .print();
+}
+
+/// Print a node's totals at the end of a multi-node session
+pub fn print_node_summary(node_id: u64, activity: &NodeActivity) {
+    SessionMessage::info(format!(
+        "Node {}: {} tasks fetched, {} proofs submitted, {} failures",
+        node_id, activity.tasks_fetched, activity.proofs_submitted, activity.failures
+    ))
+    .print();
 }
 
 /// Print session shutdown message
@@ -4,5 +4,5 @@ pub mod setup;
 pub mod tui_mode;
 
 pub use headless_mode::run_headless_mode;
-pub use setup::{SessionData, setup_session};
+pub use setup::{SessionData, setup_session, wait_for_workers};
 pub use tui_mode::run_tui_mode;
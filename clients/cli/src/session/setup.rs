@@ -1,19 +1,26 @@
 //! Session setup and initialization
 
 use crate::analytics::set_wallet_address_for_reporting;
+use crate::cli_messages::print_success;
 use crate::config::Config;
+use crate::consts::cli_consts::{pipeline, prover_pool};
 use crate::environment::Environment;
 use crate::events::Event;
-use crate::orchestrator::OrchestratorClient;
-use crate::runtime::start_authenticated_worker;
-use ed25519_dalek::SigningKey;
+use crate::key_store::{KeyStore, passphrase_from_env};
+use crate::orchestrator::endpoints::EndpointPool;
+use crate::orchestrator::{Connector, Orchestrator};
+use crate::prover::ProvingContext;
+use crate::prover::pool::ProverPool;
+use crate::runtime::start_authenticated_workers;
+use futures::future::join_all;
 use std::error::Error;
+use std::path::PathBuf;
+use std::sync::Arc;
 use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
 use tokio::sync::{broadcast, mpsc};
 use tokio::task::JoinHandle;
 
 /// Session data for both TUI and headless modes
-#[derive(Debug)]
 pub struct SessionData {
     /// Event receiver for worker events
     pub event_receiver: mpsc::Receiver<Event>,
@@ -23,31 +30,66 @@ pub struct SessionData {
     pub shutdown_sender: broadcast::Sender<()>,
     /// Shutdown sender for max tasks completion
     pub max_tasks_shutdown_sender: broadcast::Sender<()>,
-    /// Node ID
-    pub node_id: u64,
-    /// Orchestrator client
-    pub orchestrator: OrchestratorClient,
+    /// Node IDs, one worker each
+    pub node_ids: Vec<u64>,
+    /// Environment the workers run against
+    pub environment: Environment,
+    /// Orchestrator endpoints being failed over between, if the environment has several
+    pub endpoints: Option<Arc<EndpointPool>>,
     /// Number of workers (for display purposes)
     pub num_workers: usize,
+    /// Prover subprocesses of the workers, stopped once they are done
+    pub prover_pool: Arc<ProverPool>,
 }
 
-/// Clamp thread count based on available system memory
-/// Returns the maximum number of threads that can be safely used given system memory
-fn clamp_threads_by_memory(requested_threads: usize) -> usize {
+/// Maximum number of threads that fit in system memory when each needs `memory_per_thread`
+/// bytes. Always at least 1.
+pub fn max_threads_by_memory(memory_per_thread: u64) -> usize {
     let mut sysinfo = System::new();
     sysinfo.refresh_memory();
 
     let total_system_memory = sysinfo.total_memory();
-    let memory_per_thread = crate::consts::cli_consts::PROJECTED_MEMORY_REQUIREMENT;
 
     // Calculate max threads based on total system memory
     // Reserve 25% of system memory for OS and other processes
     let available_memory = (total_system_memory as f64 * 0.75) as u64;
-    let max_threads_by_memory = (available_memory / memory_per_thread) as usize;
+    ((available_memory / memory_per_thread.max(1)) as usize).max(1)
+}
+
+/// Clamp thread count based on available system memory
+/// Returns the maximum number of threads that can be safely used given system memory
+fn clamp_threads_by_memory(requested_threads: usize) -> usize {
+    let memory_per_thread = crate::consts::cli_consts::PROJECTED_MEMORY_REQUIREMENT;
 
     // Return the minimum of requested threads and memory-limited threads
-    // Always allow at least 1 thread
-    requested_threads.min(max_threads_by_memory.max(1))
+    requested_threads.min(max_threads_by_memory(memory_per_thread))
+}
+
+/// Maximum number of proving threads: 75% of the CPU cores, leaving room for other processes
+pub fn max_workers() -> usize {
+    let total_cores = crate::system::num_cores();
+    ((total_cores as f64 * 0.75).ceil() as usize).max(1)
+}
+
+/// Number of proving threads for `--max-threads`, clamped to [1, 75% of num_cores], and to
+/// available memory when the thread count was set explicitly or `check_mem` is set
+pub fn resolve_num_workers(max_threads: Option<u32>, check_mem: bool) -> usize {
+    let mut num_workers: usize = max_threads.unwrap_or(1).clamp(1, max_workers() as u32) as usize;
+
+    // Check memory and clamp threads if max-threads was explicitly set OR check-memory flag is set
+    if max_threads.is_some() || check_mem {
+        let memory_clamped_workers = clamp_threads_by_memory(num_workers);
+        if memory_clamped_workers < num_workers {
+            crate::print_cmd_warn!(
+                "Memory limit",
+                "Reduced thread count from {} to {} due to insufficient memory. Each thread requires ~4GB RAM.",
+                num_workers,
+                memory_clamped_workers
+            );
+            num_workers = memory_clamped_workers;
+        }
+    }
+    num_workers
 }
 
 /// Warn the user if their available memory seems insufficient for the task(s) at hand
@@ -80,56 +122,65 @@ pub fn warn_memory_configuration(max_threads: Option<u32>) {
 /// Sets up an authenticated worker session
 ///
 /// This function handles all the common setup required for both TUI and headless modes:
-/// 1. Creates signing key for the prover
+/// 1. Loads each node's signing key, creating it on first run
 /// 2. Sets up shutdown channel
-/// 3. Starts authenticated worker
+/// 3. Starts one authenticated worker per node
 /// 4. Returns session data for mode-specific handling
 ///
 /// # Arguments
-/// * `config` - Resolved configuration with node_id and client_id
+/// * `configs` - Resolved configurations, one per node, with node_id and client_id
 /// * `env` - Environment to connect to
 /// * `max_threads` - Optional maximum number of threads for proving
 /// * `max_difficulty` - Optional override for task difficulty
+/// * `initial_difficulty` - Difficulty to start at before any task completes, from a benchmark
+/// * `spool_dir` - Directory for spooled proof submissions, if spooling is enabled
+/// * `key_store` - Persistent node signing keys
+/// * `connector` - Creates the orchestrator client
+/// * `proving` - Prover subprocesses, caches and verifier shared by the workers
 ///
 /// # Returns
 /// * `Ok(SessionData)` - Successfully set up session
 /// * `Err` - Session setup failed
 pub async fn setup_session(
-    config: Config,
+    configs: Vec<Config>,
     env: Environment,
     check_mem: bool,
     max_threads: Option<u32>,
     max_tasks: Option<u32>,
     max_difficulty: Option<crate::nexus_orchestrator::TaskDifficulty>,
+    initial_difficulty: Option<crate::nexus_orchestrator::TaskDifficulty>,
+    spool_dir: Option<PathBuf>,
+    key_store: KeyStore,
+    connector: &Connector,
+    proving: ProvingContext,
 ) -> Result<SessionData, Box<dyn Error>> {
-    let node_id = config.node_id.parse::<u64>()?;
-    let client_id = config.user_id;
-
-    // Create a signing key for the prover
-    let mut csprng = rand_core::OsRng;
-    let signing_key: SigningKey = SigningKey::generate(&mut csprng);
-
-    // Create orchestrator client
-    let orchestrator_client = OrchestratorClient::new(env.clone());
-
-    // Clamp the number of workers to [1, 75% of num_cores]. Leave room for other processes.
-    let total_cores = crate::system::num_cores();
-    let max_workers = ((total_cores as f64 * 0.75).ceil() as usize).max(1);
-    let mut num_workers: usize = max_threads.unwrap_or(1).clamp(1, max_workers as u32) as usize;
-
-    // Check memory and clamp threads if max-threads was explicitly set OR check-memory flag is set
-    if max_threads.is_some() || check_mem {
-        let memory_clamped_workers = clamp_threads_by_memory(num_workers);
-        if memory_clamped_workers < num_workers {
-            crate::print_cmd_warn!(
-                "Memory limit",
-                "Reduced thread count from {} to {} due to insufficient memory. Each thread requires ~4GB RAM.",
-                num_workers,
-                memory_clamped_workers
+    let primary = configs.first().ok_or("No node to run")?.clone();
+    let client_id = primary.user_id;
+
+    // Load each node's signing key so its identity is stable across restarts
+    let passphrase = passphrase_from_env();
+    let mut nodes = Vec::with_capacity(configs.len());
+    for config in &configs {
+        let node_id = config.node_id.parse::<u64>()?;
+        let (signing_key, created) = key_store.load_or_create(node_id, passphrase.as_deref())?;
+        if created {
+            print_success(
+                &format!("Created signing key for node {}", node_id),
+                &format!(
+                    "Public key: {}",
+                    hex::encode(signing_key.verifying_key().to_bytes())
+                ),
             );
-            num_workers = memory_clamped_workers;
         }
+        nodes.push((node_id, signing_key));
     }
+    let node_ids: Vec<u64> = nodes.iter().map(|(node_id, _)| *node_id).collect();
+
+    // Create orchestrator client
+    let orchestrator_client = connector.connect(env.clone())?;
+
+    // Clamp the number of workers to the cores and memory available
+    let num_workers = resolve_num_workers(max_threads, check_mem);
 
     // Additional memory warning if explicitly requested
     if check_mem {
@@ -140,29 +191,61 @@ pub async fn setup_session(
     let (shutdown_sender, _) = broadcast::channel(1);
 
     // Set wallet for reporting
-    set_wallet_address_for_reporting(config.wallet_address.clone());
+    set_wallet_address_for_reporting(primary.wallet_address.clone());
 
-    // Start authenticated worker (only mode we support now)
-    let (event_receiver, join_handles, max_tasks_shutdown_sender) = start_authenticated_worker(
-        node_id,
-        signing_key,
+    // Start authenticated workers (only mode we support now)
+    let prover_pool = proving.pool.clone();
+    let (event_receiver, join_handles, max_tasks_shutdown_sender) = start_authenticated_workers(
+        nodes,
         orchestrator_client.clone(),
         shutdown_sender.subscribe(),
-        env,
+        env.clone(),
         client_id,
         max_tasks,
         max_difficulty,
+        initial_difficulty,
         num_workers,
+        spool_dir,
+        primary.retry_policies,
+        primary.circuit_breaker,
+        proving,
     )
     .await;
 
+    // Keep track of every endpoint's health, so that failover picks a working one. The probes
+    // hold no work of their own, so they are not waited for on exit.
+    let endpoints = orchestrator_client
+        .endpoints()
+        .filter(|pool| pool.endpoint_count() > 1);
+    if let Some(pool) = &endpoints {
+        pool.spawn_probes(shutdown_sender.subscribe());
+    }
+
     Ok(SessionData {
         event_receiver,
         join_handles,
         shutdown_sender,
         max_tasks_shutdown_sender,
-        node_id,
-        orchestrator: orchestrator_client,
+        node_ids,
+        environment: env,
+        endpoints,
         num_workers,
+        prover_pool,
     })
 }
+
+/// Waits for the workers to wind down after shutdown, then stops the idle prover subprocesses.
+/// Workers still running after the shutdown timeout are aborted, which kills the subprocesses
+/// of the proofs they were generating, so exit takes a bounded time.
+pub async fn wait_for_workers(join_handles: Vec<JoinHandle<()>>, pool: &ProverPool) {
+    let abort_handles: Vec<_> = join_handles.iter().map(JoinHandle::abort_handle).collect();
+    if tokio::time::timeout(pipeline::shutdown_timeout(), join_all(join_handles))
+        .await
+        .is_err()
+    {
+        for handle in abort_handles {
+            handle.abort();
+        }
+    }
+    pool.shutdown(prover_pool::stop_grace()).await;
+}
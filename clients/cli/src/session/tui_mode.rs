@@ -3,8 +3,8 @@
 use super::{
     SessionData,
     messages::{print_session_exit_success, print_session_shutdown, print_session_starting},
+    wait_for_workers,
 };
-use crate::orchestrator::Orchestrator;
 use crate::ui::{self, UIConfig};
 use crate::version::checker::check_for_new_version;
 use crossterm::{
@@ -34,7 +34,7 @@ pub async fn run_tui_mode(
     with_background: bool,
 ) -> Result<(), Box<dyn Error>> {
     // Print session start message
-    print_session_starting("TUI", session.node_id);
+    print_session_starting("TUI", &session.node_ids);
 
     // Check for new version and get version info
     let current_version = env!("CARGO_PKG_VERSION");
@@ -65,11 +65,13 @@ pub async fn run_tui_mode(
         session.num_workers,
         version_update_available,
         latest_version,
+        session.node_ids.clone(),
+        session.endpoints.clone(),
     );
 
     let app = ui::App::new(
-        Some(session.node_id),
-        session.orchestrator.environment().clone(),
+        session.node_ids.first().copied(),
+        session.environment.clone(),
         session.event_receiver,
         session.shutdown_sender.clone(),
         session.max_tasks_shutdown_sender.subscribe(),
@@ -92,9 +94,7 @@ pub async fn run_tui_mode(
 
     // Wait for workers to finish
     print_session_shutdown();
-    for handle in session.join_handles {
-        let _ = handle.await;
-    }
+    wait_for_workers(session.join_handles, &session.prover_pool).await;
     print_session_exit_success();
 
     Ok(())
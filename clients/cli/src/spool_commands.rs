@@ -0,0 +1,134 @@
+//! `spool` subcommands for inspecting and draining the proof submission spool.
+
+use crate::cli_messages::{print_error, print_info, print_success};
+use crate::environment::Environment;
+use crate::key_store::{KeyStore, passphrase_from_env};
+use crate::network::Spool;
+use crate::network::error_handler::ErrorHandler;
+use crate::orchestrator::{Connector, Orchestrator};
+use std::error::Error;
+
+/// Lists spooled submissions.
+pub fn list_spool(spool: &Spool) -> Result<(), Box<dyn Error>> {
+    let (entries, unreadable) = spool.load()?;
+    if entries.is_empty() && unreadable.is_empty() {
+        print_info("Proof spool is empty", &spool.dir().display().to_string());
+        return Ok(());
+    }
+
+    print_info(
+        "Spooled proofs",
+        &format!("{} in {}", entries.len(), spool.dir().display()),
+    );
+    for entry in &entries {
+        println!(
+            "  {}  node {}  {} bytes  age {}m{}  {}",
+            entry.task_id,
+            entry.node_id,
+            entry.payload_size(),
+            entry.age_secs() / 60,
+            if entry.is_expired() { " (expired)" } else { "" },
+            entry.orchestrator_url
+        );
+    }
+    for path in &unreadable {
+        println!("  unreadable: {}", path.display());
+    }
+    Ok(())
+}
+
+/// Submits every unexpired spooled proof once, removing entries the orchestrator accepts or
+/// rejects outright. Expired entries are dropped. Each proof is signed with its node's key from
+/// `key_store`.
+pub async fn flush_spool(
+    spool: &Spool,
+    key_store: &KeyStore,
+    connector: &Connector,
+) -> Result<(), Box<dyn Error>> {
+    let (entries, _) = spool.load()?;
+    let passphrase = passphrase_from_env();
+    let error_handler = ErrorHandler::new();
+    let mut submitted = 0;
+    let mut remaining = 0;
+
+    for entry in entries {
+        if entry.is_expired() {
+            spool.remove(&entry.task_id)?;
+            print_info("Dropped expired proof", &entry.task_id);
+            continue;
+        }
+
+        let signing_key = match key_store.read(entry.node_id) {
+            Ok(Some(key_file)) => key_file
+                .signing_key(passphrase.as_deref())
+                .map_err(|e| e.to_string()),
+            Ok(None) => Err(format!("No signing key for node {}", entry.node_id)),
+            Err(e) => Err(e.to_string()),
+        };
+        let signing_key = match signing_key {
+            Ok(signing_key) => signing_key,
+            Err(e) => {
+                remaining += 1;
+                print_error(
+                    &format!("Cannot sign proof for task {}", entry.task_id),
+                    Some(&e),
+                );
+                continue;
+            }
+        };
+
+        let orchestrator =
+            connector.connect(Environment::from_orchestrator_url(&entry.orchestrator_url))?;
+        let submission = entry.submission();
+        match orchestrator
+            .submit_proof(
+                &submission.task_id,
+                &submission.proof_hash,
+                submission.proof_bytes.clone(),
+                submission.proofs_bytes.clone(),
+                signing_key,
+                1,
+                submission.task_type,
+                &submission.individual_proof_hashes,
+            )
+            .await
+        {
+            Ok(()) => {
+                spool.remove(&entry.task_id)?;
+                submitted += 1;
+                print_success("Submitted proof", &entry.task_id);
+            }
+            Err(e) if error_handler.is_permanent_rejection(&e) => {
+                spool.remove(&entry.task_id)?;
+                let details = e.to_pretty().unwrap_or_else(|| e.to_string());
+                print_error(
+                    &format!("Proof for task {} rejected, removed", entry.task_id),
+                    Some(&details),
+                );
+            }
+            Err(e) => {
+                remaining += 1;
+                print_error(
+                    &format!("Failed to submit proof for task {}", entry.task_id),
+                    Some(&e.to_string()),
+                );
+            }
+        }
+    }
+
+    print_info(
+        "Spool flushed",
+        &format!("{} submitted, {} still spooled", submitted, remaining),
+    );
+    Ok(())
+}
+
+/// Deletes every spooled proof without submitting it.
+pub fn purge_spool(spool: &Spool) -> Result<(), Box<dyn Error>> {
+    let removed = spool.purge()?;
+    print_success(
+        "Proof spool purged",
+        &format!("{} entries removed from {}", removed, spool.dir().display()),
+    );
+    Ok(())
+}
@@ -4,11 +4,13 @@
 
 use crate::environment::Environment;
 use crate::events::Event as WorkerEvent;
+use crate::orchestrator::endpoints::EndpointPool;
 use crate::ui::dashboard::{DashboardState, render_dashboard};
 use crate::ui::login::render_login;
 use crate::ui::splash::render_splash;
 use crossterm::event::{self, Event, KeyCode};
 use ratatui::{Frame, Terminal, backend::Backend};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{broadcast, mpsc};
 
@@ -19,6 +21,8 @@ pub struct UIConfig {
     pub num_threads: usize,
     pub update_available: bool,
     pub latest_version: Option<String>,
+    pub node_ids: Vec<u64>,
+    pub endpoints: Option<Arc<EndpointPool>>,
 }
 
 impl UIConfig {
@@ -27,12 +31,16 @@ impl UIConfig {
         num_threads: usize,
         update_available: bool,
         latest_version: Option<String>,
+        node_ids: Vec<u64>,
+        endpoints: Option<Arc<EndpointPool>>,
     ) -> Self {
         Self {
             with_background_color,
             num_threads,
             update_available,
             latest_version,
+            node_ids,
+            endpoints,
         }
     }
 }
@@ -84,6 +92,12 @@ pub struct App {
 
     /// Latest version available, if any.
     latest_version: Option<String>,
+
+    /// All node IDs running in this session.
+    node_ids: Vec<u64>,
+
+    /// Orchestrator endpoints being failed over between, if any.
+    endpoints: Option<Arc<EndpointPool>>,
 }
 
 impl App {
@@ -108,6 +122,8 @@ impl App {
             num_threads: ui_config.num_threads,
             version_update_available: ui_config.update_available,
             latest_version: ui_config.latest_version,
+            node_ids: ui_config.node_ids,
+            endpoints: ui_config.endpoints,
         }
     }
 
@@ -120,6 +136,8 @@ impl App {
             self.num_threads,
             self.version_update_available,
             self.latest_version.clone(),
+            self.node_ids.clone(),
+            self.endpoints.clone(),
         );
         let state = DashboardState::new(
             node_id,
@@ -172,6 +190,8 @@ pub async fn run<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> std::i
                     app.num_threads,
                     app.version_update_available,
                     app.latest_version.clone(),
+                    app.node_ids.clone(),
+                    app.endpoints.clone(),
                 );
                 app.current_screen = Screen::Dashboard(Box::new(DashboardState::new(
                     app.node_id,
@@ -207,6 +227,8 @@ pub async fn run<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> std::i
                                 app.num_threads,
                                 app.version_update_available,
                                 app.latest_version.clone(),
+                                app.node_ids.clone(),
+                                app.endpoints.clone(),
                             );
                             app.current_screen = Screen::Dashboard(Box::new(DashboardState::new(
                                 app.node_id,
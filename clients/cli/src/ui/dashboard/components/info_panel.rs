@@ -3,6 +3,7 @@
 //! Renders system information panel
 
 use crate::environment::Environment;
+use crate::network::BreakerState;
 
 use super::super::state::DashboardState;
 use ratatui::Frame;
@@ -15,28 +16,107 @@ pub fn render_info_panel(f: &mut Frame, area: ratatui::layout::Rect, state: &Das
     let mut info_lines = Vec::new();
 
     // Node information with enhanced formatting
-    let node_text = if let Some(id) = state.node_id {
-        format!("Node: {}", id)
+    if state.node_activity.is_empty() {
+        let node_text = if let Some(id) = state.node_id {
+            format!("Node: {}", id)
+        } else {
+            "Node: Disconnected".to_string()
+        };
+        info_lines.push(Line::from(vec![Span::styled(
+            node_text,
+            Style::default().fg(Color::LightBlue),
+        )]));
     } else {
-        "Node: Disconnected".to_string()
-    };
-    info_lines.push(Line::from(vec![Span::styled(
-        node_text,
-        Style::default().fg(Color::LightBlue),
-    )]));
+        // Per-node breakdown: submitted / fetched, plus failures
+        info_lines.push(Line::from(vec![Span::styled(
+            format!("Nodes: {}", state.node_activity.len()),
+            Style::default().fg(Color::LightBlue),
+        )]));
+        for (node_id, activity) in &state.node_activity {
+            info_lines.push(Line::from(vec![
+                Span::styled(
+                    format!(" {}: ", node_id),
+                    Style::default().fg(Color::LightBlue),
+                ),
+                Span::styled(
+                    format!(
+                        "{}/{} proved",
+                        activity.proofs_submitted, activity.tasks_fetched
+                    ),
+                    Style::default().fg(Color::LightGreen),
+                ),
+                Span::styled(
+                    format!(", {} failed", activity.failures),
+                    Style::default().fg(if activity.failures > 0 {
+                        Color::LightRed
+                    } else {
+                        Color::Gray
+                    }),
+                ),
+            ]));
+        }
+    }
 
     // Environment with color coding
     let env_color = match state.environment {
         Environment::Production => Color::Green,
         Environment::Custom {
             orchestrator_url: _,
-        } => Color::Yellow,
+        }
+        | Environment::Failover { .. } => Color::Yellow,
     };
     info_lines.push(Line::from(vec![Span::styled(
         format!("Env: {}", state.environment),
         Style::default().fg(env_color),
     )]));
 
+    // Active orchestrator endpoint, when failing over between several
+    if let Some(endpoints) = &state.endpoints {
+        let all = endpoints.endpoints();
+        let active = endpoints.active();
+        let healthy = all.iter().filter(|endpoint| endpoint.healthy).count();
+        let latency = active
+            .latency
+            .map(|latency| format!(", {} ms", latency.as_millis()))
+            .unwrap_or_default();
+        info_lines.push(Line::from(vec![Span::styled(
+            format!(
+                "Endpoint: {} ({}/{} up{})",
+                active.url,
+                healthy,
+                all.len(),
+                latency
+            ),
+            Style::default().fg(if active.healthy {
+                Color::Green
+            } else {
+                Color::LightRed
+            }),
+        )]));
+    }
+
+    // Orchestrator availability, while the circuit breaker holds requests back
+    match state.breaker_state {
+        BreakerState::Open { next_probe } => {
+            info_lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "Orchestrator unavailable, next probe in {}s",
+                    next_probe
+                        .saturating_duration_since(std::time::Instant::now())
+                        .as_secs()
+                ),
+                Style::default().fg(Color::LightRed),
+            )]));
+        }
+        BreakerState::HalfOpen => {
+            info_lines.push(Line::from(vec![Span::styled(
+                "Orchestrator unavailable, probing",
+                Style::default().fg(Color::Yellow),
+            )]));
+        }
+        BreakerState::Closed => {}
+    }
+
     // Version info
     let version = env!("CARGO_PKG_VERSION");
     info_lines.push(Line::from(vec![Span::styled(
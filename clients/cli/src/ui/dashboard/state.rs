@@ -4,11 +4,14 @@
 
 use crate::consts::cli_consts::MAX_ACTIVITY_LOGS;
 use crate::environment::Environment;
-use crate::events::{Event as WorkerEvent, ProverState};
+use crate::events::{Event as WorkerEvent, NodeActivity, ProverState};
+use crate::network::BreakerState;
+use crate::orchestrator::endpoints::EndpointPool;
 use crate::ui::app::UIConfig;
 use crate::ui::metrics::{SystemMetrics, TaskFetchInfo, ZkVMMetrics};
 
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
 use std::time::Instant;
 use sysinfo::System;
 
@@ -25,8 +28,14 @@ pub enum FetchingState {
 pub struct DashboardState {
     /// Unique identifier for the node.
     pub node_id: Option<u64>,
+    /// Per-node progress, populated only when several nodes run in this session
+    pub node_activity: BTreeMap<u64, NodeActivity>,
     /// The environment in which the application is running.
     pub environment: Environment,
+    /// Orchestrator endpoints being failed over between, if the environment has several
+    pub endpoints: Option<Arc<EndpointPool>>,
+    /// Orchestrator circuit breaker state, as last reported
+    pub breaker_state: BreakerState,
     /// The start time of the application, used for computing uptime.
     pub start_time: Instant,
     /// Last task fetched ID
@@ -79,9 +88,23 @@ impl DashboardState {
         start_time: Instant,
         ui_config: UIConfig,
     ) -> Self {
+        // Track per-node progress only when there is more than one node to tell apart
+        let node_activity = if ui_config.node_ids.len() > 1 {
+            ui_config
+                .node_ids
+                .iter()
+                .map(|node_id| (*node_id, NodeActivity::default()))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+
         Self {
             node_id,
+            node_activity,
             environment,
+            endpoints: ui_config.endpoints,
+            breaker_state: BreakerState::Closed,
             start_time,
             last_task: None,
             current_task: None,
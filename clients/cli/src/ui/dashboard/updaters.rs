@@ -41,6 +41,10 @@ impl DashboardState {
 
     /// Process a single event and update relevant state
     fn process_event(&mut self, event: &WorkerEvent) {
+        if let Some(node_id) = event.node_id {
+            self.node_activity.entry(node_id).or_default().record(event);
+        }
+
         match event.worker {
             Worker::TaskFetcher => self.handle_task_fetcher_event(event),
             Worker::Prover(_) => self.handle_prover_event(event),
@@ -53,6 +57,10 @@ impl DashboardState {
                 self.set_current_prover_state(state);
             }
         }
+
+        if let Some(state) = event.breaker_state {
+            self.breaker_state = state;
+        }
     }
 
     /// Handle TaskFetcher events
@@ -67,11 +75,16 @@ impl DashboardState {
 
                 // Count this as a task fetch if we haven't seen this task before
                 self.zkvm_metrics.tasks_fetched += 1;
-                // Track Step 2 start (proving begins at the end of Step 1)
-                self.step2_start_time = Some(Instant::now());
             }
         }
 
+        // Track Step 2 start. Tasks are prefetched, so proving may begin well after Step 1.
+        if event.event_type == EventType::StateChange
+            && event.msg.contains("Step 2 of 4: Proving task")
+        {
+            self.step2_start_time = Some(Instant::now());
+        }
+
         // Handle fetching state changes
         if Self::is_completion_event(event) {
             self.set_fetching_state(FetchingState::Idle);
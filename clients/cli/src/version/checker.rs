@@ -74,7 +74,8 @@
 //! 4. **Test timing**: Use configurable intervals for faster tests
 //! 5. **Clean shutdown**: Always test graceful shutdown scenarios
 
-use reqwest::{Client, ClientBuilder};
+use crate::network::http;
+use reqwest::Client;
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
@@ -154,7 +155,7 @@ pub struct VersionChecker {
 
 impl VersionChecker {
     pub fn new(current_version: String) -> Self {
-        let client = ClientBuilder::new()
+        let client = http::client_builder()
             .timeout(Duration::from_secs(10))
             .user_agent(format!("nexus-cli/{}", current_version))
             .build()
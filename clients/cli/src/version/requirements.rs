@@ -1,3 +1,4 @@
+use crate::network::http;
 use reqwest::Client;
 use semver::Version;
 use serde::{Deserialize, Serialize};
@@ -59,7 +60,7 @@ impl VersionRequirements {
     /// Fetch version requirements from remote config with multiple fallbacks
     /// Priority: Firebase Hosting -> Cloud Function Cache -> GitHub
     pub async fn fetch() -> Result<Self, VersionRequirementsError> {
-        let client = Client::builder()
+        let client = http::client_builder()
             .timeout(CONFIG_TIMEOUT)
             .user_agent("nexus-cli/version-checker")
             .build()
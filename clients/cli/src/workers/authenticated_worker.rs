@@ -1,15 +1,28 @@
-//! Single authenticated worker that orchestrates fetch→prove→submit
+//! Single authenticated worker that pipelines fetch→prove→submit
+//!
+//! The three stages run as separate tasks connected by bounded queues: the next task is
+//! fetched while the current one is proving, and finished proofs are submitted in the
+//! background so proving never waits on the network.
 
 use super::core::{EventSender, WorkerConfig};
-use super::fetcher::TaskFetcher;
+use super::fetcher::{FetchError, TaskFetcher};
 use super::prover::TaskProver;
-use super::submitter::ProofSubmitter;
-use crate::events::{Event, ProverState};
-use crate::orchestrator::OrchestratorClient;
+use super::submitter::{ProofSubmitter, SubmitError};
+use crate::consts::cli_consts::pipeline;
+use crate::events::{Event, EventType, ProverState};
+use crate::logging::LogLevel;
+use crate::network::BreakerState;
+use crate::nexus_orchestrator::TaskDifficulty;
+use crate::orchestrator::Orchestrator;
+use crate::orchestrator::error::{OrchestratorError, Remedy};
+use crate::prover::ProverResult;
+use crate::prover::checkpoint::TaskCheckpoints;
+use crate::task::Task;
 
 use ed25519_dalek::SigningKey;
-use std::time::Duration;
-use tokio::sync::{broadcast, mpsc};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Semaphore, broadcast, mpsc, oneshot, watch};
 use tokio::task::JoinHandle;
 
 /// Single authenticated worker that handles the complete task lifecycle
@@ -18,23 +31,29 @@ pub struct AuthenticatedWorker {
     prover: TaskProver,
     submitter: ProofSubmitter,
     event_sender: EventSender,
+    breaker_states: watch::Receiver<BreakerState>,
+    checkpoints: Arc<TaskCheckpoints>,
     max_tasks: Option<u32>,
-    tasks_completed: u32,
     shutdown_sender: broadcast::Sender<()>,
 }
 
+/// A proven task waiting in the submission queue
+struct ProvenTask {
+    task: Task,
+    proof_result: ProverResult,
+    proving_started: Instant,
+}
+
 impl AuthenticatedWorker {
     pub fn new(
         node_id: u64,
         signing_key: SigningKey,
-        orchestrator: OrchestratorClient,
+        orchestrator: Arc<dyn Orchestrator>,
         config: WorkerConfig,
-        event_sender: mpsc::Sender<Event>,
+        event_sender_helper: EventSender,
         max_tasks: Option<u32>,
         shutdown_sender: broadcast::Sender<()>,
     ) -> Self {
-        let event_sender_helper = EventSender::new(event_sender);
-
         // Create the 3 specialized components
         let fetcher = TaskFetcher::new(
             node_id,
@@ -45,8 +64,11 @@ impl AuthenticatedWorker {
         );
 
         let prover = TaskProver::new(event_sender_helper.clone(), config.clone());
+        let breaker_states = config.circuit_breaker.subscribe();
+        let checkpoints = Arc::clone(&config.proving.checkpoints);
 
         let submitter = ProofSubmitter::new(
+            node_id,
             signing_key,
             Box::new(orchestrator),
             event_sender_helper.clone(),
@@ -58,16 +80,15 @@ impl AuthenticatedWorker {
             prover,
             submitter,
             event_sender: event_sender_helper,
+            breaker_states,
+            checkpoints,
             max_tasks,
-            tasks_completed: 0,
             shutdown_sender,
         }
     }
 
     /// Start the worker
-    pub async fn run(mut self, mut shutdown: broadcast::Receiver<()>) -> Vec<JoinHandle<()>> {
-        let mut join_handles = Vec::new();
-
+    pub async fn run(self, shutdown: broadcast::Receiver<()>) -> Vec<JoinHandle<()>> {
         // Send initial state
         self.event_sender
             .send_event(Event::state_change(
@@ -76,44 +97,189 @@ impl AuthenticatedWorker {
             ))
             .await;
 
-        // Main work loop
-        let worker_handle = tokio::spawn(async move {
-            loop {
-                tokio::select! {
-                    _ = shutdown.recv() => break,
-                    should_exit = self.work_cycle() => {
-                        if should_exit {
-                            break;
-                        }
-                        // Natural rate limiting through work cycle
-                        tokio::time::sleep(Duration::from_millis(100)).await;
+        let (task_sender, task_receiver) = mpsc::channel(pipeline::TASK_QUEUE_DEPTH);
+        let (proven_sender, proven_receiver) = mpsc::channel(pipeline::SUBMISSION_QUEUE_DEPTH);
+        let (success_sender, success_receiver) = mpsc::channel(pipeline::SUBMISSION_QUEUE_DEPTH);
+        let (replay_done_sender, replay_done_receiver) = oneshot::channel();
+
+        // Tasks that may still be fetched before reaching max_tasks. A permit is consumed per
+        // fetched task and handed back if that task fails to prove or submit.
+        let task_budget = self
+            .max_tasks
+            .map(|max| Arc::new(Semaphore::new(max as usize)));
+
+        let fetch_stage = FetchStage {
+            fetcher: self.fetcher,
+            event_sender: self.event_sender.clone(),
+            task_sender,
+            success_receiver,
+            replay_done: Some(replay_done_receiver),
+            task_budget: task_budget.clone(),
+        };
+        let prove_stage = ProveStage {
+            prover: self.prover,
+            event_sender: self.event_sender.clone(),
+            task_receiver,
+            proven_sender,
+            task_budget: task_budget.clone(),
+        };
+        let node_done = self.shutdown_sender.subscribe();
+        let submit_stage = SubmitStage {
+            submitter: self.submitter,
+            event_sender: self.event_sender,
+            proven_receiver,
+            success_sender,
+            replay_done: Some(replay_done_sender),
+            breaker_states: self.breaker_states,
+            checkpoints: self.checkpoints,
+            task_budget,
+            max_tasks: self.max_tasks,
+            tasks_completed: 0,
+            shutdown_sender: self.shutdown_sender,
+        };
+
+        vec![
+            tokio::spawn(fetch_stage.run(shutdown.resubscribe(), node_done)),
+            tokio::spawn(prove_stage.run(shutdown.resubscribe())),
+            tokio::spawn(submit_stage.run(shutdown)),
+        ]
+    }
+}
+
+/// Why the node has to stop after `error`, if it does
+fn stop_reason(error: &OrchestratorError) -> Option<String> {
+    match error.remedy() {
+        Remedy::ReRegister => Some(format!(
+            "Stopping node: {}. Register it again with: nexus-cli register-node",
+            error
+        )),
+        Remedy::Stop => Some(format!("Stopping node: {}", error)),
+        Remedy::BackOff | Remedy::DropTask => None,
+    }
+}
+
+/// Fetches tasks ahead of the prover
+struct FetchStage {
+    fetcher: TaskFetcher,
+    event_sender: EventSender,
+    task_sender: mpsc::Sender<Task>,
+    /// Difficulty and duration of completed tasks, for adaptive difficulty
+    success_receiver: mpsc::Receiver<(TaskDifficulty, u64)>,
+    /// Signalled once spooled proofs from earlier runs have been replayed
+    replay_done: Option<oneshot::Receiver<()>>,
+    task_budget: Option<Arc<Semaphore>>,
+}
+
+impl FetchStage {
+    /// Runs until shutdown, or until `node_done` reports that the submit stage is done with
+    /// the node and no more tasks are needed.
+    async fn run(
+        mut self,
+        mut shutdown: broadcast::Receiver<()>,
+        mut node_done: broadcast::Receiver<()>,
+    ) {
+        loop {
+            tokio::select! {
+                _ = shutdown.recv() => break,
+                _ = node_done.recv() => break,
+                keep_going = self.fetch_next() => {
+                    if !keep_going {
+                        break;
                     }
                 }
             }
-        });
-        join_handles.push(worker_handle);
+        }
+    }
+
+    /// Fetch one task into the queue.
+    /// Returns false once no more tasks are needed, the prover has stopped, or the orchestrator
+    /// no longer accepts the node.
+    async fn fetch_next(&mut self) -> bool {
+        // Replay spooled proofs before fetching new tasks
+        if let Some(replay_done) = self.replay_done.take() {
+            let _ = replay_done.await;
+        }
+
+        if let Some(budget) = &self.task_budget {
+            match budget.acquire().await {
+                Ok(permit) => permit.forget(),
+                // Closed by the submit stage once max_tasks is reached
+                Err(_) => return false,
+            }
+        }
+
+        // Wait for room in the queue so that at most TASK_QUEUE_DEPTH tasks are prefetched
+        let Ok(slot) = self.task_sender.reserve().await else {
+            return false;
+        };
 
-        join_handles
+        // Apply completions reported since the last fetch before choosing a difficulty
+        while let Ok((difficulty, duration_secs)) = self.success_receiver.try_recv() {
+            self.fetcher.record_success(difficulty, duration_secs);
+        }
+
+        match self.fetcher.fetch_task().await {
+            Ok(task) => slot.send(task),
+            Err(FetchError::Network(e)) => {
+                // Error already logged in fetcher. Wait before retrying, until the next probe
+                // if the orchestrator is unavailable.
+                if let Some(budget) = &self.task_budget {
+                    budget.add_permits(1);
+                }
+                if let Some(reason) = stop_reason(&e) {
+                    // Closing the task queue winds down the prove and submit stages
+                    self.event_sender
+                        .send_task_event(reason, EventType::Error, LogLevel::Error)
+                        .await;
+                    return false;
+                }
+                let wait = self
+                    .fetcher
+                    .unavailable_for()
+                    .unwrap_or(Duration::from_secs(1));
+                tokio::time::sleep(wait).await;
+            }
+        }
+        true
     }
+}
 
-    /// Complete work cycle: fetch→prove→submit
-    /// Returns true if the worker should exit (max tasks reached)
-    async fn work_cycle(&mut self) -> bool {
-        // Step 1: Fetch task
-        let task = match self.fetcher.fetch_task().await {
-            Ok(task) => task,
-            Err(_) => {
-                // Error already logged in fetcher, wait before retry
-                tokio::time::sleep(Duration::from_secs(1)).await;
-                return false; // Don't exit on fetch error, just retry
+/// Proves queued tasks and hands the results to the submit stage
+struct ProveStage {
+    prover: TaskProver,
+    event_sender: EventSender,
+    task_receiver: mpsc::Receiver<Task>,
+    proven_sender: mpsc::Sender<ProvenTask>,
+    task_budget: Option<Arc<Semaphore>>,
+}
+
+impl ProveStage {
+    async fn run(mut self, mut shutdown: broadcast::Receiver<()>) {
+        loop {
+            tokio::select! {
+                _ = shutdown.recv() => break,
+                keep_going = self.prove_next() => {
+                    if !keep_going {
+                        break;
+                    }
+                }
             }
+        }
+    }
+
+    /// Prove the next queued task.
+    /// Returns false once the fetch or submit stage has stopped.
+    async fn prove_next(&mut self) -> bool {
+        let Some(task) = self.task_receiver.recv().await else {
+            return false;
         };
 
-        // Time starts from successfully obtaining the task
-        let start_time = std::time::Instant::now();
+        // Wait for room in the submission queue so unsubmitted proofs can't pile up
+        let Ok(slot) = self.proven_sender.reserve().await else {
+            return false;
+        };
 
-        // Step 2: Prove task
-        // Send state change to Proving
+        let proving_started = Instant::now();
         self.event_sender
             .send_event(Event::state_change(
                 ProverState::Proving,
@@ -121,9 +287,27 @@ impl AuthenticatedWorker {
             ))
             .await;
 
-        let proof_result = match self.prover.prove_task(&task).await {
-            Ok(proof_result) => proof_result,
+        match self.prover.prove_task(&task).await {
+            Ok(proof_result) => {
+                self.event_sender
+                    .send_event(Event::state_change(
+                        ProverState::Waiting,
+                        format!(
+                            "Proof for task {} queued for submission, ready for next task",
+                            task.task_id
+                        ),
+                    ))
+                    .await;
+                slot.send(ProvenTask {
+                    task,
+                    proof_result,
+                    proving_started,
+                });
+            }
             Err(_) => {
+                if let Some(budget) = &self.task_budget {
+                    budget.add_permits(1);
+                }
                 // Send state change back to Waiting on proof failure
                 self.event_sender
                     .send_event(Event::state_change(
@@ -131,66 +315,142 @@ impl AuthenticatedWorker {
                         "Proof generation failed, ready for next task".to_string(),
                     ))
                     .await;
-                return false; // Don't exit on proof error, just retry
             }
-        };
+        }
+        true
+    }
+}
+
+/// Submits finished proofs and enforces max_tasks
+struct SubmitStage {
+    submitter: ProofSubmitter,
+    event_sender: EventSender,
+    proven_receiver: mpsc::Receiver<ProvenTask>,
+    success_sender: mpsc::Sender<(TaskDifficulty, u64)>,
+    replay_done: Option<oneshot::Sender<()>>,
+    /// Circuit breaker transitions. Submissions made while it was open wait in the spool, so
+    /// the spool is replayed each time it closes again.
+    breaker_states: watch::Receiver<BreakerState>,
+    /// Checkpoints of tasks in flight, removed once their proof is accepted
+    checkpoints: Arc<TaskCheckpoints>,
+    task_budget: Option<Arc<Semaphore>>,
+    max_tasks: Option<u32>,
+    tasks_completed: u32,
+    shutdown_sender: broadcast::Sender<()>,
+}
 
-        // Step 3: Submit proof
-        let submission_result = self.submitter.submit_proof(&task, &proof_result).await;
+impl SubmitStage {
+    async fn run(mut self, mut shutdown: broadcast::Receiver<()>) {
+        loop {
+            tokio::select! {
+                _ = shutdown.recv() => break,
+                keep_going = self.submit_next() => {
+                    if !keep_going {
+                        break;
+                    }
+                }
+            }
+        }
+    }
 
-        // Only increment task counter on successful submission
-        if submission_result.is_ok() {
-            self.tasks_completed += 1;
-
-            // Update success tracking for difficulty promotion
-            let duration_secs = start_time.elapsed().as_secs();
-            self.fetcher.update_success_tracking(duration_secs);
-
-            // Send information about completing the task
-            self.event_sender
-                .send_event(Event::state_change(
-                    ProverState::Waiting,
-                    format!(
-                        "{} completed, Task size: {}, Duration: {}s, Difficulty: {}",
-                        task.task_id,
-                        task.public_inputs_list.len(),
-                        self.fetcher.last_success_duration_secs.unwrap_or(0),
-                        self.fetcher
-                            .last_success_difficulty
-                            .map(|difficulty| difficulty.as_str_name())
-                            .unwrap_or("Unknown")
-                    ),
-                ))
-                .await;
-            // Check if we've reached the maximum number of tasks
-            if let Some(max) = self.max_tasks {
-                if self.tasks_completed >= max {
-                    // Give a brief moment for the "Step 4 of 4" message to be processed
-                    // before triggering shutdown
-                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    /// Submit the next queued proof.
+    /// Returns false once max_tasks is reached, the prove stage has stopped, or the
+    /// orchestrator no longer accepts the node. The node is reported done in each case.
+    async fn submit_next(&mut self) -> bool {
+        if let Some(replay_done) = self.replay_done.take() {
+            self.submitter.replay_spool().await;
+            let _ = replay_done.send(());
+        }
 
+        let proven = tokio::select! {
+            proven = self.proven_receiver.recv() => proven,
+            Ok(()) = self.breaker_states.changed() => {
+                if *self.breaker_states.borrow_and_update() == BreakerState::Closed {
+                    self.submitter.replay_spool().await;
+                }
+                return true;
+            }
+        };
+        let Some(ProvenTask {
+            task,
+            proof_result,
+            proving_started,
+        }) = proven
+        else {
+            // The fetch stage stopped and every proof in flight has been submitted
+            let _ = self.shutdown_sender.send(());
+            return false;
+        };
+
+        if let Err(e) = self.submitter.submit_proof(&task, &proof_result).await {
+            if let Some(budget) = &self.task_budget {
+                budget.add_permits(1);
+            }
+            if let SubmitError::Network(e) = &e {
+                if let Some(reason) = stop_reason(e) {
+                    // Dropping the submission queue winds down the prove and fetch stages
                     self.event_sender
-                        .send_event(Event::state_change(
-                            ProverState::Waiting,
-                            format!("Completed {} tasks, shutting down", self.tasks_completed),
-                        ))
+                        .send_proof_event(reason, EventType::Error, LogLevel::Error)
                         .await;
-
-                    // Send shutdown signal to trigger application exit
                     let _ = self.shutdown_sender.send(());
-                    return true; // Signal to exit the worker loop
+                    return false;
                 }
             }
+            return true;
         }
 
-        // Send state change back to Waiting at the end of the work cycle
+        // Only increment task counter on successful submission
+        self.tasks_completed += 1;
+        let _ = self.checkpoints.remove(&task.task_id);
+
+        // Report success for difficulty promotion. Time is measured from the start of proving,
+        // so time spent waiting in the prefetch queue doesn't count against the task.
+        let duration_secs = proving_started.elapsed().as_secs();
+        let _ = self
+            .success_sender
+            .try_send((task.difficulty, duration_secs));
+
+        // Send information about completing the task
         self.event_sender
-            .send_event(Event::state_change(
-                ProverState::Waiting,
-                "Task completed, ready for next task".to_string(),
-            ))
+            .send_proof_event(
+                format!(
+                    "{} completed, Task size: {}, Duration: {}s (verification {:.1}s), Difficulty: {}",
+                    task.task_id,
+                    task.public_inputs_list.len(),
+                    duration_secs,
+                    proof_result.verification_time.as_secs_f64(),
+                    task.difficulty.as_str_name()
+                ),
+                EventType::Success,
+                LogLevel::Info,
+            )
             .await;
 
-        false // Continue with more tasks
+        // Check if we've reached the maximum number of tasks
+        if let Some(max) = self.max_tasks {
+            if self.tasks_completed >= max {
+                // Give a brief moment for the "Step 4 of 4" message to be processed
+                // before triggering shutdown
+                tokio::time::sleep(Duration::from_millis(100)).await;
+
+                self.event_sender
+                    .send_event(Event::state_change(
+                        ProverState::Waiting,
+                        format!("Completed {} tasks, shutting down", self.tasks_completed),
+                    ))
+                    .await;
+
+                // Stop the fetch stage; the prove stage follows once its queue closes
+                if let Some(budget) = &self.task_budget {
+                    budget.close();
+                }
+
+                // Send shutdown signal to trigger application exit
+                let _ = self.shutdown_sender.send(());
+                return false;
+            }
+        }
+
+        true
     }
 }
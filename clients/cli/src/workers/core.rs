@@ -2,22 +2,41 @@
 
 use crate::events::{Event, EventType};
 use crate::logging::LogLevel;
-use tokio::sync::mpsc;
+use std::sync::Arc;
+use tokio::sync::{Semaphore, mpsc};
+use tokio_util::sync::CancellationToken;
 
 /// Common event sending utilities for workers
 #[derive(Clone)]
 pub struct EventSender {
     sender: mpsc::Sender<Event>,
+    node_id: Option<u64>,
 }
 
 impl EventSender {
     pub fn new(sender: mpsc::Sender<Event>) -> Self {
-        Self { sender }
+        Self {
+            sender,
+            node_id: None,
+        }
+    }
+
+    /// Attribute all events sent through this sender to a node
+    pub fn for_node(mut self, node_id: u64) -> Self {
+        self.node_id = Some(node_id);
+        self
+    }
+
+    fn tag(&self, event: Event) -> Event {
+        match self.node_id {
+            Some(node_id) => event.with_node_id(node_id),
+            None => event,
+        }
     }
 
     /// Send a generic event
     pub async fn send_event(&self, event: Event) {
-        let _ = self.sender.send(event).await;
+        let _ = self.sender.send(self.tag(event)).await;
     }
 
     pub async fn send_task_event(
@@ -28,9 +47,9 @@ impl EventSender {
     ) {
         let _ = self
             .sender
-            .send(Event::task_fetcher_with_level(
+            .send(self.tag(Event::task_fetcher_with_level(
                 message, event_type, log_level,
-            ))
+            )))
             .await;
     }
 
@@ -42,12 +61,27 @@ impl EventSender {
     ) {
         let _ = self
             .sender
-            .send(Event::proof_submitter_with_level(
+            .send(self.tag(Event::proof_submitter_with_level(
                 message, event_type, log_level,
-            ))
+            )))
             .await;
     }
 
+    /// Send a proof submitter event without waiting. The event is dropped if the queue is full,
+    /// so this is only for events that may be missed, like progress updates.
+    pub fn try_send_proof_event(
+        &self,
+        message: String,
+        event_type: EventType,
+        log_level: LogLevel,
+    ) {
+        let _ = self
+            .sender
+            .try_send(self.tag(Event::proof_submitter_with_level(
+                message, event_type, log_level,
+            )));
+    }
+
     pub async fn send_prover_event(
         &self,
         thread_id: usize,
@@ -57,9 +91,9 @@ impl EventSender {
     ) {
         let _ = self
             .sender
-            .send(Event::prover_with_level(
+            .send(self.tag(Event::prover_with_level(
                 thread_id, message, event_type, log_level,
-            ))
+            )))
             .await;
     }
 }
@@ -70,7 +104,21 @@ pub struct WorkerConfig {
     pub environment: crate::environment::Environment,
     pub client_id: String,
     pub max_difficulty: Option<crate::nexus_orchestrator::TaskDifficulty>,
+    /// Difficulty to request before any task has completed, recommended by `benchmark`
+    pub initial_difficulty: Option<crate::nexus_orchestrator::TaskDifficulty>,
     pub num_workers: usize,
+    /// Directory for spooled proof submissions; spooling is disabled when unset
+    pub spool_dir: Option<std::path::PathBuf>,
+    /// Proving slots shared by every node in the session
+    pub proving_permits: Arc<Semaphore>,
+    /// Retry policies for fetching tasks and submitting proofs
+    pub retry_policies: crate::network::RetryPolicies,
+    /// Circuit breaker shared by every fetcher and submitter in the session
+    pub circuit_breaker: Arc<crate::network::CircuitBreaker>,
+    /// Prover subprocesses, caches and verifiers shared by every node in the session
+    pub proving: crate::prover::ProvingContext,
+    /// Cancelled on shutdown, stopping in-flight proving and killing its subprocesses
+    pub cancellation: CancellationToken,
 }
 
 impl WorkerConfig {
@@ -79,7 +127,16 @@ impl WorkerConfig {
             environment,
             client_id,
             max_difficulty: None,
+            initial_difficulty: None,
             num_workers: 1,
+            spool_dir: None,
+            proving_permits: Arc::new(Semaphore::new(1)),
+            retry_policies: crate::network::RetryPolicies::default(),
+            circuit_breaker: Arc::new(crate::network::CircuitBreaker::new(
+                crate::network::CircuitBreakerConfig::default(),
+            )),
+            proving: crate::prover::ProvingContext::default(),
+            cancellation: CancellationToken::new(),
         }
     }
 }
@@ -29,7 +29,6 @@ pub struct TaskFetcher {
     config: WorkerConfig,
     pub last_success_duration_secs: Option<u64>,
     pub last_success_difficulty: Option<crate::nexus_orchestrator::TaskDifficulty>,
-    last_requested_difficulty: Option<crate::nexus_orchestrator::TaskDifficulty>,
 }
 
 impl TaskFetcher {
@@ -50,7 +49,8 @@ impl TaskFetcher {
         let request_timer = RequestTimer::new(timer_config);
 
         // Create network client with retry logic
-        let network_client = NetworkClient::new(request_timer, task_fetching::MAX_RETRIES);
+        let network_client = NetworkClient::new(request_timer, config.retry_policies.fetch.clone())
+            .with_circuit_breaker(config.circuit_breaker.clone());
 
         Self {
             node_id,
@@ -61,7 +61,6 @@ impl TaskFetcher {
             config: config.clone(),
             last_success_duration_secs: None,
             last_success_difficulty: None,
-            last_requested_difficulty: None,
         }
     }
 
@@ -105,7 +104,7 @@ impl TaskFetcher {
             override_diff
         } else {
             // Adaptive difficulty system:
-            // - Starts at SmallMedium by default
+            // - Starts at the difficulty recommended by `benchmark`, or SmallMedium by default
             // - Promotes if previous task completed in < PROMOTION_THRESHOLD_SECS
             // - Small difficulty does not auto-promote (manual override only)
             if let Some(current) = self.last_success_difficulty {
@@ -150,8 +149,10 @@ impl TaskFetcher {
                     current
                 }
             } else {
-                // No previous success - start at SmallMedium
-                crate::nexus_orchestrator::TaskDifficulty::SmallMedium
+                // No previous success - start at the benchmarked difficulty or SmallMedium
+                self.config
+                    .initial_difficulty
+                    .unwrap_or(crate::nexus_orchestrator::TaskDifficulty::SmallMedium)
             }
         };
 
@@ -184,10 +185,19 @@ impl TaskFetcher {
                         .await;
                 }
 
-                // Log successful fetch
+                // Log successful fetch, noting inputs checkpointed before a restart
+                let task = &proof_task_result.task;
+                let resumed = match self.config.proving.checkpoints.proven_inputs(&task.task_id) {
+                    0 => String::new(),
+                    proven => format!(
+                        " (resumed, {} of {} inputs already proven)",
+                        proven,
+                        task.all_inputs().len()
+                    ),
+                };
                 self.event_sender
                     .send_task_event(
-                        format!("Step 1 of 4: Got task {}", proof_task_result.task.task_id),
+                        format!("Step 1 of 4: Got task {}{}", task.task_id, resumed),
                         EventType::Success,
                         LogLevel::Info,
                     )
@@ -200,9 +210,6 @@ impl TaskFetcher {
                     self.config.client_id.clone(),
                 ));
 
-                // Store the actual difficulty received from server for success tracking
-                self.last_requested_difficulty = Some(proof_task_result.actual_difficulty);
-
                 Ok(proof_task_result.task)
             }
             Err(e) => {
@@ -221,13 +228,21 @@ impl TaskFetcher {
         }
     }
 
-    /// Update success tracking after completing a task
-    /// Uses the actual difficulty received from the server
-    pub fn update_success_tracking(&mut self, duration_secs: u64) {
-        if let Some(difficulty) = self.last_requested_difficulty {
-            self.last_success_difficulty = Some(difficulty);
-            self.last_success_duration_secs = Some(duration_secs);
-        }
+    /// Time until the orchestrator may be probed again, while it is considered unavailable
+    pub fn unavailable_for(&self) -> Option<Duration> {
+        self.network_client.unavailable_for()
+    }
+
+    /// Record a completed task of a known difficulty.
+    /// The caller passes the difficulty because, with prefetching, the task fetched last may
+    /// already be a later one rather than the one that just completed.
+    pub fn record_success(
+        &mut self,
+        difficulty: crate::nexus_orchestrator::TaskDifficulty,
+        duration_secs: u64,
+    ) {
+        self.last_success_difficulty = Some(difficulty);
+        self.last_success_duration_secs = Some(duration_secs);
     }
 }
 
@@ -265,7 +280,7 @@ mod tests {
                 public_inputs: vec![1, 2, 3],
                 public_inputs_list: vec![vec![1, 2, 3]],
                 task_type: crate::nexus_orchestrator::TaskType::ProofHash,
-                difficulty: crate::nexus_orchestrator::TaskDifficulty::Medium,
+                difficulty: max_difficulty,
             };
 
             Ok(crate::orchestrator::client::ProofTaskResult {
@@ -282,6 +297,13 @@ mod tests {
             Ok("test_user".to_string())
         }
 
+        async fn get_user_page(
+            &self,
+            _wallet_address: &str,
+        ) -> Result<crate::orchestrator::client::UserPage, OrchestratorError> {
+            Ok(Default::default())
+        }
+
         async fn register_user(
             &self,
             _user_id: &str,
@@ -339,13 +361,28 @@ mod tests {
             .expect("fetcher.fetch_task failed");
         assert_eq!(task.task_id, "test_task");
 
-        // Verify the last requested difficulty was SmallMedium
+        // Verify the task was requested at SmallMedium
         assert_eq!(
-            fetcher.last_requested_difficulty,
+            task.difficulty,
             Some(crate::nexus_orchestrator::TaskDifficulty::SmallMedium)
         );
     }
 
+    #[tokio::test]
+    async fn test_starts_at_benchmarked_difficulty() {
+        let mut fetcher = create_test_fetcher();
+        fetcher.config.initial_difficulty = Some(crate::nexus_orchestrator::TaskDifficulty::Large);
+
+        let task = fetcher
+            .fetch_task()
+            .await
+            .expect("fetcher.fetch_task failed");
+        assert_eq!(
+            task.difficulty,
+            Some(crate::nexus_orchestrator::TaskDifficulty::Large)
+        );
+    }
+
     #[tokio::test]
     async fn test_small_promotes_to_small_medium() {
         let mut fetcher = create_test_fetcher();
@@ -362,7 +399,7 @@ mod tests {
 
         // Should promote from Small to SmallMedium
         assert_eq!(
-            fetcher.last_requested_difficulty,
+            task.difficulty,
             Some(crate::nexus_orchestrator::TaskDifficulty::SmallMedium)
         );
     }
@@ -384,7 +421,7 @@ mod tests {
 
         // Should promote from SmallMedium to Medium
         assert_eq!(
-            fetcher.last_requested_difficulty,
+            task.difficulty,
             Some(crate::nexus_orchestrator::TaskDifficulty::Medium)
         );
     }
@@ -405,7 +442,7 @@ mod tests {
 
         // Should promote from Medium to Large
         assert_eq!(
-            fetcher.last_requested_difficulty,
+            task.difficulty,
             Some(crate::nexus_orchestrator::TaskDifficulty::Large)
         );
     }
@@ -426,7 +463,7 @@ mod tests {
 
         // Should promote from Large to ExtraLarge
         assert_eq!(
-            fetcher.last_requested_difficulty,
+            task.difficulty,
             Some(crate::nexus_orchestrator::TaskDifficulty::ExtraLarge)
         );
     }
@@ -447,7 +484,7 @@ mod tests {
 
         // Should NOT promote (stays at Medium)
         assert_eq!(
-            fetcher.last_requested_difficulty,
+            task.difficulty,
             Some(crate::nexus_orchestrator::TaskDifficulty::Medium)
         );
     }
@@ -467,7 +504,7 @@ mod tests {
 
         // Should use the manual override (ExtraLarge)
         assert_eq!(
-            fetcher.last_requested_difficulty,
+            task.difficulty,
             Some(crate::nexus_orchestrator::TaskDifficulty::ExtraLarge)
         );
     }
@@ -487,7 +524,7 @@ mod tests {
 
         // Should use the manual override (Small)
         assert_eq!(
-            fetcher.last_requested_difficulty,
+            task.difficulty,
             Some(crate::nexus_orchestrator::TaskDifficulty::Small)
         );
     }
@@ -500,11 +537,8 @@ mod tests {
         assert_eq!(fetcher.last_success_difficulty, None);
         assert_eq!(fetcher.last_success_duration_secs, None);
 
-        // Set a requested difficulty
-        fetcher.last_requested_difficulty = Some(crate::nexus_orchestrator::TaskDifficulty::Medium);
-
-        // Update success tracking
-        fetcher.update_success_tracking(300); // 5 minutes
+        // Record a completed task
+        fetcher.record_success(crate::nexus_orchestrator::TaskDifficulty::Medium, 300); // 5 minutes
 
         // Verify tracking was updated
         assert_eq!(
@@ -514,21 +548,6 @@ mod tests {
         assert_eq!(fetcher.last_success_duration_secs, Some(300));
     }
 
-    #[tokio::test]
-    async fn test_success_tracking_without_requested_difficulty() {
-        let mut fetcher = create_test_fetcher();
-
-        // No requested difficulty set
-        fetcher.last_requested_difficulty = None;
-
-        // Update success tracking
-        fetcher.update_success_tracking(300);
-
-        // Should not update tracking when no requested difficulty
-        assert_eq!(fetcher.last_success_difficulty, None);
-        assert_eq!(fetcher.last_success_duration_secs, None);
-    }
-
     #[tokio::test]
     async fn test_extra_large_promotes_to_extra_large2() {
         let mut fetcher = create_test_fetcher();
@@ -546,7 +565,7 @@ mod tests {
 
         // Should promote from ExtraLarge to ExtraLarge2
         assert_eq!(
-            fetcher.last_requested_difficulty,
+            task.difficulty,
             Some(crate::nexus_orchestrator::TaskDifficulty::ExtraLarge2)
         );
     }
@@ -565,7 +584,7 @@ mod tests {
 
         // Should promote from ExtraLarge2 to ExtraLarge3
         assert_eq!(
-            fetcher.last_requested_difficulty,
+            task.difficulty,
             Some(crate::nexus_orchestrator::TaskDifficulty::ExtraLarge3)
         );
     }
@@ -584,7 +603,7 @@ mod tests {
 
         // Should stay at ExtraLarge5 (maximum difficulty reached)
         assert_eq!(
-            fetcher.last_requested_difficulty,
+            task.difficulty,
             Some(crate::nexus_orchestrator::TaskDifficulty::ExtraLarge5)
         );
     }
@@ -605,7 +624,7 @@ mod tests {
 
         // Should NOT promote (stays at Medium)
         assert_eq!(
-            fetcher.last_requested_difficulty,
+            task.difficulty,
             Some(crate::nexus_orchestrator::TaskDifficulty::Medium)
         );
     }
@@ -626,7 +645,7 @@ mod tests {
 
         // Should promote from Medium to Large
         assert_eq!(
-            fetcher.last_requested_difficulty,
+            task.difficulty,
             Some(crate::nexus_orchestrator::TaskDifficulty::Large)
         );
     }
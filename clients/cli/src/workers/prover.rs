@@ -32,47 +32,79 @@ impl TaskProver {
     pub async fn prove_task(&self, task: &Task) -> Result<ProverResult, ProveError> {
         // Use existing prover module for proof generation
         match authenticated_proving(
+            &self.config.proving,
             task,
             &self.config.environment,
             &self.config.client_id,
-            self.config.num_workers,
+            &self.config.proving_permits,
+            &self.config.cancellation,
         )
         .await
         {
-            Ok((proofs, combined_hash, individual_proof_hashes)) => {
+            Ok(proof_result) => {
                 // Log successful proof generation
                 self.event_sender
                     .send_prover_event(
                         self.config.num_workers, // Use num_workers as thread identifier for multi-threaded prover
                         format!(
-                            "Step 3 of 4: Proof generated for task {} (using {} workers)",
-                            task.task_id, self.config.num_workers
+                            "Step 3 of 4: Proof generated for task {} (using {} workers, verification {:.1}s)",
+                            task.task_id,
+                            self.config.num_workers,
+                            proof_result.verification_time.as_secs_f64()
                         ),
                         EventType::Success,
                         LogLevel::Info,
                     )
                     .await;
 
+                let cache = &self.config.proving.proof_cache;
+                if cache.is_enabled() {
+                    let stats = cache.stats();
+                    self.event_sender
+                        .send_prover_event(
+                            self.config.num_workers,
+                            format!(
+                                "Proof cache: {} hits, {} misses since start",
+                                stats.hits, stats.misses
+                            ),
+                            EventType::Refresh,
+                            LogLevel::Debug,
+                        )
+                        .await;
+                }
+
                 tokio::spawn(track_authenticated_proof_analytics(
                     task.clone(),
                     self.config.environment.clone(),
                     self.config.client_id.clone(),
                 ));
 
-                Ok(ProverResult {
-                    proofs,
-                    combined_hash,
-                    individual_proof_hashes,
-                })
+                Ok(proof_result)
             }
             Err(e) => {
+                // The task is dropped, so its checkpoints are of no further use. A cancelled
+                // task keeps them, since it is resumed after a restart.
+                if !matches!(e, ProverError::Cancelled) {
+                    let _ = self.config.proving.checkpoints.remove(&task.task_id);
+                }
+
+                // Resource limit breaches suggest smaller tasks rather than a retry
+                let hint = match &e {
+                    ProverError::OutOfMemory(_) => {
+                        " Try a lower --max-difficulty or fewer --max-threads."
+                    }
+                    ProverError::TimedOut(_) | ProverError::CpuTimeExceeded(_) => {
+                        " Try a lower --max-difficulty or raise prover_limits in the config file."
+                    }
+                    _ => "",
+                };
                 // Log proof generation failure
                 self.event_sender
                     .send_prover_event(
                         self.config.num_workers, // Use num_workers as thread identifier for multi-threaded prover
                         format!(
-                            "Proof generation failed for task {} (using {} workers): {}",
-                            task.task_id, self.config.num_workers, e
+                            "Proof generation failed for task {} (using {} workers): {}{}",
+                            task.task_id, self.config.num_workers, e, hint
                         ),
                         EventType::Error,
                         LogLevel::Error,
@@ -7,8 +7,12 @@ use crate::analytics::{
 use crate::consts::cli_consts::{proof_submission, rate_limiting};
 use crate::events::EventType;
 use crate::logging::LogLevel;
-use crate::network::{NetworkClient, ProofSubmission, RequestTimer, RequestTimerConfig};
+use crate::network::upload::{self, UploadProgress};
+use crate::network::{
+    NetworkClient, ProofSubmission, RequestTimer, RequestTimerConfig, Spool, SpoolEntry,
+};
 use crate::orchestrator::Orchestrator;
+use crate::orchestrator::error::{OrchestratorError, Remedy};
 use crate::prover::ProverResult;
 use crate::task::Task;
 use ed25519_dalek::SigningKey;
@@ -17,22 +21,25 @@ use thiserror::Error;
 #[derive(Error, Debug)]
 pub enum SubmitError {
     #[error("Network error: {0}")]
-    Network(#[from] crate::orchestrator::error::OrchestratorError),
+    Network(#[from] OrchestratorError),
     #[error("Serialization error: {0}")]
     Serialization(#[from] postcard::Error),
 }
 
 /// Proof submitter with built-in retry and error handling
 pub struct ProofSubmitter {
+    node_id: u64,
     signing_key: SigningKey,
     orchestrator: Box<dyn Orchestrator>,
     network_client: NetworkClient,
     event_sender: EventSender,
     config: WorkerConfig,
+    spool: Option<Spool>,
 }
 
 impl ProofSubmitter {
     pub fn new(
+        node_id: u64,
         signing_key: SigningKey,
         orchestrator: Box<dyn Orchestrator>,
         event_sender: EventSender,
@@ -48,14 +55,18 @@ impl ProofSubmitter {
         let request_timer = RequestTimer::new(timer_config);
 
         // Create network client with more retries for critical submissions
-        let network_client = NetworkClient::new(request_timer, proof_submission::MAX_RETRIES);
+        let network_client =
+            NetworkClient::new(request_timer, config.retry_policies.submit.clone())
+                .with_circuit_breaker(config.circuit_breaker.clone());
 
         Self {
+            node_id,
             signing_key,
             orchestrator,
             network_client,
             event_sender,
             config: config.clone(),
+            spool: config.spool_dir.clone().map(Spool::new),
         }
     }
 
@@ -68,7 +79,7 @@ impl ProofSubmitter {
         // Log start of submission
         self.event_sender
             .send_proof_event(
-                format!("Step 3 of 4: Submitting proof for task {}...", task.task_id),
+                format!("Step 4 of 4: Submitting proof for task {}...", task.task_id),
                 EventType::StateChange,
                 LogLevel::Info,
             )
@@ -101,17 +112,35 @@ impl ProofSubmitter {
             submission = submission.with_proofs(proofs_bytes);
         }
 
-        match self
-            .network_client
-            .submit_proof(
-                self.orchestrator.as_ref(),
-                submission,
-                self.signing_key.clone(),
-                1, // num_provers (single worker)
-            )
-            .await
-        {
+        // Write-ahead to the spool so the proof survives exhausted retries or a restart
+        self.spool_submission(&submission).await;
+
+        // Report how far large uploads have got, since they can take minutes on slow uplinks
+        let progress_sender = self.event_sender.clone();
+        let task_id = task.task_id.clone();
+        let on_progress = move |progress: UploadProgress| {
+            progress_sender.try_send_proof_event(
+                format!(
+                    "Uploading proof for task {}: {}% of {:.1} MB",
+                    task_id,
+                    progress.percent(),
+                    progress.total as f64 / 1_000_000.0
+                ),
+                EventType::Refresh,
+                LogLevel::Info,
+            );
+        };
+
+        let submit = self.network_client.submit_proof(
+            self.orchestrator.as_ref(),
+            submission,
+            self.signing_key.clone(),
+            1, // num_provers (single worker)
+        );
+        match upload::with_progress(on_progress, submit).await {
             Ok(attempts) => {
+                self.unspool(&task.task_id).await;
+
                 // Log successful submission with attempt count
                 let attempt_text = if attempts == 1 {
                     "".to_string()
@@ -151,6 +180,8 @@ impl ProofSubmitter {
                     )
                     .await;
 
+                self.settle_failed_submission(&task.task_id, &e).await;
+
                 // Track analytics for submission error
                 tokio::spawn(track_proof_submission_error(
                     task.clone(),
@@ -165,6 +196,182 @@ impl ProofSubmitter {
         }
     }
 
+    /// Submit proofs left in the spool by earlier runs of this node, or by submissions that
+    /// failed while the orchestrator was unavailable.
+    /// Entries for other nodes or orchestrators are left for `spool flush`.
+    pub async fn replay_spool(&mut self) {
+        let Some(spool) = self.spool.clone() else {
+            return;
+        };
+
+        let entries = match spool.load() {
+            Ok((entries, unreadable)) => {
+                for path in unreadable {
+                    self.event_sender
+                        .send_proof_event(
+                            format!("Skipping unreadable spool entry {}", path.display()),
+                            EventType::Error,
+                            LogLevel::Warn,
+                        )
+                        .await;
+                }
+                entries
+            }
+            Err(e) => {
+                self.event_sender
+                    .send_proof_event(
+                        format!("Failed to read proof spool: {}", e),
+                        EventType::Error,
+                        LogLevel::Warn,
+                    )
+                    .await;
+                return;
+            }
+        };
+
+        let orchestrator_url = self.config.environment.orchestrator_url().to_string();
+        for entry in entries {
+            if entry.node_id != self.node_id || entry.orchestrator_url != orchestrator_url {
+                continue;
+            }
+
+            if entry.is_expired() {
+                self.unspool(&entry.task_id).await;
+                self.event_sender
+                    .send_proof_event(
+                        format!("Dropped expired spooled proof for task {}", entry.task_id),
+                        EventType::Error,
+                        LogLevel::Warn,
+                    )
+                    .await;
+                continue;
+            }
+
+            self.event_sender
+                .send_proof_event(
+                    format!("Replaying spooled proof for task {}...", entry.task_id),
+                    EventType::StateChange,
+                    LogLevel::Info,
+                )
+                .await;
+
+            match self
+                .network_client
+                .submit_proof(
+                    self.orchestrator.as_ref(),
+                    entry.submission(),
+                    self.signing_key.clone(),
+                    1,
+                )
+                .await
+            {
+                Ok(_) => {
+                    self.unspool(&entry.task_id).await;
+                    self.event_sender
+                        .send_proof_event(
+                            format!(
+                                "Step 4 of 4: Proof submitted successfully for task {} (from spool)\n",
+                                entry.task_id
+                            ),
+                            EventType::Success,
+                            LogLevel::Info,
+                        )
+                        .await;
+                }
+                Err((e, attempts)) => {
+                    let log_level = self.network_client.classify_error(&e);
+                    self.event_sender
+                        .send_proof_event(
+                            format!(
+                                "Failed to submit spooled proof for task {} after {} attempts: {}",
+                                entry.task_id, attempts, e
+                            ),
+                            EventType::Error,
+                            log_level,
+                        )
+                        .await;
+                    self.settle_failed_submission(&entry.task_id, &e).await;
+                }
+            }
+        }
+    }
+
+    /// Write a submission to the spool, if enabled
+    async fn spool_submission(&self, submission: &ProofSubmission) {
+        let Some(spool) = &self.spool else {
+            return;
+        };
+
+        let entry = SpoolEntry::new(
+            submission,
+            self.node_id,
+            self.config.environment.orchestrator_url(),
+        );
+        if let Err(e) = spool.store(&entry) {
+            self.event_sender
+                .send_proof_event(
+                    format!("Failed to spool proof for task {}: {}", entry.task_id, e),
+                    EventType::Error,
+                    LogLevel::Warn,
+                )
+                .await;
+        }
+    }
+
+    /// Remove an accepted (or unsalvageable) submission from the spool, if enabled
+    async fn unspool(&self, task_id: &str) {
+        let Some(spool) = &self.spool else {
+            return;
+        };
+
+        if let Err(e) = spool.remove(task_id) {
+            self.event_sender
+                .send_proof_event(
+                    format!("Failed to remove task {} from proof spool: {}", task_id, e),
+                    EventType::Error,
+                    LogLevel::Warn,
+                )
+                .await;
+        }
+    }
+
+    /// Keep a failed submission spooled for replay unless the orchestrator rejected it outright
+    async fn settle_failed_submission(&self, task_id: &str, error: &OrchestratorError) {
+        // A rejected task is never resumed, so its checkpoints are of no further use
+        if self.network_client.is_permanent_rejection(error) {
+            let _ = self.config.proving.checkpoints.remove(task_id);
+        }
+
+        if error.remedy() == Remedy::DropTask {
+            self.event_sender
+                .send_proof_event(
+                    format!(
+                        "Dropped proof for task {}, the orchestrator no longer accepts it",
+                        task_id
+                    ),
+                    EventType::Error,
+                    LogLevel::Warn,
+                )
+                .await;
+        }
+
+        if self.spool.is_none() {
+            return;
+        }
+
+        if self.network_client.is_permanent_rejection(error) {
+            self.unspool(task_id).await;
+        } else {
+            self.event_sender
+                .send_proof_event(
+                    format!("Proof for task {} kept in spool for replay", task_id),
+                    EventType::Waiting,
+                    LogLevel::Info,
+                )
+                .await;
+        }
+    }
+
     /// Track successful submission analytics based on task type
     async fn track_successful_submission(&self, task: &Task) {
         if task.task_type == crate::nexus_orchestrator::TaskType::ProofHash {
@@ -1,7 +1,11 @@
 use assert_cmd::Command;
 use predicates::str::contains;
 use std::fs;
+use std::net::{SocketAddr, TcpListener, TcpStream};
 use std::path::PathBuf;
+use std::process::{Child, Stdio};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
 
 /// Helper to get a temporary config directory
 fn temp_config_dir() -> tempfile::TempDir {
@@ -15,6 +19,48 @@ fn config_file_path(dir: &tempfile::TempDir) -> PathBuf {
 
 const BINARY_NAME: &str = "nexus-network";
 
+/// A `nexus-network dev-orchestrator` process, killed when dropped.
+struct DevOrchestrator {
+    child: Child,
+    url: String,
+}
+
+impl DevOrchestrator {
+    /// Spawn the local orchestrator on a free port and wait until it accepts connections.
+    fn spawn(tasks: usize) -> Self {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("find a free port")
+            .port();
+        let child = std::process::Command::new(env!("CARGO_BIN_EXE_nexus-network"))
+            .args(["dev-orchestrator", "--port", &port.to_string()])
+            .args(["--tasks", &tasks.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("spawn dev-orchestrator");
+
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while TcpStream::connect(addr).is_err() {
+            assert!(Instant::now() < deadline, "dev-orchestrator did not start");
+            sleep(Duration::from_millis(50));
+        }
+
+        Self {
+            child,
+            url: format!("http://127.0.0.1:{}", port),
+        }
+    }
+}
+
+impl Drop for DevOrchestrator {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
 #[test]
 /// Help command should display usage information.
 fn cli_help_displays_usage() {
@@ -26,8 +72,9 @@ fn cli_help_displays_usage() {
 }
 
 #[test]
-#[ignore] // This currently involves network calls and creating a config file.
+/// Registering a user against the local orchestrator should create the config file.
 fn register_user_command_creates_config_file() {
+    let orchestrator = DevOrchestrator::spawn(1);
     let tmp = temp_config_dir();
     let config_path = config_file_path(&tmp);
     fs::create_dir_all(config_path.parent().unwrap()).unwrap();
@@ -40,6 +87,8 @@ fn register_user_command_creates_config_file() {
     cmd.arg("register-user")
         .arg("--wallet-address")
         .arg("0x1234567890abcdef1234567890abcdef12345600")
+        .arg("--orchestrator-url")
+        .arg(&orchestrator.url)
         .env("HOME", tmp.path()) // simulate different $HOME
         .assert()
         .success()
@@ -49,6 +98,46 @@ fn register_user_command_creates_config_file() {
     assert!(config_path.exists());
 }
 
+#[test]
+/// A registered node should prove and submit tasks from the local orchestrator in headless mode.
+fn start_headless_proves_tasks_from_local_orchestrator() {
+    let orchestrator = DevOrchestrator::spawn(2);
+    let tmp = temp_config_dir();
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).unwrap();
+    cmd.args(["register-user", "--wallet-address"])
+        .arg("0x1234567890abcdef1234567890abcdef12345601")
+        .args(["--orchestrator-url", &orchestrator.url])
+        .env("HOME", tmp.path())
+        .assert()
+        .success();
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).unwrap();
+    cmd.arg("register-node")
+        .args(["--orchestrator-url", &orchestrator.url])
+        .env("HOME", tmp.path())
+        .assert()
+        .success()
+        .stdout(contains("Node registration complete!"));
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).unwrap();
+    cmd.args([
+        "start",
+        "--headless",
+        "--max-tasks",
+        "1",
+        "--max-threads",
+        "1",
+    ])
+    .args(["--orchestrator-url", &orchestrator.url])
+    .env("HOME", tmp.path())
+    .env_remove("NEXUS_KEY_PASSPHRASE")
+    .timeout(Duration::from_secs(600))
+    .assert()
+    .success()
+    .stdout(contains("Proof submitted successfully for task dev-task-"));
+}
+
 #[test]
 /// Logout command should delete an existing config file.
 fn logout_deletes_config_file() {
@@ -71,3 +160,138 @@ fn logout_deletes_config_file() {
     // Confirm the file was deleted
     assert!(!config_path.exists());
 }
+
+#[test]
+/// Spool purge should remove spooled proofs next to the config file.
+fn spool_purge_removes_entries() {
+    let tmp = temp_config_dir();
+    let spool_dir = tmp.path().join(".nexus").join("spool");
+    fs::create_dir_all(&spool_dir).unwrap();
+    fs::write(spool_dir.join("task-1.spool"), "stale").unwrap();
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).unwrap();
+    cmd.arg("spool")
+        .arg("purge")
+        .env("HOME", tmp.path()) // simulate different $HOME
+        .assert()
+        .success()
+        .stdout(contains("1 entries removed"));
+
+    assert!(!spool_dir.join("task-1.spool").exists());
+}
+
+#[test]
+/// A rotated key should be stored next to the config file and listed by `keys show`.
+fn keys_rotate_then_show() {
+    let tmp = temp_config_dir();
+    let key_path = tmp.path().join(".nexus").join("keys").join("7.json");
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).unwrap();
+    cmd.args(["keys", "rotate", "--node-id", "7"])
+        .env("HOME", tmp.path()) // simulate different $HOME
+        .env_remove("NEXUS_KEY_PASSPHRASE")
+        .assert()
+        .success()
+        .stdout(contains("Rotated signing key for node 7"));
+    assert!(key_path.exists());
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).unwrap();
+    cmd.args(["keys", "show"])
+        .env("HOME", tmp.path())
+        .assert()
+        .success()
+        .stdout(contains("Node 7"));
+}
+
+#[test]
+/// Invalid options should be rejected before the command runs, with an error naming the problem.
+/// Replaying a directory without recordings fails instead of falling back to the network.
+fn invalid_options_are_rejected() {
+    let tmp = temp_config_dir();
+    let recordings = tmp.path().join("traffic");
+    fs::create_dir_all(&recordings).unwrap();
+    let recordings = recordings.to_str().unwrap();
+
+    let cases: &[(&[&str], &str)] = &[
+        (
+            &["--proxy", "ftp://proxy.internal", "spool", "list"],
+            "Invalid proxy URL",
+        ),
+        (
+            &["--replay-traffic", recordings, "spool", "list"],
+            "No recorded traffic",
+        ),
+        (
+            &["--compress", "brotli", "spool", "list"],
+            "expected none, gzip or zstd",
+        ),
+        (
+            &["--verification", "sometimes", "logout"],
+            "unknown verification policy 'sometimes'",
+        ),
+        (
+            &["benchmark", "--max-difficulty", "enormous"],
+            "Invalid difficulty level 'enormous'",
+        ),
+    ];
+    for (args, error) in cases {
+        let mut cmd = Command::cargo_bin(BINARY_NAME).unwrap();
+        cmd.args(*args)
+            .env("HOME", tmp.path())
+            .assert()
+            .failure()
+            .stderr(contains(*error));
+    }
+}
+
+#[test]
+/// Node inventory commands need a registered wallet and fail before any request without one.
+fn whoami_requires_registered_user() {
+    let tmp = temp_config_dir();
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).unwrap();
+    cmd.arg("whoami")
+        .env("HOME", tmp.path())
+        .assert()
+        .failure()
+        .stderr(contains("No user registered"));
+}
+
+#[test]
+/// A tampered ELF in the program cache should fail `programs verify`.
+fn programs_verify_rejects_tampered_elf() {
+    let tmp = temp_config_dir();
+    let elf_dir = tmp
+        .path()
+        .join(".nexus")
+        .join("programs")
+        .join("fib_input_initial");
+    fs::create_dir_all(&elf_dir).unwrap();
+    fs::write(
+        elf_dir.join("69f1c40224ec9627ca950b4208f46da44d169900a2cb9916eddd0c1de9a4a423.elf"),
+        "tampered",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).unwrap();
+    cmd.args(["programs", "verify"])
+        .env("HOME", tmp.path())
+        .assert()
+        .failure()
+        .stderr(contains("1 cached ELFs failed verification"));
+}
+
+#[test]
+/// A file that is not a proof should be rejected by `inspect-proof`.
+fn inspect_proof_rejects_non_proof_file() {
+    let tmp = temp_config_dir();
+    let proof = tmp.path().join("proof.bin");
+    fs::write(&proof, [0xff; 3]).unwrap();
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).unwrap();
+    cmd.args(["inspect-proof", proof.to_str().unwrap()])
+        .env("HOME", tmp.path())
+        .assert()
+        .failure()
+        .stderr(contains("is not a postcard-encoded proof"));
+}